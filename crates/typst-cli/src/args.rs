@@ -90,6 +90,10 @@ pub enum Command {
     /// Initializes a new project from a template.
     Init(InitCommand),
 
+    /// Manages downloaded and local packages.
+    #[command(subcommand)]
+    Package(PackageCommand),
+
     /// Processes an input file to extract provided metadata (deprecated, use `eval` instead).
     #[command(hide = true)]
     Query(QueryCommand),
@@ -97,6 +101,16 @@ pub enum Command {
     /// Evaluates a piece of Typst code, optionally in the context of a document.
     Eval(EvalCommand),
 
+    /// Runs a debug adapter that steps through the evaluation of a document.
+    Debug(DebugCommand),
+
+    /// Runs the tests of a package or template.
+    Test(TestCommand),
+
+    /// Copies all packages a document depends on into the project's `vendor`
+    /// directory.
+    Vendor(VendorCommand),
+
     /// Lists all discovered fonts in system and custom font paths.
     Fonts(FontsCommand),
 
@@ -151,6 +165,120 @@ pub struct InitCommand {
     pub package: PackageArgs,
 }
 
+/// Manages downloaded and local packages.
+#[derive(Debug, Clone, Subcommand)]
+pub enum PackageCommand {
+    /// Lists the packages in the package data and cache directories.
+    List(PackageListCommand),
+
+    /// Shows the manifest of a package.
+    Info(PackageInfoCommand),
+
+    /// Removes a package from the package cache directory.
+    Remove(PackageRemoveCommand),
+
+    /// Removes outdated versions of packages from the package cache directory.
+    Prune(PackagePruneCommand),
+
+    /// Reports package imports in a project for which newer versions exist.
+    Upgrade(PackageUpgradeCommand),
+
+    /// Checks a package for problems before publishing it.
+    Check(PackageCheckCommand),
+}
+
+/// Lists the packages in the package data and cache directories.
+#[derive(Debug, Clone, Parser)]
+pub struct PackageListCommand {
+    /// Arguments related to storage of packages in the system.
+    #[clap(flatten)]
+    pub package: PackageArgs,
+}
+
+/// Shows the manifest of a package.
+#[derive(Debug, Clone, Parser)]
+pub struct PackageInfoCommand {
+    /// The package, e.g. `@preview/cetz`.
+    ///
+    /// You can specify the version by appending e.g. `:0.1.0`. If no version is
+    /// specified, Typst will default to the latest version. The package is
+    /// downloaded if it's not yet available locally.
+    pub spec: String,
+
+    /// Arguments related to storage of packages in the system.
+    #[clap(flatten)]
+    pub package: PackageArgs,
+}
+
+/// Removes a package from the package cache directory.
+#[derive(Debug, Clone, Parser)]
+pub struct PackageRemoveCommand {
+    /// The package to remove, e.g. `@preview/cetz:0.1.0`.
+    ///
+    /// If no version is specified, all versions of the package are removed.
+    pub spec: String,
+
+    /// Also removes the package from the package data directory.
+    #[clap(long)]
+    pub data: bool,
+
+    /// Arguments related to storage of packages in the system.
+    #[clap(flatten)]
+    pub package: PackageArgs,
+}
+
+/// Removes outdated versions of packages from the package cache directory.
+#[derive(Debug, Clone, Parser)]
+pub struct PackagePruneCommand {
+    /// Removes all packages instead of only those for which a newer version is
+    /// cached.
+    #[clap(long)]
+    pub all: bool,
+
+    /// Arguments related to storage of packages in the system.
+    #[clap(flatten)]
+    pub package: PackageArgs,
+}
+
+/// Reports package imports in a project for which newer versions exist.
+#[derive(Debug, Clone, Parser)]
+pub struct PackageUpgradeCommand {
+    /// The project directory, defaults to the working directory.
+    #[clap(value_hint = ValueHint::DirPath)]
+    pub dir: Option<PathBuf>,
+
+    /// Rewrites outdated imports to the latest versions.
+    #[clap(long)]
+    pub write: bool,
+
+    /// Arguments related to storage of packages in the system.
+    #[clap(flatten)]
+    pub package: PackageArgs,
+}
+
+/// Checks a package for problems before publishing it.
+///
+/// Validates the package manifest, inspects the files that end up in the
+/// published bundle, and compiles the package's entrypoint and template.
+#[derive(Debug, Clone, Parser)]
+pub struct PackageCheckCommand {
+    /// The package directory, defaults to the working directory.
+    #[clap(value_hint = ValueHint::DirPath)]
+    pub dir: Option<PathBuf>,
+
+    /// Common font arguments.
+    #[clap(flatten)]
+    pub font: FontArgs,
+
+    /// Arguments related to storage of packages in the system.
+    #[clap(flatten)]
+    pub package: PackageArgs,
+
+    /// Processing arguments.
+    #[clap(flatten)]
+    pub process: ProcessArgs,
+}
+
 /// Processes an input file to extract provided metadata (deprecated, use `eval` instead).
 #[derive(Debug, Clone, Parser)]
 pub struct QueryCommand {
@@ -192,6 +320,30 @@ pub struct QueryCommand {
     pub process: ProcessArgs,
 }
 
+/// Copies all packages a document depends on into the project's `vendor`
+/// directory.
+///
+/// Packages in the `vendor` directory take precedence over all other package
+/// sources, so that the project can be compiled without network access.
+#[derive(Debug, Clone, Parser)]
+pub struct VendorCommand {
+    /// Path to input Typst file. Use `-` to read input from stdin.
+    #[clap(value_parser = input_value_parser(), value_hint = ValueHint::FilePath)]
+    pub input: Input,
+
+    /// The target to compile for.
+    #[clap(long, default_value_t)]
+    pub target: Target,
+
+    /// World arguments.
+    #[clap(flatten)]
+    pub world: WorldArgs,
+
+    /// Processing arguments.
+    #[clap(flatten)]
+    pub process: ProcessArgs,
+}
+
 /// Evaluates a piece of Typst code, optionally in the context of a document.
 #[derive(Debug, Clone, Parser)]
 pub struct EvalCommand {
@@ -226,6 +378,62 @@ pub struct EvalCommand {
     pub process: ProcessArgs,
 }
 
+/// Runs a debug adapter that steps through the evaluation of a document.
+///
+/// The adapter speaks the Debug Adapter Protocol over stdin and stdout.
+#[derive(Debug, Clone, Parser)]
+pub struct DebugCommand {
+    /// Path to input Typst file.
+    #[clap(value_hint = ValueHint::FilePath)]
+    pub input: PathBuf,
+
+    /// The target to compile for.
+    #[clap(long, default_value_t)]
+    pub target: Target,
+
+    /// World arguments.
+    #[clap(flatten)]
+    pub world: WorldArgs,
+
+    /// Processing arguments.
+    #[clap(flatten)]
+    pub process: ProcessArgs,
+}
+
+/// Runs the tests of a package or template.
+///
+/// Every Typst file in the `tests` directory of the project root is a test,
+/// except for files whose name starts with an underscore. A test passes if it
+/// compiles without errors (including failed assertions) and its rendered pages
+/// match the reference image stored next to it. Tests without visible output
+/// need no reference image.
+#[derive(Debug, Clone, Parser)]
+pub struct TestCommand {
+    /// Only runs tests whose name contains one of the given strings.
+    pub filter: Vec<String>,
+
+    /// Creates, updates, or removes reference images to match the current
+    /// output instead of comparing against them.
+    #[arg(long)]
+    pub update: bool,
+
+    /// The format of the reference images.
+    #[arg(long = "format", default_value_t)]
+    pub format: ReferenceFormat,
+
+    /// The PPI (pixels per inch) to use for PNG references.
+    #[arg(long = "ppi", default_value_t = 144.0)]
+    pub ppi: f32,
+
+    /// World arguments.
+    #[clap(flatten)]
+    pub world: WorldArgs,
+
+    /// Processing arguments.
+    #[clap(flatten)]
+    pub process: ProcessArgs,
+}
+
 /// Lists all discovered fonts in system and custom font paths.
 #[derive(Debug, Clone, Parser)]
 pub struct FontsCommand {
@@ -236,6 +444,24 @@ pub struct FontsCommand {
     /// Also lists style variants of each font family.
     #[arg(long)]
     pub variants: bool,
+
+    /// Only lists fonts that cover all characters of the given text, ignoring
+    /// whitespace.
+    #[arg(long, value_name = "TEXT")]
+    pub covers: Option<String>,
+
+    /// The format to serialize in, if it should be machine-readable.
+    ///
+    /// The serialized output always includes all variants along with their
+    /// flags and Unicode coverage.
+    #[arg(long = "format", short = 'f')]
+    pub format: Option<SerializationFormat>,
+
+    /// Whether to pretty-print the serialized output.
+    ///
+    /// Only applies to JSON format.
+    #[clap(long)]
+    pub pretty: bool,
 }
 
 /// Update the CLI using a pre-compiled binary from a Typst GitHub release.
@@ -293,8 +519,12 @@ pub struct InfoCommand {
 #[derive(Debug, Clone, Args)]
 pub struct CompileArgs {
     /// Path to input Typst file. Use `-` to read input from stdin.
+    ///
+    /// When omitted, compiles the targets declared in the `typst.toml` project
+    /// manifest of the working directory or one of its ancestors. Settings
+    /// passed on the command line take precedence over the manifest.
     #[clap(value_parser = input_value_parser(), value_hint = ValueHint::FilePath)]
-    pub input: Input,
+    pub input: Option<Input>,
 
     /// Path to output file (PDF, PNG, SVG, or HTML). Use `-` to write output to
     /// stdout.
@@ -383,11 +613,21 @@ pub struct CompileArgs {
     /// apart from file names and line numbers.
     #[arg(long = "timings", value_name = "OUTPUT_JSON")]
     pub timings: Option<Option<PathBuf>>,
+
+    /// Reports where the compilation spends its time. (experimental)
+    ///
+    /// Time is attributed to functions, call sites, show rules, and laid out
+    /// elements, with inclusive and exclusive time and call counts. Without a
+    /// value, prints a table of the most expensive entries. With a value, as
+    /// in `--profile=profile.json`, writes all entries to the JSON file
+    /// instead.
+    #[arg(long = "profile", value_name = "OUTPUT_JSON", require_equals = true)]
+    pub profile: Option<Option<PathBuf>>,
 }
 
 /// Arguments for the construction of a world. Shared by compile, watch, eval, and
 /// query.
-#[derive(Debug, Clone, Args)]
+#[derive(Debug, Default, Clone, Args)]
 pub struct WorldArgs {
     /// Configures the project root (for absolute paths).
     #[clap(long = "root", env = "TYPST_ROOT", value_name = "DIR")]
@@ -402,14 +642,60 @@ pub struct WorldArgs {
     )]
     pub inputs: Vec<(String, String)>,
 
+    /// Add a key-value pair with a JSON value visible through `sys.inputs`.
+    ///
+    /// Takes precedence over pairs of the same key passed with `--input` or
+    /// `--input-file`.
+    #[clap(
+        long = "input-json",
+        value_name = "key=json",
+        action = ArgAction::Append,
+        value_parser = ValueParser::new(parse_sys_input_pair),
+    )]
+    pub json_inputs: Vec<(String, String)>,
+
+    /// Add the key-value pairs of a JSON, TOML, or YAML file visible through
+    /// `sys.inputs`.
+    ///
+    /// The file format is determined by the extension and the file must
+    /// contain a dictionary. Pairs passed with `--input` or `--input-json`
+    /// take precedence over pairs from files.
+    #[clap(long = "input-file", value_name = "FILE", action = ArgAction::Append)]
+    pub input_files: Vec<PathBuf>,
+
     /// Common font arguments.
     #[clap(flatten)]
     pub font: FontArgs,
 
+    /// Adds fonts to try for text in a script or language when the primary
+    /// font list has no match, e.g. `hani=Noto Serif CJK SC,Source Han Serif`.
+    ///
+    /// The key is a four letter script code or a language code with an
+    /// optional region, like `ja` or `zh-TW`. Fallback fonts configured in the
+    /// document take precedence.
+    #[clap(
+        long = "fallback-font",
+        value_name = "key=families",
+        action = ArgAction::Append,
+        value_parser = ValueParser::new(parse_fallback_font),
+    )]
+    pub fallback_fonts: Vec<(String, String)>,
+
+    /// Warns about characters that are shaped with a font from outside of the
+    /// primary font list.
+    #[clap(long)]
+    pub warn_fallback: bool,
+
     /// Arguments related to storage of packages in the system.
     #[clap(flatten)]
     pub package: PackageArgs,
 
+    /// Fail if a package resolved during compilation is missing from or
+    /// differs from the project's `typst.lock`. The lockfile is never updated
+    /// in this mode.
+    #[clap(long)]
+    pub locked: bool,
+
     /// The document's creation date formatted as a UNIX timestamp.
     ///
     /// For more information, see <https://reproducible-builds.org/specs/source-date-epoch/>.
@@ -438,10 +724,14 @@ pub struct ProcessArgs {
     /// The format to emit diagnostics in.
     #[clap(long, default_value_t)]
     pub diagnostic_format: DiagnosticFormat,
+
+    /// Shows the arguments of traced function calls in error diagnostics.
+    #[clap(long)]
+    pub trace_args: bool,
 }
 
 /// Arguments related to where packages are stored in the system.
-#[derive(Debug, Clone, Args)]
+#[derive(Debug, Default, Clone, Args)]
 pub struct PackageArgs {
     /// Custom path to local packages, defaults to system-dependent location.
     #[clap(long = "package-path", env = "TYPST_PACKAGE_PATH", value_name = "DIR")]
@@ -454,10 +744,32 @@ pub struct PackageArgs {
         value_name = "DIR"
     )]
     pub package_cache_path: Option<PathBuf>,
+
+    /// Adds a registry that serves the packages of a namespace.
+    ///
+    /// The location is either an HTTP(S) URL or a local directory with the
+    /// same layout as <https://packages.typst.org>: the archives at
+    /// `{namespace}/{name}-{version}.tar.gz` and an index at
+    /// `{namespace}/index.json`. Multiple registries are separated by commas
+    /// in the environment variable.
+    #[clap(
+        long = "package-registry",
+        env = "TYPST_PACKAGE_REGISTRIES",
+        value_name = "namespace=location",
+        action = ArgAction::Append,
+        value_delimiter = ',',
+        value_parser = ValueParser::new(parse_package_registry),
+    )]
+    pub package_registries: Vec<(String, String)>,
+
+    /// Forbids all network access, so that packages can only be loaded from
+    /// the vendor, data, and cache directories.
+    #[clap(long)]
+    pub offline: bool,
 }
 
 /// Common arguments to customize available fonts.
-#[derive(Debug, Clone, Parser)]
+#[derive(Debug, Default, Clone, Parser)]
 pub struct FontArgs {
     /// Adds additional directories that are recursively searched for fonts.
     ///
@@ -476,6 +788,16 @@ pub struct FontArgs {
     #[arg(long, env = "TYPST_IGNORE_SYSTEM_FONTS")]
     pub ignore_system_fonts: bool,
 
+    /// Discards the cached font index and parses all font files again.
+    #[arg(long, env = "TYPST_RESCAN_FONTS")]
+    pub rescan_fonts: bool,
+
+    /// The file in which the metadata of font files is cached between runs.
+    ///
+    /// Defaults to `typst/fonts.json` in the system's cache directory.
+    #[arg(long = "font-index", env = "TYPST_FONT_INDEX", value_name = "FILE")]
+    pub font_index: Option<PathBuf>,
+
     /// Ensures fonts embedded into Typst won't be considered.
     #[cfg(feature = "embedded-fonts")]
     #[arg(long, env = "TYPST_IGNORE_EMBEDDED_FONTS")]
@@ -624,6 +946,28 @@ pub enum DepsFormat {
 
 display_possible_values!(DepsFormat);
 
+/// Which format to use for the reference images of tests.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, ValueEnum)]
+pub enum ReferenceFormat {
+    /// Compares rendered pixels, tolerating minor rendering differences.
+    #[default]
+    Png,
+    /// Compares the SVG output exactly.
+    Svg,
+}
+
+impl ReferenceFormat {
+    /// The file extension of reference images in this format.
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Png => "png",
+            Self::Svg => "svg",
+        }
+    }
+}
+
+display_possible_values!(ReferenceFormat);
+
 /// The target to compile for.
 #[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, ValueEnum)]
 pub enum Target {
@@ -642,6 +986,8 @@ pub enum DiagnosticFormat {
     #[default]
     Human,
     Short,
+    Json,
+    Sarif,
 }
 
 display_possible_values!(DiagnosticFormat);
@@ -714,7 +1060,7 @@ pub enum PdfStandard {
 
 display_possible_values!(PdfStandard);
 
-/// Output file format for query and info commands
+/// Output file format for query, info, and fonts commands
 #[derive(Debug, Default, Copy, Clone, Eq, PartialEq, ValueEnum)]
 pub enum SerializationFormat {
     #[default]
@@ -811,6 +1157,38 @@ fn parse_sys_input_pair(raw: &str) -> Result<(String, String), String> {
     Ok((key, val))
 }
 
+/// Parses a namespace and the location of the registry that serves it.
+fn parse_package_registry(raw: &str) -> Result<(String, String), String> {
+    let (namespace, location) = raw.split_once('=').ok_or(
+        "registry must be a namespace and a location separated by an equal sign",
+    )?;
+    let namespace = namespace.trim().trim_start_matches('@').to_owned();
+    if namespace.is_empty() {
+        return Err("the namespace was missing or empty".to_owned());
+    }
+    let location = location.trim().trim_end_matches('/').to_owned();
+    if location.is_empty() {
+        return Err("the location was missing or empty".to_owned());
+    }
+    Ok((namespace, location))
+}
+
+/// Parses a script or language and the fallback fonts for it.
+fn parse_fallback_font(raw: &str) -> Result<(String, String), String> {
+    let (key, families) = raw.split_once('=').ok_or(
+        "fallback fonts must be a script or language and a comma-separated list \
+         of font families separated by an equal sign",
+    )?;
+    let key = key.trim().to_owned();
+    if key.is_empty() {
+        return Err("the script or language was missing or empty".to_owned());
+    }
+    if families.split(',').any(|family| family.trim().is_empty()) {
+        return Err("a font family was missing or empty".to_owned());
+    }
+    Ok((key, families.to_owned()))
+}
+
 /// Parses a UNIX timestamp according to <https://reproducible-builds.org/specs/source-date-epoch/>
 fn parse_source_date_epoch(raw: &str) -> Result<DateTime<Utc>, String> {
     let timestamp: i64 = raw
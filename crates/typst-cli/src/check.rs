@@ -0,0 +1,627 @@
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::io::Write;
+use std::path::{Component, Path, PathBuf};
+
+use codespan_reporting::term;
+use codespan_reporting::term::termcolor::WriteColor;
+use ecow::{EcoString, EcoVec, eco_format};
+use ignore::gitignore::GitignoreBuilder;
+use typst::World;
+use typst::diag::{HintedStrResult, SourceDiagnostic, StrResult, Warned, bail};
+use typst::layout::PagedDocument;
+use typst::syntax::package::{
+    PackageManifest, PackageSpec, PackageVersion, TemplateInfo,
+};
+use typst::syntax::{Source, Span, is_ident};
+use walkdir::WalkDir;
+
+use crate::args::{Input, PackageCheckCommand, WorldArgs};
+use crate::compile::print_diagnostics;
+use crate::packages::{format_size, imports};
+use crate::set_failed;
+use crate::world::SystemWorld;
+
+/// The file name of the package manifest.
+const MANIFEST: &str = "typst.toml";
+
+/// Files larger than this are flagged unless they are excluded.
+const LARGE_FILE: u64 = 1024 * 1024;
+
+/// Execute a package check command.
+pub fn check(command: &PackageCheckCommand) -> HintedStrResult<()> {
+    let dir = match &command.dir {
+        Some(dir) => dir.clone(),
+        None => std::env::current_dir().map_err(|err| {
+            eco_format!("failed to determine working directory ({err})")
+        })?,
+    };
+    let dir = dir.canonicalize().map_err(|err| {
+        eco_format!("failed to find package directory at {} ({err})", dir.display())
+    })?;
+
+    let mut world =
+        SystemWorld::new(None, &world_args(command, &dir, None), &command.process)?;
+    let Some(id) = world.id(&dir.join(MANIFEST)) else {
+        bail!(
+            "no package manifest found (searched at {})",
+            dir.join(MANIFEST).display();
+            hint: "pass the directory containing the package's `{MANIFEST}`";
+        );
+    };
+    let source = world
+        .source(id)
+        .map_err(|err| eco_format!("failed to read package manifest ({err})"))?;
+
+    let mut checker = Checker {
+        dir: &dir,
+        manifest: &source,
+        bundle: Bundle::default(),
+        errors: EcoVec::new(),
+        warnings: EcoVec::new(),
+        reported: HashSet::new(),
+    };
+
+    let manifest = match toml::from_str::<PackageManifest>(source.text()) {
+        Ok(manifest) => Some(manifest),
+        Err(err) => {
+            let range = err.span().unwrap_or(0..0);
+            checker.errors.push(SourceDiagnostic::error(
+                Span::from_range(id, range),
+                eco_format!("package manifest is malformed ({})", err.message()),
+            ));
+            None
+        }
+    };
+
+    if let Some(manifest) = &manifest {
+        checker.bundle = Bundle::collect(&dir, &manifest.package.exclude)?;
+        checker.check_manifest(manifest);
+        checker.check_files();
+        checker.check_imports(&world, manifest.template.as_ref());
+
+        // Compile the entrypoint as it would be imported.
+        let entrypoint = dir.join(manifest.package.entrypoint.as_str());
+        if let Some(main) = world.id(&entrypoint) {
+            world.set_main(main);
+            checker.compile(&mut world);
+        }
+    }
+
+    checker.print(&world, command)?;
+
+    if let Some(manifest) = &manifest {
+        if let Some(template) = &manifest.template {
+            checker.compile_template(command, manifest, template)?;
+        }
+        print_summary(manifest, &checker.bundle).unwrap();
+    }
+
+    Ok(())
+}
+
+/// The files in the package directory, split by whether they end up in the
+/// published bundle.
+#[derive(Default)]
+struct Bundle {
+    included: BTreeSet<PathBuf>,
+    excluded: BTreeSet<PathBuf>,
+    sizes: HashMap<PathBuf, u64>,
+}
+
+impl Bundle {
+    /// Walks the package directory and applies the exclude patterns.
+    fn collect(dir: &Path, exclude: &[EcoString]) -> StrResult<Self> {
+        let mut builder = GitignoreBuilder::new(dir);
+        for pattern in exclude {
+            builder.add_line(None, pattern).map_err(|err| {
+                eco_format!("invalid exclude pattern `{pattern}` ({err})")
+            })?;
+        }
+        let matcher = builder
+            .build()
+            .map_err(|err| eco_format!("invalid exclude patterns ({err})"))?;
+
+        let mut bundle = Self::default();
+        for entry in WalkDir::new(dir)
+            .sort_by_file_name()
+            .into_iter()
+            .filter_entry(|entry| entry.file_name() != ".git")
+        {
+            let entry = entry
+                .map_err(|err| eco_format!("failed to read package directory ({err})"))?;
+            if !entry.file_type().is_file() {
+                continue;
+            }
+
+            let path = entry.path().strip_prefix(dir).unwrap_or(entry.path());
+            let size = entry.metadata().map(|metadata| metadata.len()).unwrap_or(0);
+            if matcher.matched_path_or_any_parents(path, false).is_ignore() {
+                bundle.excluded.insert(path.to_path_buf());
+            } else {
+                bundle.included.insert(path.to_path_buf());
+            }
+            bundle.sizes.insert(path.to_path_buf(), size);
+        }
+
+        Ok(bundle)
+    }
+
+    /// The size of a file in the package directory.
+    fn size(&self, path: &Path) -> u64 {
+        self.sizes.get(path).copied().unwrap_or(0)
+    }
+
+    /// The total size of the given files.
+    fn total(&self, files: &BTreeSet<PathBuf>) -> u64 {
+        files.iter().map(|path| self.size(path)).sum()
+    }
+}
+
+/// Collects diagnostics for a package.
+struct Checker<'a> {
+    dir: &'a Path,
+    manifest: &'a Source,
+    bundle: Bundle,
+    errors: EcoVec<SourceDiagnostic>,
+    warnings: EcoVec<SourceDiagnostic>,
+    /// Excluded files that were already reported.
+    reported: HashSet<PathBuf>,
+}
+
+impl Checker<'_> {
+    /// Validates the manifest against the package specification.
+    fn check_manifest(&mut self, manifest: &PackageManifest) {
+        let info = &manifest.package;
+
+        for key in manifest.unknown_fields.keys() {
+            let span = self.key_span("", key);
+            self.warnings.push(SourceDiagnostic::warning(
+                span,
+                eco_format!("unknown key `{key}` in package manifest"),
+            ));
+        }
+
+        for key in info.unknown_fields.keys() {
+            let span = self.key_span("package", key);
+            self.warnings.push(SourceDiagnostic::warning(
+                span,
+                eco_format!("unknown key `{key}`"),
+            ));
+        }
+
+        if !is_ident(&info.name) {
+            let span = self.key_span("package", "name");
+            self.errors.push(SourceDiagnostic::error(
+                span,
+                eco_format!("`{}` is not a valid package name", info.name),
+            ));
+        }
+
+        for (missing, key) in [
+            (info.authors.is_empty(), "authors"),
+            (info.license.is_none(), "license"),
+            (info.description.is_none(), "description"),
+        ] {
+            if missing {
+                let span = self.key_span("package", "");
+                self.errors.push(
+                    SourceDiagnostic::error(
+                        span,
+                        eco_format!("package manifest is missing `{key}`"),
+                    )
+                    .with_hint("this key is required for publishing"),
+                );
+            }
+        }
+
+        if let Some(required) = info.compiler {
+            let current = PackageVersion::compiler();
+            if !current.matches_ge(&required) {
+                let span = self.key_span("package", "compiler");
+                self.errors.push(SourceDiagnostic::error(
+                    span,
+                    eco_format!(
+                        "package requires Typst {required} or newer \
+                         (current version is {current})"
+                    ),
+                ));
+            }
+        }
+
+        self.check_path("package", "entrypoint", &info.entrypoint, "entrypoint");
+
+        let Some(template) = &manifest.template else { return };
+
+        for key in template.unknown_fields.keys() {
+            let span = self.key_span("template", key);
+            self.warnings.push(SourceDiagnostic::warning(
+                span,
+                eco_format!("unknown key `{key}`"),
+            ));
+        }
+
+        let template_dir = Path::new(template.path.as_str());
+        let in_template = |path: &PathBuf| {
+            normalize(template_dir).is_some_and(|dir| path.starts_with(dir))
+        };
+        if !self.bundle.included.iter().any(in_template) {
+            let span = self.key_span("template", "path");
+            self.errors.push(SourceDiagnostic::error(
+                span,
+                eco_format!("template directory `{}` contains no files", template.path),
+            ));
+        }
+
+        let entrypoint = template_dir.join(template.entrypoint.as_str());
+        self.check_path(
+            "template",
+            "entrypoint",
+            &entrypoint.to_string_lossy(),
+            "template entrypoint",
+        );
+
+        match &template.thumbnail {
+            Some(thumbnail) => {
+                let span = self.key_span("template", "thumbnail");
+                let path = Path::new(thumbnail.as_str());
+                if normalize(path).is_none_or(|path| !self.exists(&path)) {
+                    self.errors.push(SourceDiagnostic::error(
+                        span,
+                        eco_format!("thumbnail `{thumbnail}` does not exist"),
+                    ));
+                } else if !path
+                    .extension()
+                    .is_some_and(|ext| ext == "png" || ext == "webp")
+                {
+                    self.errors.push(SourceDiagnostic::error(
+                        span,
+                        "thumbnail must be a PNG or WebP image",
+                    ));
+                }
+            }
+            None => {
+                let span = self.key_span("template", "");
+                self.errors.push(
+                    SourceDiagnostic::error(span, "template has no thumbnail").with_hint(
+                        "add a `thumbnail` key with the path to a PNG or WebP image",
+                    ),
+                );
+            }
+        }
+    }
+
+    /// Checks that a path from the manifest refers to a bundled file.
+    fn check_path(&mut self, table: &str, key: &str, path: &str, what: &str) {
+        let span = self.key_span(table, key);
+        match normalize(Path::new(path)) {
+            Some(path) if self.bundle.included.contains(&path) => {}
+            Some(path) if self.bundle.excluded.contains(&path) => {
+                self.errors.push(
+                    SourceDiagnostic::error(
+                        span,
+                        eco_format!("{what} `{}` is excluded", path.display()),
+                    )
+                    .with_hint("remove it from `exclude` in the package manifest"),
+                );
+            }
+            _ => self.errors.push(SourceDiagnostic::error(
+                span,
+                eco_format!("{what} `{path}` does not exist"),
+            )),
+        }
+    }
+
+    /// Flags large files that end up in the bundle.
+    fn check_files(&mut self) {
+        let span = self.key_span("package", "exclude");
+        for path in &self.bundle.included {
+            let size = self.bundle.size(path);
+            if size > LARGE_FILE {
+                self.warnings.push(
+                    SourceDiagnostic::warning(
+                        span,
+                        eco_format!(
+                            "file `{}` is large ({}) and will be published",
+                            path.display(),
+                            format_size(size),
+                        ),
+                    )
+                    .with_hint("add it to `exclude` if the package doesn't need it"),
+                );
+            }
+        }
+    }
+
+    /// Checks that relative imports and includes in bundled Typst files refer
+    /// to bundled files.
+    ///
+    /// Absolute paths are resolved relative to the package root, except in
+    /// the template, which becomes its own project once it is instantiated.
+    fn check_imports(&mut self, world: &SystemWorld, template: Option<&TemplateInfo>) {
+        let template_dir = template.and_then(|t| normalize(Path::new(t.path.as_str())));
+        let files: Vec<_> = self
+            .bundle
+            .included
+            .iter()
+            .filter(|path| path.extension().is_some_and(|ext| ext == "typ"))
+            .cloned()
+            .collect();
+
+        for file in files {
+            let Some(id) = world.id(&self.dir.join(&file)) else { continue };
+            let Ok(source) = world.source(id) else { continue };
+            for (range, path) in imports(source.root()) {
+                if path.starts_with('@') {
+                    continue;
+                }
+
+                let span = Span::from_range(id, range);
+                let base = file.parent().unwrap_or(Path::new(""));
+                let target = match path.strip_prefix('/') {
+                    Some(absolute) => match &template_dir {
+                        Some(dir) if file.starts_with(dir) => {
+                            normalize(&dir.join(absolute))
+                        }
+                        _ => normalize(Path::new(absolute)),
+                    },
+                    None => normalize(&base.join(path.as_str())),
+                };
+
+                let Some(target) = target else {
+                    self.errors.push(SourceDiagnostic::error(
+                        span,
+                        "path escapes the package directory",
+                    ));
+                    continue;
+                };
+
+                self.check_dependency(span, &target);
+            }
+        }
+    }
+
+    /// Compiles the current main file of the world and checks that it doesn't
+    /// depend on excluded files.
+    fn compile(&mut self, world: &mut SystemWorld) {
+        let Warned { output, warnings } = typst::compile::<PagedDocument>(&*world);
+        if let Err(errors) = output {
+            self.errors.extend(errors);
+        }
+        self.warnings.extend(warnings);
+
+        let dependencies: Vec<_> = world.dependencies().collect();
+        for path in dependencies {
+            if let Ok(path) = path.strip_prefix(self.dir) {
+                self.check_dependency(Span::detached(), path);
+            }
+        }
+    }
+
+    /// Prints the collected diagnostics.
+    fn print(&self, world: &SystemWorld, command: &PackageCheckCommand) -> StrResult<()> {
+        print_diagnostics(
+            world,
+            &self.errors,
+            &self.warnings,
+            command.process.diagnostic_options(),
+        )
+        .map_err(|err| eco_format!("failed to print diagnostics ({err})"))?;
+        if !self.errors.is_empty() {
+            set_failed();
+        }
+        Ok(())
+    }
+
+    /// Compiles the template against the bundled files of the package and
+    /// prints the resulting diagnostics.
+    ///
+    /// The template's imports of the package itself are served from a
+    /// temporary copy of the bundle, so that the template can only use what
+    /// is actually published.
+    fn compile_template(
+        &self,
+        command: &PackageCheckCommand,
+        manifest: &PackageManifest,
+        template: &TemplateInfo,
+    ) -> HintedStrResult<()> {
+        let template_dir = self.dir.join(template.path.as_str());
+        let entrypoint = template_dir.join(template.entrypoint.as_str());
+        if !entrypoint.is_file() {
+            return Ok(());
+        }
+
+        let temp = tempfile::tempdir()
+            .map_err(|err| eco_format!("failed to create temporary directory ({err})"))?;
+
+        // Copy the bundle for each namespace from which the template imports
+        // this package.
+        let info = &manifest.package;
+        let namespaces: BTreeSet<EcoString> = self
+            .bundle
+            .included
+            .iter()
+            .filter(|file| file.starts_with(template.path.as_str()))
+            .filter_map(|file| std::fs::read_to_string(self.dir.join(file)).ok())
+            .flat_map(|text| imports(&typst::syntax::parse(&text)))
+            .filter_map(|(_, source)| source.parse::<PackageSpec>().ok())
+            .filter(|spec| spec.name == info.name && spec.version == info.version)
+            .map(|spec| spec.namespace)
+            .collect();
+
+        for namespace in namespaces {
+            let target = temp
+                .path()
+                .join(namespace.as_str())
+                .join(info.name.as_str())
+                .join(info.version.to_string());
+            for file in &self.bundle.included {
+                let dest = target.join(file);
+                if let Some(parent) = dest.parent() {
+                    std::fs::create_dir_all(parent).map_err(|err| {
+                        eco_format!("failed to create temporary directory ({err})")
+                    })?;
+                }
+                std::fs::copy(self.dir.join(file), dest)
+                    .map_err(|err| eco_format!("failed to copy package file ({err})"))?;
+            }
+        }
+
+        let input = Input::Path(entrypoint);
+        let args = world_args(command, &template_dir, Some(temp.path()));
+        let world = SystemWorld::new(Some(&input), &args, &command.process)?;
+        let Warned { output, warnings } = typst::compile::<PagedDocument>(&world);
+        let errors = output.err().unwrap_or_default();
+        print_diagnostics(
+            &world,
+            &errors,
+            &warnings,
+            command.process.diagnostic_options(),
+        )
+        .map_err(|err| eco_format!("failed to print diagnostics ({err})"))?;
+        if !errors.is_empty() {
+            set_failed();
+        }
+
+        Ok(())
+    }
+
+    /// Checks that a file relative to the package root that is needed by the
+    /// package is part of the bundle.
+    fn check_dependency(&mut self, span: Span, path: &Path) {
+        if self.bundle.excluded.contains(path) {
+            if !self.reported.insert(path.to_path_buf()) {
+                return;
+            }
+            self.errors.push(
+                SourceDiagnostic::error(
+                    span,
+                    eco_format!("file `{}` is excluded from the package", path.display()),
+                )
+                .with_hint("remove it from `exclude` in the package manifest"),
+            );
+        } else if span.is_detached() || self.exists(path) {
+            // Missing dependencies of the compilation are reported by the
+            // compiler itself.
+        } else {
+            self.errors.push(SourceDiagnostic::error(
+                span,
+                eco_format!("file `{}` does not exist", path.display()),
+            ));
+        }
+    }
+
+    /// Whether a file relative to the package root exists in the package
+    /// directory.
+    fn exists(&self, path: &Path) -> bool {
+        self.bundle.included.contains(path) || self.bundle.excluded.contains(path)
+    }
+
+    /// Finds the span of a key in a table of the manifest.
+    ///
+    /// An empty table refers to the top level and an empty key refers to the
+    /// table's header. Falls back to the table's header or the start of the
+    /// manifest if the key is not found.
+    fn key_span(&self, table: &str, key: &str) -> Span {
+        let text = self.manifest.text();
+        let id = self.manifest.id();
+        let mut current = "";
+        let mut header = None;
+        let mut offset = 0;
+
+        for line in text.split_inclusive('\n') {
+            let start = offset + (line.len() - line.trim_start().len());
+            let trimmed = line.trim();
+            offset += line.len();
+
+            if let Some(name) = trimmed.strip_prefix('[') {
+                current = name.trim_matches(|c| c == '[' || c == ']').trim();
+                let range = start..start + trimmed.len();
+                if current == table {
+                    header = Some(range.clone());
+                }
+                if table.is_empty() && current == key {
+                    return Span::from_range(id, range);
+                }
+            } else if current == table
+                && !key.is_empty()
+                && let Some(rest) = trimmed.strip_prefix(key)
+                && rest.trim_start().starts_with('=')
+            {
+                return Span::from_range(id, start..start + key.len());
+            }
+        }
+
+        Span::from_range(id, header.unwrap_or(0..0))
+    }
+}
+
+/// Returns the world arguments for compiling a package's files.
+fn world_args(
+    command: &PackageCheckCommand,
+    root: &Path,
+    package_path: Option<&Path>,
+) -> WorldArgs {
+    let mut package = command.package.clone();
+    if let Some(path) = package_path {
+        package.package_path = Some(path.to_path_buf());
+    }
+
+    WorldArgs {
+        root: Some(root.to_path_buf()),
+        font: command.font.clone(),
+        package,
+        ..WorldArgs::default()
+    }
+}
+
+/// Lexically normalizes a relative path, returning `None` if it escapes its
+/// root.
+fn normalize(path: &Path) -> Option<PathBuf> {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                if !normalized.pop() {
+                    return None;
+                }
+            }
+            Component::Normal(part) => normalized.push(part),
+            Component::RootDir | Component::Prefix(_) => return None,
+        }
+    }
+    Some(normalized)
+}
+
+/// Prints the size of the bundle and the excluded files.
+fn print_summary(manifest: &PackageManifest, bundle: &Bundle) -> std::io::Result<()> {
+    let styles = term::Styles::default();
+    let mut out = crate::terminal::out();
+    let info = &manifest.package;
+
+    out.set_color(&styles.header_help)?;
+    write!(out, "checked")?;
+    out.reset()?;
+    writeln!(
+        out,
+        " {}:{} ({} files, {})",
+        info.name,
+        info.version,
+        bundle.included.len(),
+        format_size(bundle.total(&bundle.included)),
+    )?;
+
+    if !bundle.excluded.is_empty() {
+        writeln!(
+            out,
+            "excluded {} files ({}):",
+            bundle.excluded.len(),
+            format_size(bundle.total(&bundle.excluded)),
+        )?;
+        for path in &bundle.excluded {
+            writeln!(out, "  {} ({})", path.display(), format_size(bundle.size(path)))?;
+        }
+    }
+
+    Ok(())
+}
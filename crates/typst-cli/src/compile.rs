@@ -5,19 +5,22 @@ use chrono::{DateTime, Datelike, Timelike, Utc};
 use ecow::eco_format;
 use parking_lot::RwLock;
 use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
+use typst::CompileOptions;
 use typst::diag::{
     At, HintedStrResult, HintedString, SourceDiagnostic, SourceResult, StrResult, Warned,
     bail,
 };
+use typst::engine::CancellationToken;
 use typst::foundations::{Datetime, Smart};
 use typst::layout::{Page, PageRanges, PagedDocument};
 use typst::syntax::Span;
 use typst_html::HtmlDocument;
+use typst_kit::diagnostics::DiagnosticOptions;
 use typst_pdf::{PdfOptions, PdfStandards, Timestamp};
 
 use crate::args::{
     CompileArgs, CompileCommand, DepsFormat, DiagnosticFormat, Input, Output,
-    OutputFormat, PdfStandard, WatchCommand,
+    OutputFormat, PdfStandard, ProcessArgs, WatchCommand,
 };
 use crate::deps::write_deps;
 #[cfg(feature = "http-server")]
@@ -33,14 +36,13 @@ pub fn compile(
     timer: &mut Timer,
     command: &'static CompileCommand,
 ) -> HintedStrResult<()> {
-    let mut config = CompileConfig::new(command)?;
-    let mut world = SystemWorld::new(
-        Some(&command.args.input),
-        &command.args.world,
-        &command.args.process,
-    )
-    .map_err(|err| eco_format!("{err}"))?;
-    timer.record(&mut world, |world| compile_once(world, &mut config))?
+    for args in crate::project::resolve(&command.args)? {
+        let mut config = CompileConfig::new(args)?;
+        let mut world = SystemWorld::new(Some(&config.input), &args.world, &args.process)
+            .map_err(|err| eco_format!("{err}"))?;
+        timer.record(&mut world, |world| compile_once(world, &mut config))??;
+    }
+    Ok(())
 }
 
 /// A preprocessed `CompileCommand`.
@@ -59,8 +61,8 @@ pub struct CompileConfig {
     pub pages: Option<PageRanges>,
     /// The document's creation date formatted as a UNIX timestamp, with UTC suffix.
     pub creation_timestamp: Option<DateTime<Utc>>,
-    /// The format to emit diagnostics in.
-    pub diagnostic_format: DiagnosticFormat,
+    /// How to emit diagnostics.
+    pub diagnostics: DiagnosticOptions,
     /// Opens the output file with the default viewer or a specific program after
     /// compilation.
     pub open: Option<Option<String>>,
@@ -80,17 +82,22 @@ pub struct CompileConfig {
     /// Server for `typst watch` to HTML.
     #[cfg(feature = "http-server")]
     pub server: Option<HtmlServer>,
+    /// Cancels the compilation when triggered. Used by `typst watch` to abort
+    /// a compilation that is outdated by a file change.
+    pub cancellation: Option<CancellationToken>,
 }
 
 impl CompileConfig {
-    /// Preprocess a `CompileCommand`, producing a compilation config.
-    pub fn new(command: &CompileCommand) -> HintedStrResult<Self> {
-        Self::new_impl(&command.args, None)
+    /// Preprocess the arguments of a `CompileCommand`, producing a compilation
+    /// config.
+    pub fn new(args: &CompileArgs) -> HintedStrResult<Self> {
+        Self::new_impl(args, None)
     }
 
-    /// Preprocess a `WatchCommand`, producing a compilation config.
-    pub fn watching(command: &WatchCommand) -> HintedStrResult<Self> {
-        Self::new_impl(&command.args, Some(command))
+    /// Preprocess the arguments of a `WatchCommand`, producing a compilation
+    /// config.
+    pub fn watching(args: &CompileArgs, command: &WatchCommand) -> HintedStrResult<Self> {
+        Self::new_impl(args, Some(command))
     }
 
     /// The shared implementation of [`CompileConfig::new`] and
@@ -100,7 +107,9 @@ impl CompileConfig {
         watch: Option<&WatchCommand>,
     ) -> HintedStrResult<Self> {
         let mut warnings = Vec::new();
-        let input = args.input.clone();
+        let Some(input) = args.input.clone() else {
+            bail!("no input file was specified");
+        };
 
         let output_format = if let Some(specified) = args.format {
             specified
@@ -221,13 +230,14 @@ impl CompileConfig {
             tagged,
             creation_timestamp: args.world.creation_timestamp,
             ppi: args.ppi,
-            diagnostic_format: args.process.diagnostic_format,
+            diagnostics: args.process.diagnostic_options(),
             open: args.open.clone(),
             export_cache: ExportCache::new(),
             deps,
             deps_format,
             #[cfg(feature = "http-server")]
             server,
+            cancellation: None,
         })
     }
 }
@@ -247,6 +257,16 @@ pub fn compile_once(
 
     let Warned { output, mut warnings } = compile_and_export(world, config);
 
+    // A cancelled compilation is superseded by the next one, so there is
+    // nothing to report.
+    if config
+        .cancellation
+        .as_ref()
+        .is_some_and(CancellationToken::is_cancelled)
+    {
+        return Ok(());
+    }
+
     // Add static warnings (for deprecated CLI flags and such).
     for warning in config.warnings.iter() {
         warnings.push(
@@ -255,6 +275,18 @@ pub fn compile_once(
         );
     }
 
+    // Report packages whose locked contents changed.
+    for message in world.take_lock_warnings() {
+        warnings.push(SourceDiagnostic::warning(Span::detached(), message));
+    }
+
+    // Record the packages resolved by a successful compilation.
+    if output.is_ok()
+        && let Err(err) = world.write_lock()
+    {
+        warnings.push(SourceDiagnostic::warning(Span::detached(), err));
+    }
+
     match &output {
         // Print success message and possibly warnings.
         Ok(_) => {
@@ -267,7 +299,7 @@ pub fn compile_once(
                 }
             }
 
-            print_diagnostics(world, &[], &warnings, config.diagnostic_format)
+            print_diagnostics(world, &[], &warnings, config.diagnostics)
                 .map_err(|err| eco_format!("failed to print diagnostics ({err})"))?;
 
             open_output(config)?;
@@ -281,7 +313,7 @@ pub fn compile_once(
                 Status::Error.print(config).unwrap();
             }
 
-            print_diagnostics(world, errors, &warnings, config.diagnostic_format)
+            print_diagnostics(world, errors, &warnings, config.diagnostics)
                 .map_err(|err| eco_format!("failed to print diagnostics ({err})"))?;
         }
     }
@@ -299,9 +331,14 @@ fn compile_and_export(
     world: &mut SystemWorld,
     config: &mut CompileConfig,
 ) -> Warned<SourceResult<Vec<Output>>> {
+    let options = CompileOptions {
+        cancellation: config.cancellation.clone(),
+        ..CompileOptions::default()
+    };
     match config.output_format {
         OutputFormat::Html => {
-            let Warned { output, warnings } = typst::compile::<HtmlDocument>(world);
+            let Warned { output, warnings } =
+                typst::compile_with::<HtmlDocument>(world, &options);
             let result = output.and_then(|document| export_html(&document, config));
             Warned {
                 output: result.map(|()| vec![config.output.clone()]),
@@ -309,7 +346,8 @@ fn compile_and_export(
             }
         }
         _ => {
-            let Warned { output, warnings } = typst::compile::<PagedDocument>(world);
+            let Warned { output, warnings } =
+                typst::compile_with::<PagedDocument>(world, &options);
             let result = output.and_then(|document| export_paged(&document, config));
             Warned { output: result, warnings }
         }
@@ -618,19 +656,37 @@ pub fn print_diagnostics(
     world: &SystemWorld,
     errors: &[SourceDiagnostic],
     warnings: &[SourceDiagnostic],
-    format: DiagnosticFormat,
+    options: DiagnosticOptions,
 ) -> Result<(), codespan_reporting::files::Error> {
     typst_kit::diagnostics::emit(
         &mut terminal::out(),
         world,
         errors.iter().chain(warnings),
-        match format {
-            DiagnosticFormat::Human => typst_kit::diagnostics::DiagnosticFormat::Human,
-            DiagnosticFormat::Short => typst_kit::diagnostics::DiagnosticFormat::Short,
-        },
+        options,
     )
 }
 
+impl ProcessArgs {
+    /// How to emit diagnostics as configured on the command line.
+    pub fn diagnostic_options(&self) -> DiagnosticOptions {
+        DiagnosticOptions {
+            format: self.diagnostic_format.into(),
+            trace_args: self.trace_args,
+        }
+    }
+}
+
+impl From<DiagnosticFormat> for typst_kit::diagnostics::DiagnosticFormat {
+    fn from(format: DiagnosticFormat) -> Self {
+        match format {
+            DiagnosticFormat::Human => Self::Human,
+            DiagnosticFormat::Short => Self::Short,
+            DiagnosticFormat::Json => Self::Json,
+            DiagnosticFormat::Sarif => Self::Sarif,
+        }
+    }
+}
+
 impl From<PdfStandard> for typst_pdf::PdfStandard {
     fn from(standard: PdfStandard) -> Self {
         match standard {
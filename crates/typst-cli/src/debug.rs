@@ -0,0 +1,678 @@
+use std::collections::{HashMap, HashSet};
+use std::io::{self, BufRead, Read, Write};
+use std::path::Path;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use codespan_reporting::term::termcolor::NoColor;
+use ecow::{EcoString, eco_format};
+use serde_json::{Value as Json, json};
+use typst::diag::{HintedStrResult, SourceDiagnostic, SourceResult, Warned};
+use typst::engine::{DebugFrame, Debugger};
+use typst::foundations::{Repr, Value};
+use typst::layout::PagedDocument;
+use typst::syntax::{FileId, Source, Span};
+use typst::{World, WorldExt};
+use typst_html::HtmlDocument;
+use typst_kit::diagnostics::DiagnosticOptions;
+
+use crate::args::{DebugCommand, Input, Target};
+use crate::world::SystemWorld;
+
+/// The ID of the only thread reported to the client.
+const THREAD: u64 = 1;
+
+/// The variables reference of the innermost frame's locals.
+const LOCALS: u64 = 1;
+
+/// The maximum length of a displayed value.
+const MAX_VALUE_LEN: usize = 200;
+
+/// Execute a debug command.
+pub fn debug(command: &'static DebugCommand) -> HintedStrResult<()> {
+    // Evaluate on a single thread so that the observed call stack is
+    // consistent.
+    rayon::ThreadPoolBuilder::new().num_threads(1).build_global().ok();
+
+    let input = Input::Path(command.input.clone());
+    let world = SystemWorld::new(Some(&input), &command.world, &command.process)?;
+
+    let (sender, receiver) = mpsc::channel();
+    let client = Client::default();
+    let session = Arc::new(Session {
+        client: client.clone(),
+        state: Mutex::default(),
+        commands: Mutex::new(receiver),
+    });
+
+    let mut adapter = Adapter {
+        world: &world,
+        input: &command.input,
+        client,
+        session: session.clone(),
+        sender: Some(sender),
+    };
+
+    std::thread::scope(|scope| {
+        let mut compilation = None;
+        let mut stdin = io::stdin().lock();
+        while let Some(request) = read_message(&mut stdin)
+            .map_err(|err| eco_format!("failed to read request ({err})"))?
+        {
+            match request["command"].as_str().unwrap_or_default() {
+                "configurationDone" => {
+                    adapter.client.respond(&request, Ok(Json::Null));
+                    let (world, session) = (&world, session.clone());
+                    compilation = Some(scope.spawn(move || {
+                        run(world, command.target, &session);
+                    }));
+                }
+                "disconnect" => {
+                    adapter.client.respond(&request, Ok(Json::Null));
+                    break;
+                }
+                _ => adapter.handle(&request),
+            }
+        }
+
+        // Let a paused compilation run to completion.
+        adapter.sender = None;
+        if let Some(compilation) = compilation {
+            compilation.join().ok();
+        }
+
+        Ok(())
+    })
+}
+
+/// Compiles the document and reports the results to the client.
+fn run(world: &SystemWorld, target: Target, session: &Arc<Session>) {
+    let debugger = session.clone();
+    let Warned { output, warnings } =
+        match target {
+            Target::Paged => typst::debug::<PagedDocument>(world, debugger)
+                .map(|output| output.map(drop)),
+            Target::Html => typst::debug::<HtmlDocument>(world, debugger)
+                .map(|output| output.map(drop)),
+        };
+
+    let errors = output.err().unwrap_or_default();
+    let text = format_diagnostics(world, &errors, &warnings);
+    if !text.is_empty() {
+        session
+            .client
+            .event("output", json!({ "category": "stderr", "output": text }));
+    }
+
+    session.client.event("terminated", json!({}));
+}
+
+/// Handles requests from the client.
+struct Adapter<'a> {
+    /// The world in which the document is compiled.
+    world: &'a SystemWorld,
+    /// The path to the input file given on the command line.
+    input: &'a Path,
+    /// The connection to the client.
+    client: Client,
+    /// The session shared with the compilation.
+    session: Arc<Session>,
+    /// Sends commands to a paused compilation.
+    sender: Option<Sender<Command>>,
+}
+
+impl Adapter<'_> {
+    /// Handles a request and responds to it.
+    fn handle(&self, request: &Json) {
+        let args = &request["arguments"];
+        let result = match request["command"].as_str().unwrap_or_default() {
+            "initialize" => Ok(self.initialize()),
+            "launch" => self.launch(args),
+            "attach" => Ok(self.attach(args)),
+            "setBreakpoints" => Ok(self.set_breakpoints(args)),
+            "setExceptionBreakpoints" => Ok(json!({ "breakpoints": [] })),
+            "threads" => Ok(json!({ "threads": [{ "id": THREAD, "name": "main" }] })),
+            "stackTrace" => Ok(self.stack_trace()),
+            "scopes" => Ok(self.scopes(args)),
+            "variables" => Ok(self.variables(args)),
+            "continue" => self.resume(Mode::Run),
+            "next" => self.resume(Mode::StepOver(self.depth())),
+            "stepIn" => self.resume(Mode::StepIn),
+            "stepOut" => self.resume(Mode::StepOut(self.depth())),
+            "pause" => {
+                self.session.state().mode = Mode::Pause;
+                Ok(Json::Null)
+            }
+            "evaluate" => self.evaluate(args),
+            command => Err(eco_format!("unsupported request: {command}")),
+        };
+
+        self.client.respond(request, result);
+    }
+
+    /// Announces the capabilities of the adapter.
+    fn initialize(&self) -> Json {
+        self.client.event("initialized", json!({}));
+        json!({
+            "supportsConfigurationDoneRequest": true,
+            "supportsEvaluateForHovers": true,
+        })
+    }
+
+    /// Prepares the compilation. The document is fixed by the command line,
+    /// so a `program` that names another file is rejected.
+    fn launch(&self, args: &Json) -> Result<Json, EcoString> {
+        if let Some(program) = args["program"].as_str() {
+            let canonical = |path: &Path| std::fs::canonicalize(path).ok();
+            let program = Path::new(program);
+            if canonical(program).is_none() || canonical(program) != canonical(self.input)
+            {
+                return Err(eco_format!(
+                    "cannot launch {}, the debugger was started for {}",
+                    program.display(),
+                    self.input.display(),
+                ));
+            }
+        }
+        Ok(self.attach(args))
+    }
+
+    /// Prepares the compilation, pausing it on entry if requested.
+    fn attach(&self, args: &Json) -> Json {
+        if args["stopOnEntry"].as_bool() == Some(true) {
+            self.session.state().mode = Mode::Pause;
+        }
+        Json::Null
+    }
+
+    /// Replaces the breakpoints of a source file.
+    fn set_breakpoints(&self, args: &Json) -> Json {
+        let lines: Vec<u64> = args["breakpoints"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|breakpoint| breakpoint["line"].as_u64())
+            .collect();
+
+        let source = args["source"]["path"]
+            .as_str()
+            .and_then(|path| self.world.id(Path::new(path)))
+            .and_then(|id| self.world.source(id).ok());
+
+        let verified = source.is_some();
+        let mut state = self.session.state();
+        if let Some(source) = source {
+            let id = source.id();
+            let lines = lines.iter().map(|&line| line.saturating_sub(1) as usize);
+            state
+                .breakpoints
+                .insert(id, Breakpoints { lines: lines.collect(), source });
+        }
+
+        let breakpoints: Vec<Json> = lines
+            .iter()
+            .map(|line| json!({ "verified": verified, "line": line }))
+            .collect();
+        json!({ "breakpoints": breakpoints })
+    }
+
+    /// Lists the frames of the call stack, innermost first.
+    fn stack_trace(&self) -> Json {
+        let state = self.session.state();
+        let frames: Vec<Json> = state
+            .stack
+            .iter()
+            .enumerate()
+            .rev()
+            .map(|(i, frame)| self.frame(i as u64 + 1, frame))
+            .collect();
+        json!({ "stackFrames": frames, "totalFrames": frames.len() })
+    }
+
+    /// Describes a single stack frame.
+    fn frame(&self, id: u64, frame: &Frame) -> Json {
+        let mut json =
+            json!({ "id": id, "name": frame.name.as_str(), "line": 0, "column": 0 });
+        let Some(file) = frame.span.id() else { return json };
+
+        if let Ok(path) = self.world.path(file) {
+            json["source"] = json!({ "path": path });
+        }
+
+        let position = self.world.range(frame.span).and_then(|range| {
+            self.world.source(file).ok()?.lines().byte_to_line_column(range.start)
+        });
+
+        if let Some((line, column)) = position {
+            json["line"] = json!(line + 1);
+            json["column"] = json!(column + 1);
+        }
+
+        json
+    }
+
+    /// Lists the scopes of a frame. Locals are only available for the innermost
+    /// frame.
+    fn scopes(&self, args: &Json) -> Json {
+        let innermost = args["frameId"].as_u64() == Some(self.depth() as u64);
+        let scopes = if innermost {
+            vec![json!({
+                "name": "Locals",
+                "presentationHint": "locals",
+                "variablesReference": LOCALS,
+                "expensive": false,
+            })]
+        } else {
+            vec![]
+        };
+        json!({ "scopes": scopes })
+    }
+
+    /// Lists the variables in a scope.
+    fn variables(&self, args: &Json) -> Json {
+        let state = self.session.state();
+        let variables: Vec<Json> = if args["variablesReference"].as_u64() == Some(LOCALS)
+        {
+            state
+                .locals
+                .iter()
+                .map(|(name, value)| {
+                    json!({
+                        "name": name.as_str(),
+                        "value": display(value).as_str(),
+                        "type": value.ty().short_name(),
+                        "variablesReference": 0,
+                    })
+                })
+                .collect()
+        } else {
+            vec![]
+        };
+        json!({ "variables": variables })
+    }
+
+    /// Evaluates an expression in the paused frame.
+    fn evaluate(&self, args: &Json) -> Result<Json, EcoString> {
+        let expr = args["expression"].as_str().unwrap_or_default();
+        let (reply, result) = mpsc::channel();
+        self.send(Command::Evaluate(expr.into(), reply))?;
+
+        let value = result
+            .recv()
+            .map_err(|_| "compilation is not paused")?
+            .map_err(|errors| format_errors(&errors))?;
+
+        Ok(json!({ "result": display(&value).as_str(), "variablesReference": 0 }))
+    }
+
+    /// Resumes a paused compilation.
+    fn resume(&self, mode: Mode) -> Result<Json, EcoString> {
+        self.send(Command::Resume(mode))?;
+        Ok(json!({ "allThreadsContinued": true }))
+    }
+
+    /// Sends a command to the paused compilation.
+    fn send(&self, command: Command) -> Result<(), EcoString> {
+        if !self.session.state().paused {
+            return Err("compilation is not paused".into());
+        }
+
+        self.sender
+            .as_ref()
+            .and_then(|sender| sender.send(command).ok())
+            .ok_or_else(|| "compilation has finished".into())
+    }
+
+    /// The current depth of the call stack.
+    fn depth(&self) -> usize {
+        self.session.state().stack.len()
+    }
+}
+
+/// Debugging state shared between the adapter and the compilation.
+struct Session {
+    /// The connection to the client.
+    client: Client,
+    /// The mutable state of the session.
+    state: Mutex<State>,
+    /// Receives commands while the compilation is paused.
+    commands: Mutex<Receiver<Command>>,
+}
+
+impl Session {
+    /// Locks the mutable state.
+    fn state(&self) -> MutexGuard<'_, State> {
+        self.state.lock().unwrap()
+    }
+}
+
+impl Debugger for Session {
+    fn enter(&self, name: Option<&str>, span: Span) {
+        let name = name.unwrap_or("<anonymous>").into();
+        self.state().stack.push(Frame { name, span, line: None });
+    }
+
+    fn exit(&self) {
+        self.state().stack.pop();
+    }
+
+    fn step(&self, frame: &DebugFrame) {
+        let mut state = self.state();
+        let Some(reason) = state.advance(frame.span) else { return };
+        state.locals = frame.locals();
+        state.paused = true;
+        drop(state);
+
+        self.client.event(
+            "stopped",
+            json!({ "reason": reason, "threadId": THREAD, "allThreadsStopped": true }),
+        );
+
+        // Block until the client tells us to resume.
+        let commands = self.commands.lock().unwrap();
+        let mode = loop {
+            match commands.recv() {
+                Ok(Command::Evaluate(expr, reply)) => {
+                    reply.send(frame.eval(&expr)).ok();
+                }
+                Ok(Command::Resume(mode)) => break mode,
+                Err(_) => break Mode::Detached,
+            }
+        };
+
+        let mut state = self.state();
+        state.mode = mode;
+        state.paused = false;
+        state.locals.clear();
+        if mode == Mode::Detached {
+            state.breakpoints.clear();
+        }
+    }
+}
+
+/// The mutable state of a debugging session.
+#[derive(Default)]
+struct State {
+    /// The breakpoints by file.
+    breakpoints: HashMap<FileId, Breakpoints>,
+    /// The current call stack, outermost first.
+    stack: Vec<Frame>,
+    /// How the compilation proceeds.
+    mode: Mode,
+    /// Whether the compilation is currently paused.
+    paused: bool,
+    /// The local variables of the innermost frame while paused.
+    locals: Vec<(EcoString, Value)>,
+}
+
+impl State {
+    /// Records that evaluation arrived at the given span and determines whether
+    /// and why it should pause there.
+    fn advance(&mut self, span: Span) -> Option<&'static str> {
+        let depth = self.stack.len();
+        let line = span.id().and_then(|id| self.breakpoints.get(&id)).and_then(|bps| {
+            let range = bps.source.range(span)?;
+            let line = bps.source.lines().byte_to_line(range.start)?;
+            Some((line, bps.lines.contains(&line)))
+        });
+
+        // Only hit a breakpoint once when arriving on its line.
+        let mut hit = false;
+        if let Some(frame) = self.stack.last_mut() {
+            if let Some((line, breakpoint)) = line {
+                hit = breakpoint && frame.line != Some(line);
+                frame.line = Some(line);
+            }
+            frame.span = span;
+        }
+
+        if hit {
+            return Some("breakpoint");
+        }
+
+        match self.mode {
+            Mode::Pause => Some("pause"),
+            Mode::StepIn => Some("step"),
+            Mode::StepOver(d) if depth <= d => Some("step"),
+            Mode::StepOut(d) if depth < d => Some("step"),
+            _ => None,
+        }
+    }
+}
+
+/// The breakpoints in a file.
+struct Breakpoints {
+    /// The source file.
+    source: Source,
+    /// The zero-based lines with breakpoints.
+    lines: HashSet<usize>,
+}
+
+/// A frame of the call stack.
+struct Frame {
+    /// The name of the module or function.
+    name: EcoString,
+    /// The span at which evaluation currently is in this frame.
+    span: Span,
+    /// The zero-based line of the span, if the file has breakpoints.
+    line: Option<usize>,
+}
+
+/// How the compilation proceeds.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq)]
+enum Mode {
+    /// Run until a breakpoint is hit.
+    #[default]
+    Run,
+    /// Pause as soon as possible.
+    Pause,
+    /// Pause at the next expression.
+    StepIn,
+    /// Pause at the next expression in a frame at most this deep.
+    StepOver(usize),
+    /// Pause at the next expression in a frame less deep than this.
+    StepOut(usize),
+    /// Run to completion because the client has disconnected.
+    Detached,
+}
+
+/// A command for a paused compilation.
+enum Command {
+    /// Resume in the given mode.
+    Resume(Mode),
+    /// Evaluate an expression in the paused frame.
+    Evaluate(EcoString, Sender<SourceResult<Value>>),
+}
+
+/// Sends messages to the client.
+#[derive(Default, Clone)]
+struct Client(Arc<Mutex<u64>>);
+
+impl Client {
+    /// Responds to a request.
+    fn respond(&self, request: &Json, result: Result<Json, EcoString>) {
+        let mut message = json!({
+            "type": "response",
+            "request_seq": request["seq"],
+            "command": request["command"],
+            "success": result.is_ok(),
+        });
+
+        match result {
+            Ok(Json::Null) => {}
+            Ok(body) => message["body"] = body,
+            Err(error) => message["message"] = json!(error.as_str()),
+        }
+
+        self.send(message);
+    }
+
+    /// Sends an event.
+    fn event(&self, event: &str, body: Json) {
+        self.send(json!({ "type": "event", "event": event, "body": body }));
+    }
+
+    /// Sends a message with the next sequence number.
+    fn send(&self, mut message: Json) {
+        let mut seq = self.0.lock().unwrap();
+        *seq += 1;
+        message["seq"] = json!(*seq);
+
+        let body = message.to_string();
+        let mut stdout = io::stdout().lock();
+        write!(stdout, "Content-Length: {}\r\n\r\n{body}", body.len()).ok();
+        stdout.flush().ok();
+    }
+}
+
+/// Reads a message with a `Content-Length` header. Returns `None` at the end of
+/// the input.
+fn read_message(reader: &mut impl BufRead) -> io::Result<Option<Json>> {
+    let mut length = None;
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line)? == 0 {
+            return Ok(None);
+        }
+
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+
+        if let Some(value) = line.strip_prefix("Content-Length:") {
+            length = value.trim().parse().ok();
+        }
+    }
+
+    let length = length.ok_or_else(|| io::Error::other("missing content length"))?;
+    let mut buf = vec![0; length];
+    reader.read_exact(&mut buf)?;
+    serde_json::from_slice(&buf).map(Some).map_err(io::Error::other)
+}
+
+/// Displays a value, shortening it if necessary.
+fn display(value: &Value) -> EcoString {
+    let repr = value.repr();
+    match repr.char_indices().nth(MAX_VALUE_LEN) {
+        Some((i, _)) => eco_format!("{}…", &repr[..i]),
+        None => repr,
+    }
+}
+
+/// Formats the messages of evaluation errors.
+fn format_errors(errors: &[SourceDiagnostic]) -> EcoString {
+    let messages: Vec<&str> = errors.iter().map(|error| error.message.as_str()).collect();
+    messages.join("\n").into()
+}
+
+/// Renders diagnostics of the compilation as plain text.
+fn format_diagnostics(
+    world: &SystemWorld,
+    errors: &[SourceDiagnostic],
+    warnings: &[SourceDiagnostic],
+) -> String {
+    let mut out = NoColor::new(vec![]);
+    typst_kit::diagnostics::emit(
+        &mut out,
+        world,
+        errors.iter().chain(warnings),
+        DiagnosticOptions { trace_args: true, ..Default::default() },
+    )
+    .ok();
+    String::from_utf8_lossy(&out.into_inner()).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use typst::syntax::{LinkedNode, Side};
+
+    use super::*;
+
+    const TEXT: &str = "#let a = 1\n#let b = 2\n#(a + b)\n";
+
+    /// Returns the span of the code at the start of a zero-based line.
+    fn span_at(source: &Source, line: usize) -> Span {
+        let offset = source.lines().line_to_byte(line).unwrap();
+        LinkedNode::new(source.root())
+            .leaf_at(offset + 2, Side::Before)
+            .unwrap()
+            .span()
+    }
+
+    /// Creates a state with breakpoints on the given lines and a call stack of
+    /// the given depth.
+    fn state(source: &Source, lines: &[usize], depth: usize) -> State {
+        let mut state = State::default();
+        let lines = lines.iter().copied().collect();
+        state
+            .breakpoints
+            .insert(source.id(), Breakpoints { source: source.clone(), lines });
+        for _ in 0..depth {
+            state.stack.push(Frame {
+                name: "main".into(),
+                span: Span::detached(),
+                line: None,
+            });
+        }
+        state
+    }
+
+    #[test]
+    fn test_read_message() {
+        let mut input = io::Cursor::new(
+            "Content-Length: 9\r\n\r\n{\"seq\":1}\
+             Content-Type: application/json\r\nContent-Length: 9\r\n\r\n{\"seq\":2}",
+        );
+        assert_eq!(read_message(&mut input).unwrap(), Some(json!({ "seq": 1 })));
+        assert_eq!(read_message(&mut input).unwrap(), Some(json!({ "seq": 2 })));
+        assert_eq!(read_message(&mut input).unwrap(), None);
+    }
+
+    #[test]
+    fn test_read_message_malformed() {
+        let read = |input: &str| read_message(&mut io::Cursor::new(input));
+        assert!(read("\r\n{}").is_err());
+        assert!(read("Content-Length: 10\r\n\r\n{}").is_err());
+        assert!(read("Content-Length: 2\r\n\r\n{]").is_err());
+    }
+
+    #[test]
+    fn test_advance_breakpoint() {
+        let source = Source::detached(TEXT);
+        let mut state = state(&source, &[1], 1);
+        assert_eq!(state.advance(span_at(&source, 0)), None);
+        assert_eq!(state.advance(span_at(&source, 1)), Some("breakpoint"));
+        assert_eq!(state.advance(span_at(&source, 1)), None);
+        assert_eq!(state.advance(span_at(&source, 2)), None);
+        assert_eq!(state.advance(span_at(&source, 1)), Some("breakpoint"));
+        assert_eq!(state.stack[0].line, Some(1));
+    }
+
+    #[test]
+    fn test_advance_stepping() {
+        let source = Source::detached(TEXT);
+        let span = span_at(&source, 0);
+        let mut state = state(&source, &[], 2);
+        assert_eq!(state.advance(span), None);
+
+        state.mode = Mode::Pause;
+        assert_eq!(state.advance(span), Some("pause"));
+
+        state.mode = Mode::StepIn;
+        assert_eq!(state.advance(span), Some("step"));
+
+        state.mode = Mode::StepOver(1);
+        assert_eq!(state.advance(span), None);
+        state.stack.pop();
+        assert_eq!(state.advance(span), Some("step"));
+
+        state.mode = Mode::StepOut(1);
+        assert_eq!(state.advance(span), None);
+        state.stack.pop();
+        assert_eq!(state.advance(span), Some("step"));
+
+        state.mode = Mode::Detached;
+        assert_eq!(state.advance(span), None);
+    }
+}
@@ -55,7 +55,7 @@ pub fn eval(command: &'static EvalCommand) -> HintedStrResult<()> {
                 &world,
                 errors,
                 &warnings,
-                command.process.diagnostic_format,
+                command.process.diagnostic_options(),
             )
             .map_err(|err| eco_format!("failed to print diagnostics ({err})"))?;
         }
@@ -67,7 +67,7 @@ pub fn eval(command: &'static EvalCommand) -> HintedStrResult<()> {
                 &world,
                 &errors,
                 &warnings,
-                command.process.diagnostic_format,
+                command.process.diagnostic_options(),
             )
             .map_err(|err| eco_format!("failed to print diagnostics ({err})"))?;
         }
@@ -1,25 +1,52 @@
 use std::any::Any;
 use std::path::Path;
 
-use typst::text::FontVariant;
-use typst_kit::fonts::{self, FontPath, FontStore};
+use serde::Serialize;
+use typst::diag::StrResult;
+use typst::text::{Coverage, FontInfo, FontStyle, FontVariant};
+use typst_kit::fonts::{self, FontIndex, FontPath, FontStore};
 
 use crate::args::{FontArgs, FontsCommand};
 
 /// Execute a font listing command.
-pub fn fonts(command: &FontsCommand) {
+pub fn fonts(command: &FontsCommand) -> StrResult<()> {
     let fonts = discover_fonts(&command.font);
+    let book = fonts.book();
 
-    for (family, indices) in fonts.book().families() {
+    // Collect the fonts per family, keeping only those that cover the queried
+    // text, if any.
+    let families: Vec<_> = book
+        .families()
+        .map(|(family, indices)| {
+            let infos: Vec<_> = indices
+                .filter_map(|index| Some((index, book.info(index)?)))
+                .filter(|(_, info)| {
+                    command.covers.as_deref().is_none_or(|text| covers(info, text))
+                })
+                .collect();
+            (family, infos)
+        })
+        .filter(|(_, infos)| !infos.is_empty())
+        .collect();
+
+    if let Some(format) = command.format {
+        let entries: Vec<_> = families
+            .iter()
+            .flat_map(|(_, infos)| infos)
+            .map(|&(index, info)| FontEntry::new(info, font_path(&fonts, index)))
+            .collect();
+        let serialized = crate::serialize(&entries, format, command.pretty)?;
+        println!("{serialized}");
+        return Ok(());
+    }
+
+    for (family, infos) in families {
         println!("{family}");
         if command.variants {
-            for index in indices {
-                let Some(info) = fonts.book().info(index) else { continue };
+            for (index, info) in infos {
                 let FontVariant { style, weight, stretch } = info.variant;
-                let path = fonts
-                    .source(index)
-                    .and_then(|source| (source as &dyn Any).downcast_ref::<FontPath>())
-                    .map(|font| font.path.as_path())
+                let path = font_path(&fonts, index)
+                    .map(|path| path.path.as_path())
                     .unwrap_or_else(|| Path::new("<embedded>"))
                     .display();
 
@@ -29,15 +56,94 @@ pub fn fonts(command: &FontsCommand) {
             }
         }
     }
+
+    Ok(())
+}
+
+/// Whether a font covers all non-whitespace characters of the text.
+fn covers(info: &FontInfo, text: &str) -> bool {
+    text.chars()
+        .filter(|c| !c.is_whitespace())
+        .all(|c| info.coverage.contains(c as u32))
+}
+
+/// The location of a font on the file system, if it was loaded from there.
+fn font_path(fonts: &FontStore, index: usize) -> Option<&FontPath> {
+    fonts
+        .source(index)
+        .and_then(|source| (source as &dyn Any).downcast_ref::<FontPath>())
+}
+
+/// A font in machine-readable output.
+#[derive(Serialize)]
+struct FontEntry<'a> {
+    family: &'a str,
+    path: Option<&'a Path>,
+    /// The index in the font collection.
+    index: Option<u32>,
+    embedded: bool,
+    style: FontStyle,
+    weight: u16,
+    stretch: f64,
+    flags: Vec<String>,
+    coverage: CoverageSummary,
+}
+
+impl<'a> FontEntry<'a> {
+    fn new(info: &'a FontInfo, path: Option<&'a FontPath>) -> Self {
+        let FontVariant { style, weight, stretch } = info.variant;
+        Self {
+            family: &info.family,
+            path: path.map(|path| path.path.as_path()),
+            index: path.map(|path| path.index),
+            embedded: path.is_none(),
+            style,
+            weight: weight.to_number(),
+            stretch: stretch.to_ratio().get(),
+            flags: info.flags.iter_names().map(|(name, _)| name.to_lowercase()).collect(),
+            coverage: CoverageSummary::new(&info.coverage),
+        }
+    }
+}
+
+/// A summary of the Unicode codepoints covered by a font.
+#[derive(Serialize)]
+struct CoverageSummary {
+    /// The number of covered codepoints.
+    codepoints: usize,
+    /// Inclusive ranges of covered codepoints.
+    ranges: Vec<[u32; 2]>,
+}
+
+impl CoverageSummary {
+    fn new(coverage: &Coverage) -> Self {
+        let mut codepoints = 0;
+        let mut ranges: Vec<[u32; 2]> = vec![];
+        for c in coverage.iter() {
+            codepoints += 1;
+            match ranges.last_mut() {
+                Some(range) if range[1] + 1 == c => range[1] = c,
+                _ => ranges.push([c, c]),
+            }
+        }
+        Self { codepoints, ranges }
+    }
 }
 
 /// Discovers the fonts as specified by the CLI flags.
 #[typst_macros::time(name = "discover fonts")]
 pub fn discover_fonts(args: &FontArgs) -> FontStore {
     let mut fonts = FontStore::new();
+    let index = args.font_index.clone().map(FontIndex::new).or_else(FontIndex::system);
+    if let Some(mut index) = index {
+        if args.rescan_fonts {
+            index.invalidate();
+        }
+        fonts = fonts.with_index(index);
+    }
 
     if !args.ignore_system_fonts {
-        fonts.extend(fonts::system());
+        fonts.search_system();
     }
 
     #[cfg(feature = "embedded-fonts")]
@@ -46,8 +152,11 @@ pub fn discover_fonts(args: &FontArgs) -> FontStore {
     }
 
     for path in &args.font_paths {
-        fonts.extend(fonts::scan(path));
+        fonts.search_dir(path);
     }
 
+    // Without an up-to-date index, the next run is just slower.
+    fonts.save_index().ok();
+
     fonts
 }
@@ -0,0 +1,108 @@
+use std::path::Path;
+
+use ecow::{EcoString, eco_format};
+use serde::{Deserialize, Serialize};
+use typst::diag::StrResult;
+use typst_kit::packages::PackageLock;
+
+/// The file name of the lockfile.
+const LOCKFILE: &str = "typst.lock";
+
+/// The file name of the project manifest.
+const MANIFEST: &str = "typst.toml";
+
+/// The current version of the lockfile format.
+const VERSION: u32 = 1;
+
+/// The on-disk format of the lockfile.
+#[derive(Serialize, Deserialize)]
+struct Lockfile {
+    /// The version of the lockfile format.
+    version: u32,
+    /// The locked packages.
+    #[serde(default, rename = "package")]
+    packages: Vec<LockedPackage>,
+}
+
+/// A package entry in the lockfile.
+#[derive(Serialize, Deserialize)]
+struct LockedPackage {
+    /// The full package specification, e.g. `@preview/example:0.1.0`.
+    spec: EcoString,
+    /// The content hash of the package directory.
+    hash: EcoString,
+}
+
+/// Loads the lockfile of the project at the given root.
+///
+/// A lock is only maintained if the lockfile already exists or the root
+/// contains a project manifest, but not if it contains a package manifest. If `locked` is set, the lock may not be
+/// updated, and a missing lockfile is treated as an empty one.
+pub fn load(root: &Path, locked: bool) -> StrResult<Option<PackageLock>> {
+    let path = root.join(LOCKFILE);
+    let lock = match std::fs::read_to_string(&path) {
+        Ok(text) => {
+            let file: Lockfile = toml::from_str(&text).map_err(|err| {
+                eco_format!("failed to parse lockfile at {} ({err})", path.display())
+            })?;
+            if file.version != VERSION {
+                return Err(eco_format!(
+                    "lockfile at {} has unsupported version {}",
+                    path.display(),
+                    file.version,
+                ));
+            }
+            PackageLock::new(file.packages.into_iter().map(|p| (p.spec, p.hash)))
+        }
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
+            if !locked && !is_project_manifest(&root.join(MANIFEST)) {
+                return Ok(None);
+            }
+            PackageLock::default()
+        }
+        Err(err) => {
+            return Err(eco_format!(
+                "failed to read lockfile at {} ({err})",
+                path.display()
+            ));
+        }
+    };
+
+    Ok(Some(if locked { lock.frozen() } else { lock }))
+}
+
+/// Whether the file at the path is a project manifest, i.e. one with a
+/// `[project]` or `[[target]]` section rather than a package manifest.
+fn is_project_manifest(path: &Path) -> bool {
+    std::fs::read_to_string(path)
+        .ok()
+        .and_then(|text| toml::from_str::<toml::Table>(&text).ok())
+        .is_some_and(|table| {
+            table.contains_key("project") || table.contains_key("target")
+        })
+}
+
+/// Writes the lock to the lockfile of the project at the given root.
+pub fn write(root: &Path, lock: &PackageLock) -> StrResult<()> {
+    let file = Lockfile {
+        version: VERSION,
+        packages: lock
+            .packages()
+            .map(|(spec, hash)| LockedPackage { spec: spec.clone(), hash: hash.clone() })
+            .collect(),
+    };
+
+    let body = toml::to_string(&file)
+        .map_err(|err| eco_format!("failed to serialize lockfile ({err})"))?;
+    let text = format!(
+        "# This file is automatically generated by Typst.\n\
+         # It records the exact contents of the packages the project depends on.\n\
+         \n\
+         {body}"
+    );
+
+    let path = root.join(LOCKFILE);
+    std::fs::write(&path, text).map_err(|err| {
+        eco_format!("failed to write lockfile at {} ({err})", path.display())
+    })
+}
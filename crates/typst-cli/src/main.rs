@@ -1,6 +1,8 @@
 mod args;
+mod check;
 mod compile;
 mod completions;
+mod debug;
 mod deps;
 mod download;
 mod eval;
@@ -8,14 +10,18 @@ mod fonts;
 mod greet;
 mod info;
 mod init;
+mod lock;
 mod packages;
+mod project;
 mod query;
 #[cfg(feature = "http-server")]
 mod server;
 mod terminal;
+mod test;
 mod timings;
 #[cfg(feature = "self-update")]
 mod update;
+mod vendor;
 mod watch;
 mod world;
 
@@ -77,9 +83,13 @@ fn dispatch() -> HintedStrResult<()> {
         Command::Compile(command) => crate::compile::compile(&mut timer, command)?,
         Command::Watch(command) => crate::watch::watch(&mut timer, command)?,
         Command::Init(command) => crate::init::init(command)?,
+        Command::Package(command) => crate::packages::package(command)?,
         Command::Query(command) => crate::query::query(command)?,
         Command::Eval(command) => crate::eval::eval(command)?,
-        Command::Fonts(command) => crate::fonts::fonts(command),
+        Command::Debug(command) => crate::debug::debug(command)?,
+        Command::Test(command) => crate::test::test(command)?,
+        Command::Vendor(command) => crate::vendor::vendor(command)?,
+        Command::Fonts(command) => crate::fonts::fonts(command)?,
         Command::Update(command) => crate::update::update(command)?,
         Command::Completions(command) => crate::completions::completions(command),
         Command::Info(command) => crate::info::info(command)?,
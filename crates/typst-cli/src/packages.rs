@@ -1,10 +1,28 @@
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::io::Write;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+
+use codespan_reporting::term;
+use codespan_reporting::term::termcolor::WriteColor;
+use ecow::{EcoString, eco_format};
+use typst::diag::{FileError, HintedStrResult, StrResult, bail};
+use typst::syntax::package::{PackageSpec, VersionlessPackageSpec};
+use typst::syntax::{LinkedNode, SyntaxKind, SyntaxNode, ast};
+use typst_kit::downloader::{Downloader, OfflineDownloader};
 use typst_kit::packages::{FsPackages, SystemPackages, UniversePackages};
+use walkdir::WalkDir;
 
-use crate::args::PackageArgs;
+use crate::args::{
+    PackageArgs, PackageCommand, PackageInfoCommand, PackageListCommand,
+    PackagePruneCommand, PackageRemoveCommand, PackageUpgradeCommand,
+};
+use crate::terminal;
 
 /// Returns a new package storage for the given args.
 pub fn system(args: &PackageArgs) -> SystemPackages {
-    SystemPackages::from_parts(
+    let packages = SystemPackages::from_parts(
         args.package_path
             .clone()
             .map(FsPackages::new)
@@ -13,6 +31,343 @@ pub fn system(args: &PackageArgs) -> SystemPackages {
             .clone()
             .map(FsPackages::new)
             .or_else(FsPackages::system_cache),
-        UniversePackages::new(crate::download::downloader()),
-    )
+        UniversePackages::new(downloader(args)),
+    );
+
+    args.package_registries
+        .iter()
+        .fold(packages, |packages, (namespace, location)| {
+            packages.with_registry(UniversePackages::registry(
+                downloader(args),
+                namespace.as_str(),
+                location.as_str(),
+            ))
+        })
+}
+
+/// Returns a downloader for packages, which refuses all downloads in offline
+/// mode.
+fn downloader(args: &PackageArgs) -> Box<dyn Downloader> {
+    if args.offline {
+        Box::new(OfflineDownloader)
+    } else {
+        Box::new(crate::download::downloader())
+    }
+}
+
+/// Execute a package command.
+pub fn package(command: &PackageCommand) -> HintedStrResult<()> {
+    match command {
+        PackageCommand::List(command) => list(command),
+        PackageCommand::Info(command) => info(command),
+        PackageCommand::Remove(command) => remove(command),
+        PackageCommand::Prune(command) => prune(command),
+        PackageCommand::Upgrade(command) => upgrade(command),
+        PackageCommand::Check(command) => crate::check::check(command),
+    }
+}
+
+/// Lists the packages in the data and cache directories along with their
+/// sizes.
+fn list(command: &PackageListCommand) -> HintedStrResult<()> {
+    let packages = system(&command.package);
+    let dirs = [("data", packages.data()), ("cache", packages.cache())];
+
+    let mut out = std::io::stdout().lock();
+    for (kind, dir) in dirs {
+        let Some(dir) = dir else { continue };
+        let specs = sorted(dir.list());
+        let width = specs.iter().map(|spec| spec.to_string().len()).max().unwrap_or(0);
+
+        _ = writeln!(out, "{kind} ({})", dir.path().display());
+        for spec in &specs {
+            let size = format_size(dir_size(&package_dir(dir, spec)));
+            let spec = spec.to_string();
+            _ = writeln!(out, "  {spec:<width$}  {size}");
+        }
+        if specs.is_empty() {
+            _ = writeln!(out, "  (no packages)");
+        }
+    }
+
+    Ok(())
+}
+
+/// Prints the manifest of a package.
+fn info(command: &PackageInfoCommand) -> HintedStrResult<()> {
+    let packages = system(&command.package);
+
+    // If the user didn't specify the version, we try to figure it out
+    // automatically by downloading the package index or searching the disk.
+    let spec: PackageSpec = command.spec.parse().or_else(|err| {
+        let spec: VersionlessPackageSpec = command.spec.parse().map_err(|_| err)?;
+        let version = packages.latest_version(&spec)?;
+        StrResult::Ok(spec.at(version))
+    })?;
+
+    let root = packages.obtain(&spec)?;
+    let path = root.path().join("typst.toml");
+    let manifest = std::fs::read_to_string(&path).map_err(|err| {
+        eco_format!(
+            "failed to read package manifest ({})",
+            FileError::from_io(err, &path)
+        )
+    })?;
+
+    println!("# {spec} (at {})", root.path().display());
+    print!("{manifest}");
+    if !manifest.ends_with('\n') {
+        println!();
+    }
+
+    Ok(())
+}
+
+/// Removes one or all versions of a package.
+fn remove(command: &PackageRemoveCommand) -> HintedStrResult<()> {
+    let packages = system(&command.package);
+    let dirs = packages
+        .cache()
+        .into_iter()
+        .chain(packages.data().filter(|_| command.data));
+
+    // Find all matching versions.
+    let mut targets = vec![];
+    if let Ok(spec) = command.spec.parse::<PackageSpec>() {
+        for dir in dirs {
+            if dir.obtain(&spec).is_some() {
+                targets.push((dir, spec.clone()));
+            }
+        }
+    } else {
+        let spec: VersionlessPackageSpec = command.spec.parse()?;
+        for dir in dirs {
+            for found in sorted(dir.list()) {
+                if found.versionless() == spec {
+                    targets.push((dir, found));
+                }
+            }
+        }
+    }
+
+    if targets.is_empty() {
+        if command.data {
+            bail!("package {} is not stored locally", command.spec);
+        }
+        bail!(
+            "package {} is not cached", command.spec;
+            hint: "pass `--data` to also remove packages from the data directory";
+        );
+    }
+
+    for (dir, spec) in targets {
+        remove_package(dir, &spec)?;
+    }
+
+    Ok(())
+}
+
+/// Removes old or all versions of packages from the cache.
+fn prune(command: &PackagePruneCommand) -> HintedStrResult<()> {
+    let packages = system(&command.package);
+    let Some(cache) = packages.cache() else {
+        bail!("no package cache directory is available");
+    };
+
+    let specs = sorted(cache.list());
+    let mut removed = 0;
+    let mut freed = 0;
+    for (i, spec) in specs.iter().enumerate() {
+        // The specs are sorted by version, so a newer version directly
+        // follows.
+        let outdated = specs
+            .get(i + 1)
+            .is_some_and(|next| next.versionless() == spec.versionless());
+        if command.all || outdated {
+            freed += remove_package(cache, spec)?;
+            removed += 1;
+        }
+    }
+
+    println!("removed {removed} package(s), freeing {}", format_size(freed));
+    Ok(())
+}
+
+/// Reports or rewrites package imports for which newer versions exist.
+fn upgrade(command: &PackageUpgradeCommand) -> HintedStrResult<()> {
+    let packages = system(&command.package);
+    let dir = match &command.dir {
+        Some(dir) => dir.clone(),
+        None => std::env::current_dir().map_err(|err| {
+            eco_format!("failed to determine working directory ({err})")
+        })?,
+    };
+
+    let vendor = crate::vendor::dir(&dir);
+    let mut latest = HashMap::new();
+    let mut outdated = 0;
+
+    for entry in WalkDir::new(&dir)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_entry(|entry| entry.path() != vendor)
+    {
+        let entry = entry
+            .map_err(|err| eco_format!("failed to read project directory ({err})"))?;
+        let path = entry.path();
+        if !entry.file_type().is_file() || path.extension().is_none_or(|ext| ext != "typ")
+        {
+            continue;
+        }
+
+        let text = std::fs::read_to_string(path)
+            .map_err(|err| eco_format!("failed to read {} ({err})", path.display()))?;
+
+        let mut edits = vec![];
+        for (range, spec) in package_imports(&text) {
+            let versionless = spec.versionless();
+            let version = match latest
+                .entry(versionless.clone())
+                .or_insert_with(|| packages.latest_version(&versionless))
+            {
+                Ok(version) => *version,
+                Err(err) => {
+                    warn(eco_format!("failed to check {spec} for updates ({err})"));
+                    continue;
+                }
+            };
+
+            if version <= spec.version {
+                continue;
+            }
+
+            let relative = path.strip_prefix(&dir).unwrap_or(path);
+            let line = text[..range.start].matches('\n').count() + 1;
+            println!("{}:{line}: {spec} -> {version}", relative.display());
+            edits.push((range, versionless.at(version)));
+            outdated += 1;
+        }
+
+        if command.write && !edits.is_empty() {
+            let mut text = text;
+            for (range, spec) in edits.into_iter().rev() {
+                text.replace_range(range, &eco_format!("\"{spec}\""));
+            }
+            std::fs::write(path, text).map_err(|err| {
+                eco_format!("failed to write {} ({err})", path.display())
+            })?;
+        }
+    }
+
+    if outdated == 0 {
+        println!("all package imports are up to date");
+    } else if !command.write {
+        println!("{outdated} import(s) are outdated; pass `--write` to upgrade them");
+    }
+
+    Ok(())
+}
+
+/// Finds all imports and includes of a specific package version in Typst
+/// source code along with the range of the string literal naming the package.
+fn package_imports(text: &str) -> Vec<(Range<usize>, PackageSpec)> {
+    imports(&typst::syntax::parse(text))
+        .into_iter()
+        .filter_map(|(range, source)| Some((range, source.parse().ok()?)))
+        .collect()
+}
+
+/// Finds all imports and includes from string literals in a syntax tree along
+/// with the range of the string literal.
+pub fn imports(root: &SyntaxNode) -> Vec<(Range<usize>, EcoString)> {
+    fn visit(node: &LinkedNode, imports: &mut Vec<(Range<usize>, EcoString)>) {
+        if matches!(node.kind(), SyntaxKind::ModuleImport | SyntaxKind::ModuleInclude)
+            && let Some(source) = node.children().find(|c| c.kind() == SyntaxKind::Str)
+            && let Some(string) = source.get().cast::<ast::Str>()
+        {
+            imports.push((source.range(), string.get()));
+        }
+
+        for child in node.children() {
+            visit(&child, imports);
+        }
+    }
+
+    let mut imports = vec![];
+    visit(&LinkedNode::new(root), &mut imports);
+    imports
+}
+
+/// Removes a package from a package directory and returns the number of freed
+/// bytes.
+fn remove_package(dir: &FsPackages, spec: &PackageSpec) -> StrResult<u64> {
+    let path = package_dir(dir, spec);
+    let size = dir_size(&path);
+    std::fs::remove_dir_all(&path)
+        .map_err(|err| eco_format!("failed to remove {spec} ({err})"))?;
+
+    // Clean up the package's directory if this was its last version.
+    if let Some(parent) = path.parent() {
+        _ = std::fs::remove_dir(parent);
+    }
+
+    println!("removed {spec} ({})", format_size(size));
+    Ok(size)
+}
+
+/// Sorts package specs by namespace, name, and version.
+fn sorted(mut specs: Vec<PackageSpec>) -> Vec<PackageSpec> {
+    specs.sort_by(|a, b| {
+        (&a.namespace, &a.name, a.version).cmp(&(&b.namespace, &b.name, b.version))
+    });
+    specs
+}
+
+/// The directory of a package within a package directory.
+fn package_dir(dir: &FsPackages, spec: &PackageSpec) -> PathBuf {
+    dir.path()
+        .join(spec.namespace.as_str())
+        .join(spec.name.as_str())
+        .join(spec.version.to_string())
+}
+
+/// The total size of all files in a directory.
+fn dir_size(path: &Path) -> u64 {
+    WalkDir::new(path)
+        .into_iter()
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| entry.metadata().ok())
+        .filter(|metadata| metadata.is_file())
+        .map(|metadata| metadata.len())
+        .sum()
+}
+
+/// Formats a size as a unit of bytes.
+pub fn format_size(size: u64) -> impl Display {
+    const KI: f64 = 1024.0;
+    const MI: f64 = KI * KI;
+    const GI: f64 = KI * KI * KI;
+
+    let size = size as f64;
+    typst::utils::display(move |f| {
+        if size >= GI {
+            write!(f, "{:.1} GiB", size / GI)
+        } else if size >= MI {
+            write!(f, "{:.1} MiB", size / MI)
+        } else if size >= KI {
+            write!(f, "{:.1} KiB", size / KI)
+        } else {
+            write!(f, "{size} B")
+        }
+    })
+}
+
+/// Prints a warning.
+fn warn(message: EcoString) {
+    let styles = term::Styles::default();
+    let mut out = terminal::out();
+    _ = out.set_color(&styles.header_warning);
+    _ = write!(out, "warning");
+    _ = out.reset();
+    _ = writeln!(out, ": {message}");
 }
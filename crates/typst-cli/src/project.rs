@@ -0,0 +1,162 @@
+use std::path::{Path, PathBuf};
+
+use clap::ValueEnum;
+use ecow::eco_format;
+use typst::diag::{HintedStrResult, StrResult, bail};
+use typst::syntax::package::{ProjectManifest, TargetInfo};
+
+use crate::args::{CompileArgs, Feature, Input, Output, OutputFormat, PdfStandard};
+
+/// The file name of the project manifest.
+const MANIFEST: &str = "typst.toml";
+
+/// Resolves the arguments for each document that should be compiled.
+///
+/// If an input file was given, these are just the arguments from the command
+/// line. Otherwise, the targets declared in the project manifest of the
+/// working directory (or one of its ancestors) are compiled, with settings
+/// from the command line taking precedence over the manifest.
+pub fn resolve(args: &'static CompileArgs) -> HintedStrResult<Vec<&'static CompileArgs>> {
+    if args.input.is_some() {
+        return Ok(vec![args]);
+    }
+
+    let Some((dir, manifest)) = find()? else {
+        bail!(
+            "no input file was specified";
+            hint: "pass an input file or declare targets in a `{MANIFEST}` file";
+        );
+    };
+
+    if manifest.targets.is_empty() {
+        bail!(
+            "project manifest at {} declares no targets",
+            dir.join(MANIFEST).display();
+            hint: "add a `[[target]]` section with an `input` file";
+        );
+    }
+
+    // All targets would be written to the same file.
+    if args.output.is_some() && manifest.targets.len() > 1 {
+        bail!(
+            "cannot write {} targets to a single output file",
+            manifest.targets.len();
+            hint: "set the `output` of each target in `{MANIFEST}` instead";
+        );
+    }
+
+    manifest
+        .targets
+        .iter()
+        .map(|target| {
+            let resolved = apply(args, &dir, &manifest, target)?;
+            // Like the parsed command line arguments, the resolved arguments
+            // live for the rest of the program.
+            Ok(&*Box::leak(Box::new(resolved)))
+        })
+        .collect()
+}
+
+/// Finds and parses the project manifest closest to the working directory.
+///
+/// Returns the manifest along with the directory containing it.
+fn find() -> StrResult<Option<(PathBuf, ProjectManifest)>> {
+    let cwd = std::env::current_dir()
+        .map_err(|err| eco_format!("failed to determine working directory ({err})"))?;
+
+    let Some(dir) = cwd.ancestors().find(|dir| dir.join(MANIFEST).is_file()) else {
+        return Ok(None);
+    };
+
+    let path = dir.join(MANIFEST);
+    let text = std::fs::read_to_string(&path).map_err(|err| {
+        eco_format!("failed to read project manifest at {} ({err})", path.display())
+    })?;
+
+    let manifest: ProjectManifest = toml::from_str(&text).map_err(|err| {
+        eco_format!("failed to parse project manifest at {} ({err})", path.display())
+    })?;
+
+    if let Some(key) = manifest.project.unknown_fields.keys().next() {
+        bail!("project manifest contains unknown key `{key}` in `[project]`");
+    }
+
+    for target in &manifest.targets {
+        if let Some(key) = target.unknown_fields.keys().next() {
+            bail!("project manifest contains unknown key `{key}` in `[[target]]`");
+        }
+    }
+
+    Ok(Some((dir.to_path_buf(), manifest)))
+}
+
+/// Fills in the compilation arguments for a target from the manifest.
+fn apply(
+    args: &CompileArgs,
+    dir: &Path,
+    manifest: &ProjectManifest,
+    target: &TargetInfo,
+) -> StrResult<CompileArgs> {
+    let project = &manifest.project;
+    let mut args = args.clone();
+
+    args.input = Some(Input::Path(dir.join(target.input.as_str())));
+
+    if args.output.is_none() {
+        args.output = target
+            .output
+            .as_ref()
+            .map(|path| Output::Path(dir.join(path.as_str())));
+    }
+
+    if args.format.is_none() {
+        args.format = target
+            .format
+            .as_deref()
+            .map(|format| parse::<OutputFormat>(format, "output format"))
+            .transpose()?;
+    }
+
+    if args.pdf_standard.is_empty() {
+        args.pdf_standard = target
+            .pdf_standards
+            .iter()
+            .map(|standard| parse::<PdfStandard>(standard, "PDF standard"))
+            .collect::<StrResult<_>>()?;
+    }
+
+    // The manifest's directory is the default project root.
+    if args.world.root.is_none() {
+        let root = project.root.as_deref().unwrap_or(".");
+        args.world.root = Some(dir.join(root));
+    }
+
+    args.world
+        .font
+        .font_paths
+        .extend(project.font_paths.iter().map(|path| dir.join(path.as_str())));
+
+    // Pairs from the command line come later and thus take precedence.
+    let mut inputs: Vec<_> = project
+        .inputs
+        .iter()
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+        .collect();
+    inputs.append(&mut args.world.inputs);
+    args.world.inputs = inputs;
+
+    for feature in &project.features {
+        let feature = parse::<Feature>(feature, "feature")?;
+        if !args.process.features.contains(&feature) {
+            args.process.features.push(feature);
+        }
+    }
+
+    Ok(args)
+}
+
+/// Parses a value the same way as the corresponding command line argument.
+fn parse<T: ValueEnum>(value: &str, what: &str) -> StrResult<T> {
+    T::from_str(value, false)
+        .map_err(|_| eco_format!("project manifest contains invalid {what} `{value}`"))
+}
@@ -42,8 +42,13 @@ pub fn query(command: &'static QueryCommand) -> HintedStrResult<()> {
             let data = retrieve(&world, command, &introspector)?;
             let serialized = format(data, command)?;
             println!("{serialized}");
-            print_diagnostics(&world, &[], &warnings, command.process.diagnostic_format)
-                .map_err(|err| eco_format!("failed to print diagnostics ({err})"))?;
+            print_diagnostics(
+                &world,
+                &[],
+                &warnings,
+                command.process.diagnostic_options(),
+            )
+            .map_err(|err| eco_format!("failed to print diagnostics ({err})"))?;
         }
 
         // Print diagnostics.
@@ -53,7 +58,7 @@ pub fn query(command: &'static QueryCommand) -> HintedStrResult<()> {
                 &world,
                 &errors,
                 &warnings,
-                command.process.diagnostic_format,
+                command.process.diagnostic_options(),
             )
             .map_err(|err| eco_format!("failed to print diagnostics ({err})"))?;
         }
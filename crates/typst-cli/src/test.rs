@@ -0,0 +1,310 @@
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use codespan_reporting::term;
+use codespan_reporting::term::termcolor::WriteColor;
+use ecow::{EcoVec, eco_format, eco_vec};
+use tiny_skia as sk;
+use typst::diag::{HintedStrResult, SourceDiagnostic, StrResult, Warned, bail, error};
+use typst::foundations::Smart;
+use typst::layout::{Abs, Frame, FrameItem, PagedDocument};
+use typst::syntax::{FileId, Span};
+use typst::visualize::Color;
+use walkdir::WalkDir;
+
+use crate::args::{ReferenceFormat, TestCommand};
+use crate::compile::print_diagnostics;
+use crate::world::SystemWorld;
+use crate::{set_failed, terminal};
+
+/// Execute a test command.
+pub fn test(command: &'static TestCommand) -> HintedStrResult<()> {
+    let mut world = SystemWorld::new(None, &command.world, &command.process)?;
+
+    let dir = world.root().join("tests");
+    let tests = collect(&world, &dir, &command.filter)?;
+    if tests.is_empty() {
+        bail!(
+            "found no tests in {}", dir.display();
+            hint: "tests are Typst files in the `tests` directory of the project root";
+        );
+    }
+
+    let mut summary = Summary::default();
+    for test in &tests {
+        world.reset();
+        world.set_main(test.id);
+
+        let Warned { output, warnings } = typst::compile::<PagedDocument>(&world);
+        let outcome = match output {
+            Ok(document) => check(command, &world, test, &document)?,
+            Err(errors) => Outcome::Failed(errors),
+        };
+
+        print_outcome(&test.name, &outcome)
+            .map_err(|err| eco_format!("failed to print test outcome ({err})"))?;
+
+        let errors = match &outcome {
+            Outcome::Failed(errors) => errors.as_slice(),
+            _ => &[],
+        };
+
+        if !errors.is_empty() || !warnings.is_empty() {
+            print_diagnostics(
+                &world,
+                errors,
+                &warnings,
+                command.process.diagnostic_options(),
+            )
+            .map_err(|err| eco_format!("failed to print diagnostics ({err})"))?;
+        }
+
+        summary.record(&outcome);
+    }
+
+    summary
+        .print()
+        .map_err(|err| eco_format!("failed to print test summary ({err})"))?;
+
+    if summary.failed > 0 {
+        set_failed();
+    }
+
+    Ok(())
+}
+
+/// A test discovered in the `tests` directory.
+struct Test {
+    /// The path of the test file relative to the `tests` directory, without
+    /// extension.
+    name: String,
+    /// The path of the test file.
+    path: PathBuf,
+    /// The file ID of the test file.
+    id: FileId,
+}
+
+/// Collects all tests in the directory whose name matches the filter.
+fn collect(world: &SystemWorld, dir: &Path, filter: &[String]) -> StrResult<Vec<Test>> {
+    let mut tests = vec![];
+    if !dir.is_dir() {
+        return Ok(tests);
+    }
+
+    for entry in WalkDir::new(dir).sort_by_file_name() {
+        let entry =
+            entry.map_err(|err| eco_format!("failed to read test directory ({err})"))?;
+
+        let path = entry.path();
+        if !entry.file_type().is_file()
+            || path.extension().is_none_or(|ext| ext != "typ")
+            || entry.file_name().to_string_lossy().starts_with('_')
+        {
+            continue;
+        }
+
+        let name = path
+            .strip_prefix(dir)
+            .unwrap_or(path)
+            .with_extension("")
+            .components()
+            .map(|component| component.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
+
+        if !filter.is_empty() && !filter.iter().any(|part| name.contains(part.as_str())) {
+            continue;
+        }
+
+        let id = world
+            .id(path)
+            .ok_or_else(|| eco_format!("test {name} is not within the project root"))?;
+
+        tests.push(Test { name, path: path.to_path_buf(), id });
+    }
+
+    Ok(tests)
+}
+
+/// The outcome of a test.
+enum Outcome {
+    /// The test compiled and its output matched the reference.
+    Passed,
+    /// The reference was created, updated, or removed to match the output.
+    Updated,
+    /// The test failed with the given errors.
+    Failed(EcoVec<SourceDiagnostic>),
+}
+
+/// Checks the output of a successfully compiled test against its reference
+/// image, updating the reference if requested.
+fn check(
+    command: &TestCommand,
+    world: &SystemWorld,
+    test: &Test,
+    document: &PagedDocument,
+) -> StrResult<Outcome> {
+    let path = test.path.with_extension(command.format.extension());
+    let old = match fs::read(&path) {
+        Ok(data) => Some(data),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => None,
+        Err(err) => bail!("failed to read reference image ({err})"),
+    };
+
+    // Tests without visible output don't need a reference.
+    let live = (!is_empty(document)).then(|| Live::new(command, document));
+    let matches = match (&old, &live) {
+        (None, None) => true,
+        (Some(old), Some(live)) => live.matches(old),
+        _ => false,
+    };
+
+    if matches {
+        return Ok(Outcome::Passed);
+    }
+
+    if command.update {
+        match live {
+            Some(live) => fs::write(&path, live.encode()?)
+                .map_err(|err| eco_format!("failed to write reference image ({err})"))?,
+            None => fs::remove_file(&path)
+                .map_err(|err| eco_format!("failed to remove reference image ({err})"))?,
+        }
+        return Ok(Outcome::Updated);
+    }
+
+    let display = pathdiff::diff_paths(&path, world.workdir()).unwrap_or(path);
+    let display = display.display();
+    let error = match (old, live) {
+        (None, _) => error!(
+            Span::detached(),
+            "missing reference image {display}";
+            hint: "run `typst test --update` to create it";
+        ),
+        (Some(_), None) => error!(
+            Span::detached(),
+            "test has no visible output, but has reference image {display}";
+            hint: "run `typst test --update` to remove it";
+        ),
+        (Some(_), Some(_)) => error!(
+            Span::detached(),
+            "output does not match reference image {display}";
+            hint: "run `typst test --update` to accept the new output";
+        ),
+    };
+
+    Ok(Outcome::Failed(eco_vec![error]))
+}
+
+/// The rendered output of a test.
+enum Live {
+    Png(sk::Pixmap),
+    Svg(String),
+}
+
+impl Live {
+    /// Renders all pages of the document into a single image.
+    fn new(command: &TestCommand, document: &PagedDocument) -> Self {
+        let gap = Abs::pt(1.0);
+        match command.format {
+            ReferenceFormat::Png => Self::Png(typst_render::render_merged(
+                document,
+                command.ppi / 72.0,
+                gap,
+                Some(Color::BLACK),
+            )),
+            ReferenceFormat::Svg => Self::Svg(typst_svg::svg_merged(document, gap)),
+        }
+    }
+
+    /// Whether the output matches the reference image.
+    ///
+    /// Rendered pixels may deviate slightly to account for differences in
+    /// floating point arithmetic between platforms.
+    fn matches(&self, reference: &[u8]) -> bool {
+        match self {
+            Self::Png(live) => sk::Pixmap::decode_png(reference).is_ok_and(|old| {
+                old.width() == live.width()
+                    && old.height() == live.height()
+                    && old
+                        .data()
+                        .iter()
+                        .zip(live.data())
+                        .all(|(&a, &b)| a.abs_diff(b) <= 1)
+            }),
+            Self::Svg(live) => live.as_bytes() == reference,
+        }
+    }
+
+    /// Encodes the output as a reference image.
+    fn encode(&self) -> StrResult<Vec<u8>> {
+        match self {
+            Self::Png(live) => live
+                .encode_png()
+                .map_err(|err| eco_format!("failed to encode PNG file ({err})")),
+            Self::Svg(live) => Ok(live.clone().into_bytes()),
+        }
+    }
+}
+
+/// Whether the document has no visible output.
+fn is_empty(document: &PagedDocument) -> bool {
+    fn is_empty_frame(frame: &Frame) -> bool {
+        frame.items().all(|(_, item)| match item {
+            FrameItem::Group(group) => is_empty_frame(&group.frame),
+            FrameItem::Tag(_) => true,
+            _ => false,
+        })
+    }
+
+    document.pages.iter().all(|page| {
+        !matches!(page.fill, Smart::Custom(Some(_))) && is_empty_frame(&page.frame)
+    })
+}
+
+/// Prints the outcome of a single test.
+fn print_outcome(name: &str, outcome: &Outcome) -> io::Result<()> {
+    let styles = term::Styles::default();
+    let (label, color) = match outcome {
+        Outcome::Passed => ("pass", &styles.header_note),
+        Outcome::Updated => ("update", &styles.header_warning),
+        Outcome::Failed(_) => ("fail", &styles.header_error),
+    };
+
+    let mut out = terminal::out();
+    out.set_color(color)?;
+    write!(out, "{label:<6}")?;
+    out.reset()?;
+    writeln!(out, " {name}")
+}
+
+/// Counts the outcomes of all tests.
+#[derive(Default)]
+struct Summary {
+    passed: usize,
+    updated: usize,
+    failed: usize,
+}
+
+impl Summary {
+    /// Records the outcome of a test.
+    fn record(&mut self, outcome: &Outcome) {
+        match outcome {
+            Outcome::Passed => self.passed += 1,
+            Outcome::Updated => self.updated += 1,
+            Outcome::Failed(_) => self.failed += 1,
+        }
+    }
+
+    /// Prints the number of tests with each outcome.
+    fn print(&self) -> io::Result<()> {
+        let mut out = terminal::out();
+        writeln!(out)?;
+        write!(out, "{} passed, {} failed", self.passed, self.failed)?;
+        if self.updated > 0 {
+            write!(out, ", {} updated", self.updated)?;
+        }
+        writeln!(out)
+    }
+}
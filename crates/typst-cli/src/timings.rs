@@ -1,18 +1,26 @@
 use std::fs::File;
-use std::io::BufWriter;
+use std::io::{self, BufWriter, Write};
 use std::path::{Path, PathBuf};
 
+use ecow::{EcoString, eco_format};
+use rustc_hash::FxHashMap;
+use serde::Serialize;
 use typst::World;
 use typst::diag::{HintedStrResult, bail};
-use typst::syntax::Span;
+use typst::syntax::ast::AstNode;
+use typst::syntax::{FileId, LinkedNode, Source, Span, SyntaxKind, ast};
+use typst_timing::ProfileEntry;
 
 use crate::args::{CliArguments, Command};
+use crate::terminal;
 use crate::world::SystemWorld;
 
 /// Allows to record timings of function executions.
 pub struct Timer {
     /// Where to save the recorded timings of each compilation step.
     path: Option<PathBuf>,
+    /// Whether and where to report a profile of each compilation.
+    profile: Option<Option<PathBuf>>,
     /// The current watch iteration.
     index: usize,
 }
@@ -21,21 +29,25 @@ impl Timer {
     /// Initializes the timing system and returns a timer that can be used to
     /// record timings for a specific function invocation.
     pub fn new(args: &CliArguments) -> Timer {
-        let record = match &args.command {
-            Command::Compile(command) => command.args.timings.clone(),
-            Command::Watch(command) => command.args.timings.clone(),
-            _ => None,
+        let (record, profile) = match &args.command {
+            Command::Compile(command) => {
+                (command.args.timings.clone(), command.args.profile.clone())
+            }
+            Command::Watch(command) => {
+                (command.args.timings.clone(), command.args.profile.clone())
+            }
+            _ => (None, None),
         };
 
         // Enable event collection.
-        if record.is_some() {
+        if record.is_some() || profile.is_some() {
             typst_timing::enable();
         }
 
         let path =
             record.map(|path| path.unwrap_or_else(|| PathBuf::from("record-{n}.json")));
 
-        Timer { path, index: 0 }
+        Timer { path, profile, index: 0 }
     }
 
     /// Records all timings in `f` and writes them to disk.
@@ -44,47 +56,324 @@ impl Timer {
         world: &mut SystemWorld,
         f: impl FnOnce(&mut SystemWorld) -> T,
     ) -> HintedStrResult<T> {
-        let Some(path) = &self.path else {
+        if self.path.is_none() && self.profile.is_none() {
             return Ok(f(world));
-        };
+        }
 
         typst_timing::clear();
 
-        let string = path.to_str().unwrap_or_default();
-        let numbered = string.contains("{n}");
-        if !numbered && self.index > 0 {
-            bail!("cannot export multiple recordings without `{{n}}` in path");
-        }
+        let path = match &self.path {
+            Some(path) => {
+                let string = path.to_str().unwrap_or_default();
+                let numbered = string.contains("{n}");
+                if !numbered && self.index > 0 {
+                    bail!("cannot export multiple recordings without `{{n}}` in path");
+                }
 
-        let storage;
-        let path = if numbered {
-            storage = string.replace("{n}", &self.index.to_string());
-            Path::new(&storage)
-        } else {
-            path.as_path()
+                Some(if numbered {
+                    PathBuf::from(string.replace("{n}", &self.index.to_string()))
+                } else {
+                    path.clone()
+                })
+            }
+            None => None,
         };
 
         let output = f(world);
         self.index += 1;
 
-        let file =
-            File::create(path).map_err(|e| format!("failed to create file: {e}"))?;
-        let writer = BufWriter::with_capacity(1 << 20, file);
+        if let Some(path) = path {
+            let file =
+                File::create(path).map_err(|e| format!("failed to create file: {e}"))?;
+            let writer = BufWriter::with_capacity(1 << 20, file);
+
+            typst_timing::export_json(writer, |span| {
+                resolve_span(world, Span::from_raw(span))
+                    .unwrap_or_else(|| ("unknown".to_string(), 0))
+            })?;
+        }
 
-        typst_timing::export_json(writer, |span| {
-            resolve_span(world, Span::from_raw(span))
-                .unwrap_or_else(|| ("unknown".to_string(), 0))
-        })?;
+        if let Some(profile) = &self.profile {
+            let rows = profile_rows(world);
+            match profile {
+                Some(path) => write_profile(path, &rows)?,
+                None => print_profile(&rows)
+                    .map_err(|err| eco_format!("failed to print profile ({err})"))?,
+            }
+        }
 
         Ok(output)
     }
 }
 
+/// Aggregated timings of a function or a piece of syntax.
+#[derive(Serialize)]
+struct ProfileRow {
+    /// What was timed, e.g. `function`, `call`, or `block`.
+    kind: &'static str,
+    /// The name of the function or the source text of the call.
+    name: EcoString,
+    /// The file in which the function is defined or the syntax is located.
+    file: Option<String>,
+    /// The line of the function definition or syntax.
+    line: Option<u32>,
+    /// How often the function was called or the syntax was processed.
+    calls: u64,
+    /// The time spent, including nested work, in milliseconds.
+    inclusive: f64,
+    /// The time spent, excluding nested work, in milliseconds.
+    exclusive: f64,
+}
+
+/// Aggregates the recorded timings into profile rows, sorted by exclusive
+/// time.
+fn profile_rows(world: &SystemWorld) -> Vec<ProfileRow> {
+    aggregate_rows(typst_timing::profile(), |id| world.source(id).ok())
+}
+
+/// Aggregates profile entries into rows, sorted by exclusive time.
+///
+/// Calls of the same function are merged across call sites. User-defined
+/// functions are identified by their definition site, native functions by
+/// name.
+fn aggregate_rows(
+    entries: Vec<ProfileEntry>,
+    source: impl Fn(FileId) -> Option<Source>,
+) -> Vec<ProfileRow> {
+    let mut rows: Vec<ProfileRow> = vec![];
+    let mut indices = FxHashMap::default();
+
+    for entry in entries {
+        let span = entry.span.map(Span::from_raw).unwrap_or_else(Span::detached);
+        let (kind, name, location) = match entry.name {
+            "func call" => match function_name(&source, span) {
+                Some((name, true)) => ("function", name, locate(&source, span)),
+                Some((name, false)) => ("function", name, None),
+                None => ("function", "unknown".into(), None),
+            },
+            "call" => (
+                "call",
+                callee_text(&source, span).unwrap_or_else(|| "unknown".into()),
+                locate(&source, span),
+            ),
+            name => (name, name.into(), locate(&source, span)),
+        };
+
+        let (file, line) = location.unzip();
+        let key = (kind, name.clone(), file.clone(), line);
+        let index = *indices.entry(key).or_insert_with(|| {
+            rows.push(ProfileRow {
+                kind,
+                name,
+                file,
+                line,
+                calls: 0,
+                inclusive: 0.0,
+                exclusive: 0.0,
+            });
+            rows.len() - 1
+        });
+
+        let row = &mut rows[index];
+        row.calls += entry.calls;
+        row.inclusive += entry.inclusive / 1000.0;
+        row.exclusive += entry.exclusive / 1000.0;
+    }
+
+    rows.sort_by(|a, b| b.exclusive.total_cmp(&a.exclusive));
+    rows
+}
+
+/// Determines the name of a called function from its span and whether it is
+/// user-defined.
+///
+/// User-defined functions are spanned with their parameter list (or body for
+/// context expressions), while native functions carry the span of the callee
+/// expression through which they were accessed.
+fn function_name(
+    source: impl Fn(FileId) -> Option<Source>,
+    span: Span,
+) -> Option<(EcoString, bool)> {
+    let source = source(span.id()?)?;
+    let node = LinkedNode::new(source.root()).find(span)?;
+    let parent = node.parent();
+    if let Some(closure) = parent.and_then(|parent| parent.cast::<ast::Closure>())
+        && node.kind() == SyntaxKind::Params
+    {
+        let name = closure.name().map(|name| name.get().clone());
+        return Some((name.unwrap_or_else(|| "closure".into()), true));
+    }
+
+    if parent.is_some_and(|parent| parent.kind() == SyntaxKind::Contextual) {
+        return Some(("context".into(), true));
+    }
+
+    Some((node.get().clone().into_text(), false))
+}
+
+/// The source text of the callee of a function call.
+fn callee_text(
+    source: impl Fn(FileId) -> Option<Source>,
+    span: Span,
+) -> Option<EcoString> {
+    let source = source(span.id()?)?;
+    let node = LinkedNode::new(source.root()).find(span)?;
+    let call = node.cast::<ast::FuncCall>()?;
+    Some(call.callee().to_untyped().clone().into_text())
+}
+
+/// Writes the profile rows to a JSON file.
+fn write_profile(path: &Path, rows: &[ProfileRow]) -> HintedStrResult<()> {
+    let file = File::create(path).map_err(|e| format!("failed to create file: {e}"))?;
+    serde_json::to_writer_pretty(BufWriter::new(file), rows)
+        .map_err(|e| eco_format!("failed to write profile: {e}"))?;
+    Ok(())
+}
+
+/// Prints a table of the most expensive profile rows.
+fn print_profile(rows: &[ProfileRow]) -> io::Result<()> {
+    const LIMIT: usize = 25;
+
+    let mut out = terminal::out();
+    writeln!(
+        out,
+        "{:>10} {:>10} {:>8}  {:<10} {:<24} location",
+        "excl. ms", "incl. ms", "calls", "kind", "name"
+    )?;
+
+    for row in rows.iter().take(LIMIT) {
+        let location = match (&row.file, row.line) {
+            (Some(file), Some(line)) => format!("{file}:{line}"),
+            _ => String::new(),
+        };
+
+        let mut name = row.name.replace(char::is_whitespace, " ");
+        if name.chars().count() > 24 {
+            name = name.chars().take(23).chain(['…']).collect();
+        }
+
+        writeln!(
+            out,
+            "{:>10.1} {:>10.1} {:>8}  {:<10} {:<24} {location}",
+            row.exclusive, row.inclusive, row.calls, row.kind, name,
+        )?;
+    }
+
+    if rows.len() > LIMIT {
+        writeln!(out, "... and {} more entries", rows.len() - LIMIT)?;
+        writeln!(out, "hint: use `--profile=profile.json` to export all entries")?;
+    }
+
+    Ok(())
+}
+
 /// Turns a span into a (file, line) pair.
 fn resolve_span(world: &SystemWorld, span: Span) -> Option<(String, u32)> {
+    locate(|id| world.source(id).ok(), span)
+}
+
+/// Turns a span into a (file, line) pair, looking up sources with `source`.
+fn locate(
+    source: impl Fn(FileId) -> Option<Source>,
+    span: Span,
+) -> Option<(String, u32)> {
     let id = span.id()?;
-    let source = world.source(id).ok()?;
+    let source = source(id)?;
     let range = source.range(span)?;
     let line = source.lines().byte_to_line(range.start)?;
     Some((format!("{id:?}"), line as u32 + 1))
 }
+
+#[cfg(test)]
+mod tests {
+    use typst::syntax::SyntaxNode;
+
+    use super::*;
+
+    const TEXT: &str = "\
+#let double(x) = 2 * x
+#double(1)
+#double(2)
+#text(double(3))
+#text[a]";
+
+    /// Collects all nodes of the given kind in order.
+    fn nodes<'a>(
+        node: &'a SyntaxNode,
+        kind: SyntaxKind,
+        found: &mut Vec<&'a SyntaxNode>,
+    ) {
+        if node.kind() == kind {
+            found.push(node);
+        }
+        for child in node.children() {
+            nodes(child, kind, found);
+        }
+    }
+
+    /// Creates a profile entry.
+    fn entry(
+        name: &'static str,
+        span: Option<Span>,
+        calls: u64,
+        times: (f64, f64),
+    ) -> ProfileEntry {
+        ProfileEntry {
+            name,
+            span: span.map(Span::into_raw),
+            calls,
+            inclusive: times.0,
+            exclusive: times.1,
+        }
+    }
+
+    #[test]
+    fn test_profile_rows() {
+        let source = Source::detached(TEXT);
+        let mut params = vec![];
+        nodes(source.root(), SyntaxKind::Params, &mut params);
+        let mut calls = vec![];
+        nodes(source.root(), SyntaxKind::FuncCall, &mut calls);
+        let call = |i: usize| Some(calls[i].span());
+        let callee = |i: usize| calls[i].children().next().map(SyntaxNode::span);
+
+        let entries = vec![
+            entry("layout", None, 1, (10_000.0, 4_000.0)),
+            entry("func call", Some(params[0].span()), 3, (3_000.0, 3_000.0)),
+            entry("func call", callee(2), 1, (5_000.0, 1_000.0)),
+            entry("call", call(0), 1, (1_000.0, 250.0)),
+            entry("func call", callee(4), 1, (2_000.0, 500.0)),
+            entry("call", call(1), 1, (1_000.0, 200.0)),
+            entry("func call", Some(Span::detached()), 1, (100.0, 100.0)),
+        ];
+
+        let rows =
+            aggregate_rows(entries, |id| (id == source.id()).then(|| source.clone()));
+        let file = format!("{:?}", source.id());
+        let rows: Vec<_> = rows
+            .iter()
+            .map(|row| {
+                let location = row.file.as_deref().zip(row.line);
+                (
+                    row.kind,
+                    row.name.as_str(),
+                    location,
+                    row.calls,
+                    row.inclusive,
+                    row.exclusive,
+                )
+            })
+            .collect();
+        assert_eq!(
+            rows,
+            [
+                ("layout", "layout", None, 1, 10.0, 4.0),
+                ("function", "double", Some((file.as_str(), 1)), 3, 3.0, 3.0),
+                ("function", "text", None, 2, 7.0, 1.5),
+                ("call", "double", Some((file.as_str(), 2)), 1, 1.0, 0.25),
+                ("call", "double", Some((file.as_str(), 3)), 1, 1.0, 0.2),
+                ("function", "unknown", None, 1, 0.1, 0.1),
+            ]
+        );
+    }
+}
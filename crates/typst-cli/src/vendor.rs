@@ -0,0 +1,94 @@
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use codespan_reporting::term;
+use codespan_reporting::term::termcolor::WriteColor;
+use ecow::eco_format;
+use fs_extra::dir::CopyOptions;
+use typst::World;
+use typst::diag::{HintedStrResult, PackageError, Warned};
+use typst::layout::PagedDocument;
+use typst::syntax::package::PackageSpec;
+use typst_html::HtmlDocument;
+use typst_kit::packages::FsPackages;
+
+use crate::args::{Target, VendorCommand};
+use crate::compile::print_diagnostics;
+use crate::set_failed;
+use crate::world::SystemWorld;
+
+/// The name of the directory with vendored packages in the project root.
+const VENDOR: &str = "vendor";
+
+/// Returns the directory with vendored packages for the given project root.
+pub fn dir(root: &Path) -> PathBuf {
+    root.join(VENDOR)
+}
+
+/// Execute a vendor command.
+pub fn vendor(command: &'static VendorCommand) -> HintedStrResult<()> {
+    let mut world =
+        SystemWorld::new(Some(&command.input), &command.world, &command.process)?;
+
+    // Reset everything and ensure that the main file is present.
+    world.reset();
+    world.source(world.main()).map_err(|err| err.to_string())?;
+
+    // Compile the document to discover all (transitively) imported packages.
+    let Warned { output, warnings } = match command.target {
+        Target::Paged => {
+            typst::compile::<PagedDocument>(&world).map(|output| output.map(drop))
+        }
+        Target::Html => {
+            typst::compile::<HtmlDocument>(&world).map(|output| output.map(drop))
+        }
+    };
+
+    let errors = output.err().unwrap_or_default();
+    print_diagnostics(&world, &errors, &warnings, command.process.diagnostic_options())
+        .map_err(|err| eco_format!("failed to print diagnostics ({err})"))?;
+
+    // Without a successful compilation, we can't be sure that we found all
+    // packages.
+    if !errors.is_empty() {
+        set_failed();
+        return Ok(());
+    }
+
+    let mut specs: Vec<_> = world.packages().collect();
+    specs.sort_by_cached_key(|spec| spec.to_string());
+    specs.dedup();
+
+    let vendor = FsPackages::new(dir(world.root()));
+    for spec in specs {
+        // Already vendored packages are served from the vendor directory.
+        if vendor.obtain(&spec).is_some() {
+            continue;
+        }
+
+        let source = world.package_dir(&spec)?;
+        vendor.store(&spec, |target| {
+            fs_extra::dir::copy(&source, target, &CopyOptions::new().content_only(true))
+                .map(drop)
+                .map_err(|err| {
+                    PackageError::Other(Some(eco_format!(
+                        "failed to copy package: {err}"
+                    )))
+                })
+        })?;
+
+        print_vendored(&spec).unwrap();
+    }
+
+    Ok(())
+}
+
+/// Prints that a package was vendored.
+fn print_vendored(spec: &PackageSpec) -> std::io::Result<()> {
+    let styles = term::Styles::default();
+    let mut out = crate::terminal::out();
+    out.set_color(&styles.header_help)?;
+    write!(out, "vendored")?;
+    out.reset()?;
+    writeln!(out, " {spec}")
+}
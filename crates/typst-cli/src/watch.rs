@@ -1,11 +1,15 @@
 use std::io::{self, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
 
 use codespan_reporting::term::termcolor::WriteColor;
 use codespan_reporting::term::{self, termcolor};
 use ecow::eco_format;
-use typst::diag::{HintedStrResult, StrResult, bail, warning};
+use typst::diag::{HintedStrResult, HintedString, StrResult, bail, warning};
+use typst::engine::CancellationToken;
 use typst::syntax::Span;
 use typst::utils::format_duration;
+use typst_kit::diagnostics::DiagnosticFormat;
 use typst_kit::watcher::Watcher;
 
 use crate::args::{Input, Output, WatchCommand};
@@ -16,7 +20,14 @@ use crate::{print_error, terminal};
 
 /// Execute a watching compilation command.
 pub fn watch(timer: &mut Timer, command: &'static WatchCommand) -> HintedStrResult<()> {
-    let mut config = CompileConfig::watching(command)?;
+    let args = match crate::project::resolve(&command.args)?.as_slice() {
+        [args] => *args,
+        _ => bail!(
+            "cannot watch multiple targets at once";
+            hint: "pass the input file of the target to watch";
+        ),
+    };
+    let mut config = CompileConfig::watching(args, command)?;
 
     let Output::Path(output) = &config.output else {
         bail!("cannot write document to stdout in watch mode");
@@ -28,11 +39,7 @@ pub fn watch(timer: &mut Timer, command: &'static WatchCommand) -> HintedStrResu
     // Create the world that serves sources, files, and fonts.
     // Additionally, if any files do not exist, wait until they do.
     let mut world = loop {
-        match SystemWorld::new(
-            Some(&command.args.input),
-            &command.args.world,
-            &command.args.process,
-        ) {
+        match SystemWorld::new(Some(&config.input), &args.world, &args.process) {
             Ok(world) => break world,
             Err(
                 ref err @ (WorldCreationError::InputNotFound(ref path)
@@ -64,24 +71,69 @@ pub fn watch(timer: &mut Timer, command: &'static WatchCommand) -> HintedStrResu
     }
 
     // Recompile whenever something relevant happens.
+    let mut changed = false;
     loop {
-        // Watch all dependencies of the most recent compilation.
-        watcher.update(world.dependencies())?;
-
-        // Wait until anything relevant happens.
-        watcher.wait()?;
+        // If a change already superseded the most recent compilation, its
+        // dependencies may be incomplete, so we keep watching the previous
+        // ones and recompile right away.
+        if !changed {
+            // Watch all dependencies of the most recent compilation.
+            watcher.update(world.dependencies())?;
+
+            // Wait until anything relevant happens.
+            watcher.wait()?;
+        }
 
         // Reset all dependencies.
         world.reset();
 
-        // Recompile.
-        timer.record(&mut world, |world| compile_once(world, &mut config))??;
+        // Recompile, cancelling if something relevant happens meanwhile.
+        changed = compile_cancellable(timer, &mut world, &mut config, &mut watcher)?;
 
         // Evict the cache.
         comemo::evict(10);
     }
 }
 
+/// Compiles once while watching for changes in another thread. If a change
+/// happens during the compilation, it is cancelled.
+///
+/// Returns whether there was a change.
+fn compile_cancellable(
+    timer: &mut Timer,
+    world: &mut SystemWorld,
+    config: &mut CompileConfig,
+    watcher: &mut Watcher,
+) -> HintedStrResult<bool> {
+    /// How often the watching thread checks whether the compilation finished.
+    const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+    let token = CancellationToken::new();
+    let done = AtomicBool::new(false);
+    config.cancellation = Some(token.clone());
+
+    let changed = std::thread::scope(|scope| {
+        let waiting = scope.spawn(|| {
+            while !done.load(Ordering::Relaxed) {
+                if watcher.wait_timeout(POLL_INTERVAL)? {
+                    token.cancel();
+                    return Ok(true);
+                }
+            }
+            Ok(false)
+        });
+
+        let result = timer.record(world, |world| compile_once(world, config));
+        done.store(true, Ordering::Relaxed);
+        let changed: StrResult<bool> = waiting.join().unwrap();
+        result??;
+        Ok::<_, HintedString>(changed?)
+    });
+
+    config.cancellation = None;
+    changed
+}
+
 /// The status in which the watcher can be.
 pub enum Status {
     Compiling,
@@ -93,6 +145,14 @@ pub enum Status {
 impl Status {
     /// Clear the terminal and render the status message.
     pub fn print(&self, config: &CompileConfig) -> io::Result<()> {
+        // Don't interleave machine-readable diagnostics with the status.
+        if matches!(
+            config.diagnostics.format,
+            DiagnosticFormat::Json | DiagnosticFormat::Sarif
+        ) {
+            return Ok(());
+        }
+
         let timestamp = chrono::offset::Local::now().format("%H:%M:%S");
         let color = self.color();
 
@@ -158,6 +218,6 @@ fn warn_watching_std(world: &SystemWorld, config: &CompileConfig) -> StrResult<(
         hint: "to recompile on changes, watch a regular file instead";
         hint: "to compile once and exit, please use `typst compile` instead";
     );
-    print_diagnostics(world, &[], &[warning], config.diagnostic_format)
+    print_diagnostics(world, &[], &[warning], config.diagnostics)
         .map_err(|err| eco_format!("failed to print diagnostics ({err})"))
 }
@@ -1,22 +1,27 @@
+use std::collections::HashSet;
 use std::fmt;
+use std::fs;
 use std::io::{self, Read};
 use std::path::{Path, PathBuf};
 use std::sync::{LazyLock, OnceLock};
 
 use chrono::{DateTime, Datelike, FixedOffset, Local, Utc};
-use ecow::{EcoString, eco_format};
-use typst::diag::{FileError, FileResult};
-use typst::foundations::{Bytes, Datetime, Dict, IntoValue, Repr};
+use ecow::{EcoString, EcoVec, eco_format};
+use typst::diag::{FileError, FileResult, PackageResult, SourceDiagnostic, StrResult};
+use typst::foundations::{Bytes, Datetime, Dict, Fold, IntoValue, Repr, Value};
+use typst::loading::{LoadSource, Loaded, decode_json, decode_toml, decode_yaml};
+use typst::syntax::package::PackageSpec;
 use typst::syntax::{
-    FileId, PathError, RootedPath, Source, VirtualPath, VirtualRoot, VirtualizeError,
+    FileId, PathError, RootedPath, Source, Spanned, VirtualPath, VirtualRoot,
+    VirtualizeError,
 };
-use typst::text::{Font, FontBook};
+use typst::text::{FallbackFonts, Font, FontBook, FontFamily, FontList, TextElem};
 use typst::utils::LazyHash;
 use typst::{Library, LibraryExt, World};
 use typst_kit::diagnostics::DiagnosticWorld;
 use typst_kit::files::{FileLoader, FileStore, FsRoot};
 use typst_kit::fonts::FontStore;
-use typst_kit::packages::SystemPackages;
+use typst_kit::packages::{FsPackages, SystemPackages};
 
 use crate::args::{Feature, Input, ProcessArgs, WorldArgs};
 
@@ -40,7 +45,7 @@ impl SystemWorld {
     /// Creates a new system world.
     pub fn new(
         input: Option<&Input>,
-        world_args: &'static WorldArgs,
+        world_args: &WorldArgs,
         process_args: &ProcessArgs,
     ) -> Result<Self, WorldCreationError> {
         // Set up the thread pool.
@@ -53,13 +58,7 @@ impl SystemWorld {
         }
 
         let library = {
-            // Convert the input pairs to a dictionary.
-            let inputs: Dict = world_args
-                .inputs
-                .iter()
-                .map(|(k, v)| (k.as_str().into(), v.as_str().into_value()))
-                .collect();
-
+            let inputs = sys_inputs(world_args)?;
             let features = process_args
                 .features
                 .iter()
@@ -69,7 +68,15 @@ impl SystemWorld {
                 })
                 .collect();
 
-            Library::builder().with_inputs(inputs).with_features(features).build()
+            let mut library =
+                Library::builder().with_inputs(inputs).with_features(features).build();
+            library
+                .styles
+                .set(TextElem::fallback_fonts, fallback_fonts(world_args)?);
+            if world_args.warn_fallback {
+                library.styles.set(TextElem::warn_fallback, true);
+            }
+            library
         };
 
         let now = match world_args.creation_timestamp {
@@ -80,9 +87,10 @@ impl SystemWorld {
         Ok(Self {
             workdir: std::env::current_dir().ok(),
             library: LazyHash::new(library),
-            fonts: LazyLock::new(Box::new(|| {
-                crate::fonts::discover_fonts(&world_args.font)
-            })),
+            fonts: LazyLock::new({
+                let font = world_args.font.clone();
+                Box::new(move || crate::fonts::discover_fonts(&font))
+            }),
             files: FileStore::new(SystemFiles::new(input, world_args)?),
             now,
         })
@@ -93,11 +101,73 @@ impl SystemWorld {
         self.files.loader().project.path()
     }
 
+    /// Resolves the file system path of a file.
+    pub fn path(&self, id: FileId) -> FileResult<PathBuf> {
+        self.files.loader().resolve(id)
+    }
+
+    /// Resolves the ID of a file in the project root given its path.
+    pub fn id(&self, path: &Path) -> Option<FileId> {
+        let path = path.canonicalize().ok()?;
+        let vpath = VirtualPath::virtualize(self.root(), &path).ok()?;
+        Some(RootedPath::new(VirtualRoot::Project, vpath).intern())
+    }
+
+    /// Changes the main file of the world.
+    pub fn set_main(&mut self, id: FileId) {
+        self.files.loader_mut().main = id;
+    }
+
     /// The current working directory.
     pub fn workdir(&self) -> &Path {
         self.workdir.as_deref().unwrap_or(Path::new("."))
     }
 
+    /// Writes the project's lockfile if the packages resolved since it was
+    /// last written differ from the recorded ones.
+    ///
+    /// Packages that the last compilation did not depend on are removed from
+    /// the lock, so this should only be called after a successful compilation.
+    pub fn write_lock(&mut self) -> StrResult<()> {
+        let used: HashSet<EcoString> =
+            self.packages().map(|spec| eco_format!("{spec}")).collect();
+        let Some(mut lock) = self.files.loader().packages.lock() else {
+            return Ok(());
+        };
+        lock.retain(|spec| used.contains(spec));
+        if lock.is_changed() {
+            crate::lock::write(self.root(), &lock)?;
+            lock.mark_unchanged();
+        }
+        Ok(())
+    }
+
+    /// Returns the warnings about packages whose locked contents were updated,
+    /// clearing them.
+    pub fn take_lock_warnings(&self) -> Vec<EcoString> {
+        self.files
+            .loader()
+            .packages
+            .lock()
+            .map(|mut lock| lock.take_warnings())
+            .unwrap_or_default()
+    }
+
+    /// Return all packages the last compilation depended on.
+    pub fn packages(&mut self) -> impl Iterator<Item = PackageSpec> + '_ {
+        let (_, deps) = self.files.dependencies();
+        deps.filter_map(|id| match id.root() {
+            VirtualRoot::Package(spec) => Some(spec.clone()),
+            VirtualRoot::Project => None,
+        })
+    }
+
+    /// Resolves the directory from which a package is loaded.
+    pub fn package_dir(&self, spec: &PackageSpec) -> PackageResult<PathBuf> {
+        let root = self.files.loader().packages.obtain(spec)?;
+        Ok(root.path().to_path_buf())
+    }
+
     /// Return all paths the last compilation depended on.
     pub fn dependencies(&mut self) -> impl Iterator<Item = PathBuf> + '_ {
         let (loader, deps) = self.files.dependencies();
@@ -216,7 +286,7 @@ impl SystemFiles {
     /// Creates a new loader given the configuration.
     pub fn new(
         input: Option<&Input>,
-        world_args: &'static WorldArgs,
+        world_args: &WorldArgs,
     ) -> Result<Self, WorldCreationError> {
         // Resolve the system-global input path.
         let input_path = match input {
@@ -258,11 +328,15 @@ impl SystemFiles {
             *EMPTY_ID
         };
 
-        Ok(Self {
-            main,
-            project: FsRoot::new(root),
-            packages: crate::packages::system(&world_args.package),
-        })
+        let mut packages = crate::packages::system(&world_args.package)
+            .with_vendor(FsPackages::new(crate::vendor::dir(&root)));
+        if let Some(lock) = crate::lock::load(&root, world_args.locked)
+            .map_err(WorldCreationError::InvalidLock)?
+        {
+            packages = packages.with_lock(lock);
+        }
+
+        Ok(Self { main, project: FsRoot::new(root), packages })
     }
 
     /// Resolves the file system path for the given `id`.
@@ -312,6 +386,88 @@ enum Now {
     System(OnceLock<DateTime<Utc>>),
 }
 
+/// Collects the key-value pairs visible through `sys.inputs`.
+///
+/// Pairs from input files come first so that pairs passed with `--input` and
+/// `--input-json` can override them.
+fn sys_inputs(world_args: &WorldArgs) -> Result<Dict, WorldCreationError> {
+    let mut inputs = Dict::new();
+
+    for path in &world_args.input_files {
+        let display = path.display();
+        let data = fs::read(path).map_err(|err| {
+            WorldCreationError::InvalidInputs(eco_format!(
+                "failed to read input file {display} ({err})"
+            ))
+        })?;
+
+        let loaded = loaded_input(data);
+        let decoded = match path.extension().and_then(|ext| ext.to_str()) {
+            Some("json") => decode_json(&loaded),
+            Some("toml") => decode_toml(&loaded).map(Value::Dict),
+            Some("yaml" | "yml") => decode_yaml(&loaded),
+            _ => {
+                return Err(WorldCreationError::InvalidInputs(eco_format!(
+                    "input file {display} must be a JSON, TOML, or YAML file"
+                )));
+            }
+        };
+
+        let Value::Dict(dict) =
+            decoded.map_err(|errors| invalid_input(&display, errors))?
+        else {
+            return Err(WorldCreationError::InvalidInputs(eco_format!(
+                "input file {display} must contain a dictionary"
+            )));
+        };
+
+        for (key, value) in dict {
+            inputs.insert(key, value);
+        }
+    }
+
+    for (key, value) in &world_args.inputs {
+        inputs.insert(key.as_str().into(), value.as_str().into_value());
+    }
+
+    for (key, json) in &world_args.json_inputs {
+        let loaded = loaded_input(json.clone().into_bytes());
+        let value = decode_json(&loaded).map_err(|errors| invalid_input(key, errors))?;
+        inputs.insert(key.as_str().into(), value);
+    }
+
+    Ok(inputs)
+}
+
+/// Resolves the fallback fonts passed on the command line.
+fn fallback_fonts(world_args: &WorldArgs) -> Result<FallbackFonts, WorldCreationError> {
+    let mut fonts = FallbackFonts::default();
+    for (key, families) in &world_args.fallback_fonts {
+        let target = key.parse().map_err(WorldCreationError::InvalidFallbackFonts)?;
+        let families = families.split(',').map(|family| FontFamily::new(family.trim()));
+        let list = FontList::new(families.collect())
+            .map_err(WorldCreationError::InvalidFallbackFonts)?;
+
+        // Later arguments take precedence over earlier ones for the same key.
+        fonts = FallbackFonts(vec![(target, list)]).fold(fonts);
+    }
+    Ok(fonts)
+}
+
+/// Wraps the data of an input for decoding with the library's data loaders.
+fn loaded_input(data: Vec<u8>) -> Loaded {
+    Loaded::new(Spanned::detached(LoadSource::Bytes), Bytes::new(data))
+}
+
+/// Creates an error for an input that failed to decode.
+fn invalid_input(
+    name: &dyn fmt::Display,
+    errors: EcoVec<SourceDiagnostic>,
+) -> WorldCreationError {
+    let message = errors.first().map(|error| error.message.as_str()).unwrap_or_default();
+    WorldCreationError::InvalidInputs(eco_format!("invalid input {name}: {message}"))
+}
+
 /// An error that occurs during world construction.
 #[derive(Debug)]
 pub enum WorldCreationError {
@@ -321,6 +477,12 @@ pub enum WorldCreationError {
     InputMalformed(VirtualizeError),
     /// The root directory does not appear to exist.
     RootNotFound(PathBuf),
+    /// An input for `sys.inputs` could not be read or decoded.
+    InvalidInputs(EcoString),
+    /// The project's lockfile could not be read.
+    InvalidLock(EcoString),
+    /// The fallback fonts passed on the command line are invalid.
+    InvalidFallbackFonts(EcoString),
     /// Another type of I/O error.
     Io(io::Error),
 }
@@ -346,6 +508,9 @@ impl fmt::Display for WorldCreationError {
             WorldCreationError::RootNotFound(path) => {
                 write!(f, "root directory not found (searched at {})", path.display())
             }
+            WorldCreationError::InvalidInputs(message) => write!(f, "{message}"),
+            WorldCreationError::InvalidLock(message) => write!(f, "{message}"),
+            WorldCreationError::InvalidFallbackFonts(message) => write!(f, "{message}"),
             WorldCreationError::Io(err) => write!(f, "{err}"),
         }
     }
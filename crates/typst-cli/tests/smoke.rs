@@ -1,8 +1,11 @@
 use std::collections::HashSet;
 use std::fmt::{self, Debug, Display, Formatter};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpListener;
 use std::path::{Path, PathBuf};
-use std::process::{Command, Output};
+use std::process::{Child, ChildStdin, ChildStdout, Command, Output, Stdio};
 
+use serde_json::{Value as Json, json};
 use tempfile::TempDir;
 use typst::foundations::Bytes;
 
@@ -65,6 +68,198 @@ fn test_fonts_path() {
     assert_eq!(found, expected);
 }
 
+#[test]
+fn test_fonts_index() {
+    let fonts = tempfs();
+    let cache = tempfs();
+    let index = cache.path().join("fonts.json");
+    let list = || {
+        exec()
+            .arg("fonts")
+            .arg("--ignore-embedded-fonts")
+            .arg("--ignore-system-fonts")
+            .arg("--font-index")
+            .arg(&index)
+            .arg("--font-path")
+            .arg(fonts.path())
+            .must_succeed()
+            .stdout
+    };
+
+    // A changed file must be parsed again instead of being served from the
+    // font index.
+    let mut families = vec![];
+    for data in typst_dev_assets::fonts().take(2) {
+        let font = typst::text::Font::new(Bytes::new(data), 0).unwrap();
+        fonts.write("font.ttf", data);
+        list().must_match_lines([font.info().family.as_str()]);
+        cache.read("fonts.json").must_contain("font.ttf");
+        families.push(font.info().family.clone());
+    }
+    assert_ne!(families[0], families[1]);
+
+    // An unchanged file must be served from the font index without being
+    // parsed again.
+    garble(&fonts.resolve("font.ttf"));
+    list().must_match_lines([families[1].as_str()]);
+}
+
+#[test]
+#[cfg(all(
+    unix,
+    not(any(target_os = "macos", target_os = "android", target_os = "redox"))
+))]
+fn test_fonts_index_system() {
+    let fonts = tempfs();
+    let cache = tempfs();
+    let index = cache.path().join("fonts.json");
+    let data = typst_dev_assets::fonts().next().unwrap();
+    let font = typst::text::Font::new(Bytes::new(data), 0).unwrap();
+    let path = fonts.write("dir/font.ttf", data);
+    let config = fonts.write(
+        "fonts.conf",
+        format!("<fontconfig><dir>{}</dir></fontconfig>", fonts.path().display()),
+    );
+    let list = || {
+        exec()
+            .env("FONTCONFIG_FILE", &config)
+            .arg("fonts")
+            .arg("--ignore-embedded-fonts")
+            .arg("--font-index")
+            .arg(&index)
+            .must_succeed()
+            .stdout
+    };
+
+    // System fonts go through the font index, too.
+    let family = font.info().family.as_str();
+    list().must_match_lines([family]);
+    cache.read("fonts.json").must_contain("font.ttf");
+
+    garble(&path);
+    list().must_match_lines([family]);
+}
+
+#[test]
+#[cfg(unix)]
+fn test_fonts_symlinks() {
+    let fonts = tempfs();
+    let data = typst_dev_assets::fonts().next().unwrap();
+    let font = typst::text::Font::new(Bytes::new(data), 0).unwrap();
+    fonts.write("a/b/font.ttf", data);
+
+    // Neither a loop nor an alias may make the font show up more than once.
+    std::os::unix::fs::symlink(fonts.resolve("a"), fonts.resolve("a/b/loop")).unwrap();
+    std::os::unix::fs::symlink(fonts.resolve("a/b"), fonts.resolve("alias")).unwrap();
+    let output = exec()
+        .arg("fonts")
+        .arg("--ignore-embedded-fonts")
+        .arg("--ignore-system-fonts")
+        .arg("--variants")
+        .arg("--font-path")
+        .arg(fonts.path())
+        .must_succeed()
+        .stdout;
+    let lines: Vec<_> = output.lines().collect();
+    assert_eq!(lines.len(), 2, "{output:?}");
+    assert_eq!(lines[0], font.info().family);
+}
+
+#[test]
+fn test_fonts_format() {
+    let fonts = tempfs();
+    for (i, data) in typst_dev_assets::fonts().enumerate() {
+        fonts.write(format!("{i}.otf"), data);
+    }
+
+    let list = |args: &[&str]| {
+        exec()
+            .arg("fonts")
+            .arg("--ignore-embedded-fonts")
+            .arg("--ignore-system-fonts")
+            .arg("--font-path")
+            .arg(fonts.path())
+            .args(args)
+            .must_succeed()
+            .stdout
+    };
+
+    let covering = list(&["--covers", "你好"]);
+    covering.must_contain("Noto Serif CJK SC");
+    assert!(!covering.contains("Libertinus Serif"));
+
+    list(&["--format", "json", "--covers", "你好"])
+        .must_start_with("[{")
+        .must_contain(r#""family":"Noto Serif CJK SC""#)
+        .must_contain(r#""embedded":false"#);
+
+    list(&["--format", "yaml"]).must_contain("family: Libertinus Serif");
+}
+
+#[test]
+fn test_fonts_woff() {
+    let project = tempfs();
+    project.write(
+        "fonts/regular.woff",
+        include_bytes!("../../../tests/fonts/SourceCodePro-Regular.woff"),
+    );
+    project.write(
+        "fonts/italic.woff2",
+        include_bytes!("../../../tests/fonts/SourceCodePro-It.woff2"),
+    );
+
+    let output = exec()
+        .arg("fonts")
+        .arg("--ignore-embedded-fonts")
+        .arg("--ignore-system-fonts")
+        .arg("--variants")
+        .arg("--font-path")
+        .arg(project.path().join("fonts"))
+        .must_succeed()
+        .stdout;
+    let lines: Vec<_> = output.lines().collect();
+    assert_eq!(lines.len(), 3, "{output:?}");
+    assert_eq!(lines[0], "Source Code Pro");
+    assert!(lines[1..].iter().any(|line| line.contains("Style: Normal")));
+    assert!(lines[1..].iter().any(|line| line.contains("Style: Italic")));
+
+    let main =
+        project.write("main.typ", "#set text(font: \"Source Code Pro\")\nHello _World_");
+    let output = exec()
+        .arg("compile")
+        .arg(main)
+        .arg("--ignore-system-fonts")
+        .arg("--font-path")
+        .arg(project.path().join("fonts"))
+        .must_succeed();
+    assert!(!output.stderr.contains("unknown font family"));
+    project.read("main.pdf").must_start_with("%PDF");
+}
+
+#[test]
+fn test_fallback_fonts() {
+    let project = tempfs();
+    for (i, data) in typst_dev_assets::fonts().enumerate() {
+        project.write(format!("fonts/{i}.otf"), data);
+    }
+
+    let main =
+        project.write("main.typ", "#set text(font: \"Libertinus Serif\")\nHello 你好");
+    let output = exec()
+        .arg("compile")
+        .arg(main)
+        .arg("--ignore-system-fonts")
+        .arg("--font-path")
+        .arg(project.path().join("fonts"))
+        .arg("--fallback-font")
+        .arg("hani=Noto Serif CJK SC")
+        .arg("--warn-fallback")
+        .must_succeed();
+    output
+        .stderr
+        .must_contain("used fallback font \"Noto Serif CJK SC\" for \"你好\"");
+}
+
 #[test]
 fn test_info() {
     let output = exec().arg("info").must_succeed();
@@ -80,6 +275,33 @@ fn test_deps() {
     output.stdout.must_contain("tiger.jpg").must_contain("main.typ");
 }
 
+#[test]
+fn test_profile() {
+    let project = tempfs();
+    let main = project
+        .write("main.typ", "#let double(x) = 2 * x\n#for i in range(10) { double(i) }");
+
+    let output = exec().arg("compile").arg(&main).arg("--profile").must_succeed();
+    output
+        .stderr
+        .must_contain("excl. ms")
+        .must_contain("function")
+        .must_contain("double");
+
+    let profile = project.resolve("profile.json");
+    exec()
+        .arg("compile")
+        .arg(&main)
+        .arg(format!("--profile={}", profile.display()))
+        .must_succeed();
+    project
+        .read("profile.json")
+        .must_start_with("[")
+        .must_contain(r#""kind": "function""#)
+        .must_contain(r#""name": "double""#)
+        .must_contain(r#""calls": 10"#);
+}
+
 #[test]
 fn test_path_resolved() {
     let project = tempfs();
@@ -185,9 +407,493 @@ fn test_path_to_package() {
     output.stderr.must_contain("error: panicked with: (7, 42)");
 }
 
+#[test]
+fn test_project_manifest() {
+    let project = tempfs();
+    project.write("src/main.typ", "#set document(title: sys.inputs.title)");
+    project.write("src/appendix.typ", "#sys.inputs.title");
+    project.write(
+        "typst.toml",
+        r#"[project]
+           inputs = { title = "Hello from manifest" }
+           features = ["html"]
+
+           [[target]]
+           input = "src/main.typ"
+           output = "out/main.pdf"
+
+           [[target]]
+           input = "src/appendix.typ"
+           output = "out/appendix.html""#,
+    );
+    exec()
+        .arg("compile")
+        .current_dir(project.resolve("src"))
+        .must_succeed();
+    project
+        .read("out/main.pdf")
+        .must_start_with("%PDF")
+        .must_contain("Hello from manifest");
+    project.read("out/appendix.html").must_contain("Hello from manifest");
+}
+
+#[test]
+fn test_inputs() {
+    let project = tempfs();
+    let json =
+        project.write("inputs.json", r#"{"a": "json", "b": [1, 2], "c": {"d": true}}"#);
+    let toml = project.write("inputs.toml", "a = \"toml\"\ne = 1.5");
+    let yaml = project.write("inputs.yaml", "e: yaml\nf: null");
+
+    // Later files take precedence over earlier ones.
+    exec()
+        .arg("eval")
+        .arg("(sys.inputs.a, sys.inputs.b, sys.inputs.c.d, sys.inputs.e, sys.inputs.f)")
+        .arg("--input-file")
+        .arg(&json)
+        .arg("--input-file")
+        .arg(&toml)
+        .arg("--input-file")
+        .arg(&yaml)
+        .must_succeed()
+        .stdout
+        .must_match_lines([r#"["toml",[1,2],true,"yaml",null]"#]);
+
+    // Pairs from the command line take precedence over files and JSON pairs
+    // take precedence over string pairs.
+    exec()
+        .arg("eval")
+        .arg("(sys.inputs.a, sys.inputs.b, sys.inputs.c)")
+        .args(["--input", "a=string", "--input", "c=string"])
+        .args(["--input-json", r#"b={"x": 1}"#, "--input-json", "c=3"])
+        .arg("--input-file")
+        .arg(&json)
+        .must_succeed()
+        .stdout
+        .must_match_lines([r#"["string",{"x":1},3]"#]);
+
+    exec()
+        .arg("eval")
+        .arg("sys.inputs")
+        .args(["--input-json", "a={"])
+        .must_fail()
+        .stderr
+        .must_contain("invalid input a: failed to parse JSON");
+
+    let list = project.write("list.json", "[1, 2]");
+    let malformed = project.write("malformed.toml", "a = ");
+    let text = project.write("inputs.txt", "a = 1");
+    for (path, message) in [
+        (&list, "must contain a dictionary"),
+        (&malformed, "failed to parse TOML"),
+        (&text, "must be a JSON, TOML, or YAML file"),
+        (&project.resolve("missing.json"), "failed to read input file"),
+    ] {
+        exec()
+            .arg("eval")
+            .arg("sys.inputs")
+            .arg("--input-file")
+            .arg(path)
+            .must_fail()
+            .stderr
+            .must_contain(message);
+    }
+}
+
+#[test]
+fn test_test() {
+    let project = tempfs();
+    project.write("tests/hello.typ", "#set page(width: 40pt, height: 20pt)\nHello");
+    project.write("tests/invisible.typ", "#let x = 1");
+    project.write("tests/_helper.typ", "#panic()");
+    let test = |update: bool| {
+        let mut command = exec();
+        command
+            .arg("test")
+            .arg("--ignore-system-fonts")
+            .current_dir(project.path());
+        if update {
+            command.arg("--update");
+        }
+        command
+    };
+
+    // Tests with visible output need a reference image, helpers are skipped.
+    test(false)
+        .must_fail()
+        .stderr
+        .must_contain("missing reference image")
+        .must_contain("1 passed, 1 failed");
+
+    // Updating writes the reference image.
+    test(true)
+        .must_succeed()
+        .stderr
+        .must_contain("update hello")
+        .must_contain("1 passed, 0 failed, 1 updated");
+    project.read("tests/hello.png").must_start_with(b"\x89PNG");
+
+    // Afterwards, the output matches the reference.
+    test(false)
+        .must_succeed()
+        .stderr
+        .must_contain("pass   hello")
+        .must_contain("2 passed, 0 failed");
+
+    // Changed output doesn't.
+    project.write("tests/hello.typ", "#set page(width: 40pt, height: 20pt)\nBye");
+    test(false)
+        .must_fail()
+        .stderr
+        .must_contain("fail   hello")
+        .must_contain("output does not match reference image");
+}
+
+#[test]
+fn test_package_lock() {
+    let project = tempfs();
+    let package = tempfs();
+    let main = project.write("main.typ", "#import \"@local/demo:0.1.0\": f; #f()");
+    project.write("typst.toml", "[project]");
+    package.write(
+        "local/demo/0.1.0/typst.toml",
+        r#"[package]
+           name = "demo"
+           version = "0.1.0"
+           entrypoint = "lib.typ""#,
+    );
+    package.write("local/demo/0.1.0/lib.typ", "#let f() = [Hi]");
+    let compile = |locked: bool| {
+        let mut command = exec();
+        command
+            .arg("compile")
+            .arg(&main)
+            .arg("--package-path")
+            .arg(package.path());
+        if locked {
+            command.arg("--locked");
+        }
+        command
+    };
+
+    compile(false).must_succeed();
+    project
+        .read("typst.lock")
+        .must_contain("spec = \"@local/demo:0.1.0\"")
+        .must_contain("hash = \"sha256:");
+    compile(true).must_succeed();
+
+    // Changed contents fail a locked compilation, but otherwise update the
+    // lock with a warning.
+    package.write("local/demo/0.1.0/lib.typ", "#let f() = [Bye]");
+    let lockfile = project.read("typst.lock");
+    compile(true)
+        .must_fail()
+        .stderr
+        .must_contain("contents of @local/demo:0.1.0 do not match the lockfile");
+    assert_eq!(project.read("typst.lock").0, lockfile.0);
+    compile(false).must_succeed().stderr.must_contain(
+        "contents of @local/demo:0.1.0 do not match the lockfile, updating it",
+    );
+    assert_ne!(project.read("typst.lock").0, lockfile.0);
+    compile(true).must_succeed();
+
+    // Packages that are no longer used are removed from the lock.
+    project.write("main.typ", "Hi");
+    compile(false).must_succeed();
+    assert!(!project.read("typst.lock").contains("@local/demo"));
+}
+
+#[test]
+fn test_package_lock_package_manifest() {
+    // A package's own manifest doesn't make its directory a project.
+    let package = tempfs();
+    let main = package.write("lib.typ", "Hi");
+    package.write(
+        "typst.toml",
+        r#"[package]
+           name = "demo"
+           version = "0.1.0"
+           entrypoint = "lib.typ""#,
+    );
+    exec().arg("compile").arg(&main).must_succeed();
+    assert!(!package.path().join("typst.lock").exists());
+}
+
+#[test]
+fn test_package_registry() {
+    let project = tempfs();
+    let main = project.write("main.typ", "#import \"@acme/demo:0.1.0\": f; #f()");
+    let archive = tarball(&[
+        (
+            "typst.toml",
+            r#"[package]
+               name = "demo"
+               version = "0.1.0"
+               entrypoint = "lib.typ""#,
+        ),
+        ("lib.typ", "#let f() = panic(42)"),
+    ]);
+
+    // Served over HTTP.
+    let url = serve(vec![("/acme/demo-0.1.0.tar.gz", archive.clone())]);
+    let output = exec()
+        .arg("compile")
+        .arg(&main)
+        .arg("--package-registry")
+        .arg(format!("acme={url}"))
+        .arg("--package-cache-path")
+        .arg(tempfs().path())
+        .must_fail();
+    output.stderr.must_contain("error: panicked with: 42");
+
+    // Served from a local mirror.
+    let mirror = tempfs();
+    mirror.write("acme/demo-0.1.0.tar.gz", archive);
+    let output = exec()
+        .arg("compile")
+        .arg(&main)
+        .arg("--package-registry")
+        .arg(format!("acme={}", mirror.path().display()))
+        .arg("--package-cache-path")
+        .arg(tempfs().path())
+        .must_fail();
+    output.stderr.must_contain("error: panicked with: 42");
+}
+
+#[test]
+fn test_vendor() {
+    let project = tempfs();
+    let package = tempfs();
+    let main = project.write("main.typ", "#import \"@local/demo:0.1.0\": f; #f()");
+    package.write(
+        "local/demo/0.1.0/typst.toml",
+        r#"[package]
+           name = "demo"
+           version = "0.1.0"
+           entrypoint = "lib.typ""#,
+    );
+    package.write("local/demo/0.1.0/lib.typ", "#import \"utils.typ\": f");
+    package.write("local/demo/0.1.0/utils.typ", "#let f() = [Vendored]");
+    let output = exec()
+        .arg("vendor")
+        .arg(&main)
+        .arg("--package-path")
+        .arg(package.path())
+        .must_succeed();
+    output.stderr.must_contain("vendored @local/demo:0.1.0");
+    project
+        .read("vendor/local/demo/0.1.0/utils.typ")
+        .must_contain("Vendored");
+
+    // The vendored package is used even if it's not available otherwise.
+    exec()
+        .arg("compile")
+        .arg(&main)
+        .arg("--package-path")
+        .arg(tempfs().path())
+        .arg("--offline")
+        .must_succeed();
+}
+
+#[test]
+fn test_package_commands() {
+    let project = tempfs();
+    let data = tempfs();
+    let cache = tempfs();
+    for version in ["0.1.0", "0.2.0"] {
+        data.write(
+            format!("local/demo/{version}/typst.toml"),
+            format!(
+                r#"[package]
+                   name = "demo"
+                   version = "{version}"
+                   entrypoint = "lib.typ""#
+            ),
+        );
+        cache.write(format!("preview/demo/{version}/lib.typ"), "");
+    }
+    project.write("main.typ", "#import \"@local/demo:0.1.0\"");
+    let package = |args: &[&str]| {
+        let mut command = exec();
+        command
+            .arg("package")
+            .args(args)
+            .arg("--package-path")
+            .arg(data.path())
+            .arg("--package-cache-path")
+            .arg(cache.path());
+        command
+    };
+
+    package(&["list"])
+        .must_succeed()
+        .stdout
+        .must_contain("@local/demo:0.2.0")
+        .must_contain("@preview/demo:0.1.0");
+
+    package(&["info", "@local/demo"])
+        .must_succeed()
+        .stdout
+        .must_contain("version = \"0.2.0\"");
+
+    let dir = project.path().to_str().unwrap();
+    package(&["upgrade", dir])
+        .must_succeed()
+        .stdout
+        .must_contain("main.typ:1: @local/demo:0.1.0 -> 0.2.0");
+    package(&["upgrade", dir, "--write"]).must_succeed();
+    project.read("main.typ").must_contain("@local/demo:0.2.0");
+
+    package(&["prune"]).must_succeed();
+    assert!(!cache.resolve("preview/demo/0.1.0").exists());
+    assert!(cache.resolve("preview/demo/0.2.0").exists());
+
+    package(&["remove", "@preview/demo"]).must_succeed();
+    assert!(!cache.resolve("preview/demo").exists());
+}
+
+#[test]
+fn test_package_check() {
+    let package = tempfs();
+    package.write(
+        "typst.toml",
+        r#"[package]
+           name = "demo"
+           version = "0.1.0"
+           entrypoint = "lib.typ"
+           authors = ["Jane"]
+           description = "A demo."
+           exclude = ["docs"]"#,
+    );
+    package.write("lib.typ", "#import \"util.typ\"\n#include \"docs/manual.typ\"");
+    package.write("docs/manual.typ", "");
+    let check = || {
+        let mut command = exec();
+        command.arg("package").arg("check").arg(package.path());
+        command
+    };
+
+    check()
+        .must_fail()
+        .stderr
+        .must_contain("package manifest is missing `license`")
+        .must_contain("file `util.typ` does not exist")
+        .must_contain("file `docs/manual.typ` is excluded from the package");
+
+    package.write(
+        "typst.toml",
+        r#"[package]
+           name = "demo"
+           version = "0.1.0"
+           entrypoint = "lib.typ"
+           authors = ["Jane"]
+           license = "MIT"
+           description = "A demo."
+           exclude = ["docs"]"#,
+    );
+    package.write("lib.typ", "#let f() = none");
+    check()
+        .must_succeed()
+        .stderr
+        .must_contain("checked demo:0.1.0 (2 files")
+        .must_contain("docs/manual.typ");
+}
+
+#[test]
+fn test_debug_session() {
+    let project = tempfs();
+    let main = project.write("main.typ", "#let a = 1\n#let b = a + 1\n#b");
+    let mut session = DapSession::start(&main);
+
+    assert_eq!(session.request("initialize", json!({}))["success"], true);
+    session.event("initialized");
+
+    let other = project.write("other.typ", "");
+    let response = session.request("launch", json!({ "program": other }));
+    assert_eq!(response["success"], false);
+    let response = session.request("launch", json!({ "program": main }));
+    assert_eq!(response["success"], true);
+
+    let response = session.request(
+        "setBreakpoints",
+        json!({ "source": { "path": main }, "breakpoints": [{ "line": 2 }] }),
+    );
+    assert_eq!(response["body"]["breakpoints"][0]["verified"], true);
+
+    session.request("configurationDone", json!({}));
+    assert_eq!(session.event("stopped")["body"]["reason"], "breakpoint");
+
+    let response = session.request("stackTrace", json!({ "threadId": 1 }));
+    let frame = &response["body"]["stackFrames"][0];
+    assert_eq!(frame["name"], "main");
+    assert_eq!(frame["line"], 2);
+
+    let response = session.request("variables", json!({ "variablesReference": 1 }));
+    let variables = &response["body"]["variables"];
+    assert_eq!(variables[0]["name"], "a");
+    assert_eq!(variables[0]["value"], "1");
+
+    let response = session.request("evaluate", json!({ "expression": "a + 41" }));
+    assert_eq!(response["body"]["result"], "42");
+
+    session.request("continue", json!({ "threadId": 1 }));
+    session.event("terminated");
+    session.request("disconnect", json!({}));
+    assert!(session.child.wait().unwrap().success());
+}
+
+#[test]
+fn test_diagnostics_trace_args() {
+    let project = tempfs();
+    let main = project.write(
+        "main.typ",
+        "#let g(y) = panic(y)\n#let f(x) = g(x + \"b\")\n#f(\"a\" * 50)",
+    );
+
+    let output = exec().arg("compile").arg(&main).must_fail();
+    output
+        .stderr
+        .must_contain("help: error occurred in this call of function `g`\n")
+        .must_contain("help: error occurred in this call of function `f`\n");
+    assert!(!output.stderr.contains("with arguments"));
+
+    let output = exec().arg("compile").arg(&main).arg("--trace-args").must_fail();
+    let long = "a".repeat(36);
+    output
+        .stderr
+        .must_contain(format!(
+            "help: error occurred in this call of function `g` with arguments (y: \"{long}..)"
+        ))
+        .must_contain(format!(
+            "help: error occurred in this call of function `f` with arguments (x: \"{long}..)"
+        ));
+}
+
+#[test]
+fn test_diagnostics_json() {
+    let project = tempfs();
+    let main = project.write("main.typ", "#let f(x) = panic(x)\n#f(42)");
+    let output = exec()
+        .arg("compile")
+        .arg(&main)
+        .arg("--diagnostic-format=json")
+        .must_fail();
+    output
+        .stderr
+        .must_contain(r#""severity":"error""#)
+        .must_contain(r#""message":"panicked with: 42""#)
+        .must_contain(r#"main.typ""#)
+        .must_contain(r#""args":"(x: 42)""#);
+}
+
 /// Executes a command with the Typst CLI.
 fn exec() -> Command {
-    Command::new(env!("CARGO_BIN_EXE_typst"))
+    let mut command = Command::new(env!("CARGO_BIN_EXE_typst"));
+    // Keep the font index out of the user's cache directory.
+    let index = Path::new(env!("CARGO_TARGET_TMPDIR")).join("fonts.json");
+    command.env("TYPST_FONT_INDEX", index);
+    command
 }
 
 trait CommandExt {
@@ -230,11 +936,146 @@ impl From<Output> for TestOutput {
     }
 }
 
+/// A session with the debug adapter of the Typst CLI.
+struct DapSession {
+    child: Child,
+    stdin: ChildStdin,
+    stdout: BufReader<ChildStdout>,
+    seq: u64,
+    events: Vec<Json>,
+}
+
+impl DapSession {
+    /// Starts the debug adapter for the given input file.
+    fn start(input: &Path) -> Self {
+        let mut child = exec()
+            .arg("debug")
+            .arg(input)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .unwrap();
+        let stdin = child.stdin.take().unwrap();
+        let stdout = BufReader::new(child.stdout.take().unwrap());
+        Self { child, stdin, stdout, seq: 0, events: vec![] }
+    }
+
+    /// Sends a request and waits for its response. Events that arrive in the
+    /// meantime are kept for [`event`](Self::event).
+    #[track_caller]
+    fn request(&mut self, command: &str, arguments: Json) -> Json {
+        self.seq += 1;
+        let body = json!({
+            "seq": self.seq,
+            "type": "request",
+            "command": command,
+            "arguments": arguments,
+        })
+        .to_string();
+        write!(self.stdin, "Content-Length: {}\r\n\r\n{body}", body.len()).unwrap();
+        self.stdin.flush().unwrap();
+
+        loop {
+            let message = self.read();
+            if message["type"] == "response" && message["request_seq"] == self.seq {
+                return message;
+            }
+            self.events.push(message);
+        }
+    }
+
+    /// Waits for an event with the given name.
+    #[track_caller]
+    fn event(&mut self, name: &str) -> Json {
+        if let Some(i) = self.events.iter().position(|event| event["event"] == name) {
+            return self.events.remove(i);
+        }
+
+        loop {
+            let message = self.read();
+            if message["event"] == name {
+                return message;
+            }
+        }
+    }
+
+    /// Reads the next message from the adapter.
+    #[track_caller]
+    fn read(&mut self) -> Json {
+        let mut length = 0;
+        loop {
+            let mut line = String::new();
+            assert_ne!(self.stdout.read_line(&mut line).unwrap(), 0, "adapter exited");
+            match line.trim_end().strip_prefix("Content-Length:") {
+                Some(value) => length = value.trim().parse().unwrap(),
+                None if line.trim_end().is_empty() => break,
+                None => {}
+            }
+        }
+
+        let mut buf = vec![0; length];
+        self.stdout.read_exact(&mut buf).unwrap();
+        serde_json::from_slice(&buf).unwrap()
+    }
+}
+
 #[track_caller]
 fn tempfs() -> TempFs {
     TempFs(tempfile::tempdir().unwrap())
 }
 
+/// Creates a gzipped tarball with the given files.
+fn tarball(files: &[(&str, &str)]) -> Vec<u8> {
+    let encoder = flate2::write::GzEncoder::new(Vec::new(), Default::default());
+    let mut builder = tar::Builder::new(encoder);
+    for (path, data) in files {
+        let mut header = tar::Header::new_gnu();
+        header.set_size(data.len() as u64);
+        header.set_mode(0o644);
+        builder.append_data(&mut header, path, data.as_bytes()).unwrap();
+    }
+    builder.into_inner().unwrap().finish().unwrap()
+}
+
+/// Overwrites a file with zeros, but keeps its size and modification time, so
+/// that the change goes unnoticed by the font index.
+#[track_caller]
+fn garble(path: &Path) {
+    let metadata = std::fs::metadata(path).unwrap();
+    std::fs::write(path, vec![0; metadata.len() as usize]).unwrap();
+    let file = std::fs::File::options().write(true).open(path).unwrap();
+    file.set_modified(metadata.modified().unwrap()).unwrap();
+}
+
+/// Serves the given files over HTTP on a local port in the background and
+/// returns the base URL. Unknown paths result in a `404`.
+fn serve(files: Vec<(&'static str, Vec<u8>)>) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    std::thread::spawn(move || {
+        for mut stream in listener.incoming().flatten() {
+            // Read the whole request head, but only look at the request line.
+            let lines: Vec<String> = BufReader::new(&stream)
+                .lines()
+                .map_while(Result::ok)
+                .take_while(|line| !line.is_empty())
+                .collect();
+            let path = lines.first().and_then(|line| line.split(' ').nth(1));
+            let (status, body) = match files.iter().find(|(p, _)| Some(*p) == path) {
+                Some((_, body)) => ("200 OK", body.as_slice()),
+                None => ("404 Not Found", [].as_slice()),
+            };
+            let head = format!(
+                "HTTP/1.1 {status}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                body.len(),
+            );
+            _ = stream.write_all(head.as_bytes());
+            _ = stream.write_all(body);
+        }
+    });
+    url
+}
+
 struct TempFs(TempDir);
 
 impl TempFs {
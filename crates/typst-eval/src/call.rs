@@ -6,8 +6,8 @@ use typst_library::diag::{
 };
 use typst_library::engine::{Engine, Sink, Traced};
 use typst_library::foundations::{
-    Arg, Args, Binding, Capturer, Closure, ClosureNode, Content, Context, Func,
-    NativeElement, Scope, Scopes, SymbolElem, Value,
+    Arg, Args, AutoValue, Binding, Capturer, CastInfo, Closure, ClosureNode, Content,
+    Context, Func, NativeElement, NoneValue, Scope, Scopes, SymbolElem, Type, Value,
 };
 use typst_library::introspection::Introspector;
 use typst_library::math::LrElem;
@@ -29,6 +29,7 @@ impl Eval for ast::FuncCall<'_> {
         let callee = self.callee();
         let callee_span = callee.span();
         let args = self.args();
+        let _scope = typst_timing::TimingScope::with_span("call", Some(span.into_raw()));
 
         vm.engine.route.check_call_depth().at(span)?;
 
@@ -65,7 +66,7 @@ impl Eval for ast::FuncCall<'_> {
             .map_err(|err| hint_if_shadowed_std(vm, &self.callee(), err))
             .at(callee_span)?;
 
-        let point = || Tracepoint::Call(func.name().map(Into::into));
+        let point = || Tracepoint::Call(func.name().map(Into::into), None);
         let f = || {
             func.call(&mut vm.engine, vm.context, args_value).trace(
                 vm.world(),
@@ -76,10 +77,13 @@ impl Eval for ast::FuncCall<'_> {
 
         // Stacker is broken on WASM.
         #[cfg(target_arch = "wasm32")]
-        return f();
+        let value = f()?;
 
         #[cfg(not(target_arch = "wasm32"))]
-        stacker::maybe_grow(32 * 1024, 2 * 1024 * 1024, f)
+        let value = stacker::maybe_grow(32 * 1024, 2 * 1024 * 1024, f)?;
+
+        vm.budget.check_value(&value).at(span)?;
+        Ok(value)
     }
 }
 
@@ -139,14 +143,32 @@ impl Eval for ast::Closure<'_> {
     type Output = Value;
 
     fn eval(self, vm: &mut Vm) -> SourceResult<Self::Output> {
-        // Evaluate default values of named parameters.
+        // Evaluate type annotations and default values of named parameters.
         let mut defaults = Vec::new();
-        for param in self.params().children() {
+        let mut types = Vec::new();
+        for (param, ty) in self.params().annotated() {
+            let ty = ty.map(|expr| eval_annotation(vm, expr)).transpose()?;
             if let ast::Param::Named(named) = param {
-                defaults.push(named.expr().eval(vm)?);
+                let expr = named.expr();
+                let mut default = expr.eval(vm)?;
+                if let Some(info) = &ty {
+                    match info.coerce(default) {
+                        Ok(coerced) => default = coerced,
+                        Err(default) => {
+                            let hint =
+                                "the default value must match the parameter's type";
+                            return Err(info.error(&default).with_hint(hint))
+                                .at(expr.span());
+                        }
+                    }
+                }
+                defaults.push(default);
             }
+            types.push(ty);
         }
 
+        let returns = self.returns().map(|expr| eval_annotation(vm, expr)).transpose()?;
+
         // Collect captured variables.
         let captured = {
             let mut visitor = CapturesVisitor::new(Some(&vm.scopes), Capturer::Function);
@@ -158,6 +180,8 @@ impl Eval for ast::Closure<'_> {
         let closure = Closure {
             node: ClosureNode::Closure(self.to_untyped().clone()),
             defaults,
+            types,
+            returns,
             captured,
             num_pos_params: self
                 .params()
@@ -170,6 +194,39 @@ impl Eval for ast::Closure<'_> {
     }
 }
 
+/// Evaluates a type annotation of a parameter or return value.
+fn eval_annotation(vm: &mut Vm, expr: ast::Expr) -> SourceResult<CastInfo> {
+    let value = expr.eval(vm)?;
+    annotation(value).at(expr.span())
+}
+
+/// Converts a value into the cast info it describes as a type annotation.
+fn annotation(value: Value) -> HintedStrResult<CastInfo> {
+    Ok(match value {
+        Value::Type(ty) => CastInfo::Type(ty),
+        Value::None => CastInfo::Type(Type::of::<NoneValue>()),
+        Value::Auto => CastInfo::Type(Type::of::<AutoValue>()),
+        Value::Array(array) if !array.is_empty() => CastInfo::Union(
+            array.into_iter().map(annotation).collect::<HintedStrResult<_>>()?,
+        ),
+        Value::Array(_) => bail!("type annotation must not be empty"),
+        Value::Func(func) if func.to_element().is_some() => bail!(
+            "expected type, none, auto, or array, found element function";
+            hint: "use `content` to accept any content";
+        ),
+        v => bail!("expected type, none, auto, or array, found {}", v.ty()),
+    })
+}
+
+/// Checks that a value matches a type annotation and applies the built-in
+/// coercions.
+fn check_type(ty: Option<&CastInfo>, value: Spanned<Value>) -> SourceResult<Value> {
+    match ty {
+        Some(info) => info.coerce(value.v).map_err(|v| info.error(&v)).at(value.span),
+        None => Ok(value.v),
+    }
+}
+
 /// Call the function in the context with the arguments.
 #[comemo::memoize]
 #[allow(clippy::too_many_arguments)]
@@ -220,24 +277,25 @@ pub fn eval_closure(
         vm.define(name, func.clone());
     }
 
+    let call_span = args.span;
     let num_pos_args = args.to_pos().len();
     let sink_size = num_pos_args.checked_sub(closure.num_pos_params);
 
     let mut sink = None;
     let mut sink_pos_values = None;
     let mut defaults = closure.defaults.iter();
+    let mut types = closure.types.iter().map(Option::as_ref);
     for p in params.children() {
+        let ty = types.next().flatten();
         match p {
             ast::Param::Pos(pattern) => match pattern {
                 ast::Pattern::Normal(ast::Expr::Ident(ident)) => {
-                    vm.define(ident, args.expect::<Value>(&ident)?)
+                    let value = args.expect::<Spanned<Value>>(&ident)?;
+                    vm.define(ident, check_type(ty, value)?)
                 }
                 pattern => {
-                    crate::destructure(
-                        &mut vm,
-                        pattern,
-                        args.expect::<Value>("pattern parameter")?,
-                    )?;
+                    let value = args.expect::<Spanned<Value>>("pattern parameter")?;
+                    crate::destructure(&mut vm, pattern, check_type(ty, value)?)?;
                 }
             },
             ast::Param::Spread(spread) => {
@@ -249,8 +307,10 @@ pub fn eval_closure(
             ast::Param::Named(named) => {
                 let name = named.name();
                 let default = defaults.next().unwrap();
-                let value =
-                    args.named::<Value>(&name)?.unwrap_or_else(|| default.clone());
+                let value = match args.named::<Spanned<Value>>(&name)? {
+                    Some(value) => check_type(ty, value)?,
+                    None => default.clone(),
+                };
                 vm.define(name, value);
             }
         }
@@ -270,16 +330,55 @@ pub fn eval_closure(
     // Ensure all arguments have been used.
     args.finish()?;
 
+    // Evaluate the body.
+    vm.enter(name.map(|name| name.get().as_str()), body.span());
+    let output = body.eval(&mut vm);
+    vm.exit();
+
+    // Record the bound arguments for the call's tracepoint. Calls from native
+    // functions carry the function's own span and are not traced here.
+    let output = if call_span != func.span() {
+        let point = || {
+            let args = bound_args(&vm, params, call_span);
+            Tracepoint::Call(func.name().map(Into::into), Some(args.summary()))
+        };
+        output.trace(vm.world(), point, call_span)?
+    } else {
+        output?
+    };
+
     // Handle control flow.
-    let output = body.eval(&mut vm)?;
-    match vm.flow {
-        Some(FlowEvent::Return(_, Some(explicit), _)) => return Ok(explicit),
-        Some(FlowEvent::Return(_, None, _)) => {}
+    let output = match vm.flow {
+        Some(FlowEvent::Return(span, Some(explicit), _)) => Spanned::new(explicit, span),
+        Some(FlowEvent::Return(_, None, _)) | None => Spanned::new(output, body.span()),
         Some(flow) => bail!(flow.forbidden()),
-        None => {}
-    }
+    };
 
-    Ok(output)
+    check_type(closure.returns.as_ref(), output)
+}
+
+/// Collects the values bound to a closure's parameters.
+fn bound_args(vm: &Vm, params: ast::Params, span: Span) -> Args {
+    let mut args = Args::new::<Value>(span, []);
+    for param in params.children() {
+        let ident = match param {
+            ast::Param::Pos(ast::Pattern::Normal(ast::Expr::Ident(ident))) => ident,
+            ast::Param::Named(named) => named.name(),
+            ast::Param::Spread(spread) => match spread.sink_ident() {
+                Some(ident) => ident,
+                None => continue,
+            },
+            ast::Param::Pos(_) => continue,
+        };
+        if let Ok(binding) = vm.scopes.get(&ident) {
+            args.items.push(Arg {
+                span: ident.span(),
+                name: Some(ident.get().clone().into()),
+                value: Spanned::new(binding.read().clone(), ident.span()),
+            });
+        }
+    }
+    args
 }
 
 /// This used only as the return value of `eval_field_call`.
@@ -316,6 +415,7 @@ fn eval_field_call(
         // because `target_expr.access(vm)` mutably borrows the `vm`, so that we can't
         // evaluate the arguments after it.
         let args = args.eval(vm)?.spanned(span);
+        let budget = vm.budget.clone();
         // However, this difference from the normal call order is not observable because
         // expressions like `(1, arr.len(), 2, 3).push(arr.pop())` evaluate the target to
         // a temporary which we disallow mutation on (returning an error).
@@ -324,8 +424,12 @@ fn eval_field_call(
         match target_expr.access(vm)? {
             // Only arrays and dictionaries have mutable methods.
             target @ (Value::Array(_) | Value::Dict(_)) => {
-                let value = call_method_mut(target, &field, args, span);
-                let point = || Tracepoint::Call(Some(field.get().clone()));
+                let value =
+                    call_method_mut(target, &field, args, span).and_then(|value| {
+                        budget.check_value(target).at(span)?;
+                        Ok(value)
+                    });
+                let point = || Tracepoint::Call(Some(field.get().clone()), None);
                 return Ok(FieldCall::Resolved(value.trace(vm.world(), point, span)?));
             }
             target => (target.clone(), args),
@@ -489,10 +593,17 @@ impl<'a> CapturesVisitor<'a> {
             // body is evaluated. Care must be taken so that the default values
             // of named parameters cannot access previous parameter bindings.
             Some(ast::Expr::Closure(expr)) => {
-                for param in expr.params().children() {
+                for (param, ty) in expr.params().annotated() {
                     if let ast::Param::Named(named) = param {
                         self.visit(named.expr().to_untyped());
                     }
+                    if let Some(ty) = ty {
+                        self.visit(ty.to_untyped());
+                    }
+                }
+
+                if let Some(returns) = expr.returns() {
+                    self.visit(returns.to_untyped());
                 }
 
                 self.internal.enter();
@@ -648,6 +759,8 @@ mod tests {
         test(s, "#((..x) => x + y)", &["y"]);
         test(s, "#((x, y: x + z) => x + y)", &["x", "z"]);
         test(s, "#{x => x; x}", &["x"]);
+        test(s, "#((x as y, z: 1 as z) => x)", &["y", "z"]);
+        test(s, "#let f(x) as y = x", &["y"]);
 
         // Show rule.
         test(s, "#show y: x => x", &["y"]);
@@ -29,6 +29,8 @@ fn eval_code<'a>(
 
     while let Some(expr) = exprs.next() {
         let span = expr.span();
+        vm.budget.step(vm.engine.traced).at(span)?;
+        vm.step(span);
         let value = match expr {
             ast::Expr::SetRule(set) => {
                 let styles = set.eval(vm)?;
@@ -56,6 +58,7 @@ fn eval_code<'a>(
         };
 
         output = ops::join(output, value).at(span)?;
+        vm.budget.check_value(&output).at(span)?;
 
         if let Some(event) = &vm.flow {
             warn_for_discarded_content(&mut vm.engine, event, &output);
@@ -238,6 +241,7 @@ impl Eval for ast::Array<'_> {
             }
         }
 
+        vm.budget.check_array_len(vec.len()).at(self.span())?;
         Ok(vec.into())
     }
 }
@@ -358,6 +362,8 @@ impl Eval for ast::Contextual<'_> {
         let closure = Closure {
             node: ClosureNode::Context(self.body().to_untyped().clone()),
             defaults: vec![],
+            types: vec![],
+            returns: None,
             captured,
             num_pos_params: 0,
         };
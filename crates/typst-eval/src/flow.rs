@@ -82,8 +82,10 @@ impl Eval for ast::WhileLoop<'_> {
                 bail!(self.span(), "loop seems to be infinite");
             }
 
+            vm.budget.step(vm.engine.traced).at(self.span())?;
             let value = body.eval(vm)?;
             output = ops::join(output, value).at(body.span())?;
+            vm.budget.check_value(&output).at(body.span())?;
 
             match vm.flow {
                 Some(FlowEvent::Break(_)) => {
@@ -125,11 +127,13 @@ impl Eval for ast::ForLoop<'_> {
 
                 #[allow(unused_parens)]
                 for value in $iterable {
+                    vm.budget.step(vm.engine.traced).at(self.span())?;
                     destructure(vm, $pat, value.into_value())?;
 
                     let body = self.body();
                     let value = body.eval(vm)?;
                     output = ops::join(output, value).at(body.span())?;
+                    vm.budget.check_value(&output).at(body.span())?;
 
                     match vm.flow {
                         Some(FlowEvent::Break(_)) => {
@@ -75,8 +75,12 @@ pub fn eval(
     }
 
     // Evaluate the module.
+    let name = id.vpath().file_stem().unwrap_or_default();
     let markup = root.cast::<ast::Markup>().unwrap();
-    let output = markup.eval(&mut vm)?;
+    vm.enter(Some(name), root.span());
+    let output = markup.eval(&mut vm);
+    vm.exit();
+    let output = output?;
 
     // Handle control flow.
     if let Some(flow) = vm.flow {
@@ -84,8 +88,6 @@ pub fn eval(
     }
 
     // Assemble the module.
-    let name = id.vpath().file_stem().unwrap_or_default();
-
     Ok(Module::new(name, vm.scopes.top).with_content(output).with_file_id(id))
 }
 
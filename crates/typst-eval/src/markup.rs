@@ -31,6 +31,13 @@ fn eval_markup<'a>(
     let mut seq = Vec::with_capacity(exprs.size_hint().1.unwrap_or_default());
 
     while let Some(expr) = exprs.next() {
+        vm.budget.step(vm.engine.traced).at(expr.span())?;
+
+        // Only embedded code is interesting to step through.
+        if expr.hash() {
+            vm.step(expr.span());
+        }
+
         match expr {
             ast::Expr::SetRule(set) => {
                 let styles = set.eval(vm)?;
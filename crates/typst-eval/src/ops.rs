@@ -1,4 +1,5 @@
 use typst_library::diag::{At, HintedStrResult, SourceResult};
+use typst_library::engine::Budget;
 use typst_library::foundations::{IntoValue, Value, ops};
 use typst_syntax::ast::{self, AstNode};
 
@@ -62,7 +63,10 @@ fn apply_binary(
     }
 
     let rhs = binary.rhs().eval(vm)?;
-    op(lhs, rhs).at(binary.span())
+    check_repetition(&vm.budget, binary, &lhs, &rhs)?;
+    let output = op(lhs, rhs).at(binary.span())?;
+    vm.budget.check_value(&output).at(binary.span())?;
+    Ok(output)
 }
 
 /// Apply an assignment operation.
@@ -84,8 +88,36 @@ fn apply_assignment(
         return Ok(Value::None);
     }
 
+    let budget = vm.budget.clone();
     let location = binary.lhs().access(vm)?;
+    check_repetition(&budget, binary, location, &rhs)?;
     let lhs = std::mem::take(&mut *location);
     *location = op(lhs, rhs).at(binary.span())?;
+    budget.check_value(location).at(binary.span())?;
     Ok(Value::None)
 }
+
+/// Ensures that repeating a string or array stays within the budget before the
+/// repetition is actually performed.
+fn check_repetition(
+    budget: &Budget,
+    binary: ast::Binary,
+    lhs: &Value,
+    rhs: &Value,
+) -> SourceResult<()> {
+    if !matches!(binary.op(), ast::BinOp::Mul | ast::BinOp::MulAssign) {
+        return Ok(());
+    }
+
+    let times = |n: &i64| usize::try_from(*n).unwrap_or(0);
+    match (lhs, rhs) {
+        (Value::Str(s), Value::Int(n)) | (Value::Int(n), Value::Str(s)) => {
+            budget.check_str_len(s.len().saturating_mul(times(n)))
+        }
+        (Value::Array(a), Value::Int(n)) | (Value::Int(n), Value::Array(a)) => {
+            budget.check_array_len(a.len().saturating_mul(times(n)))
+        }
+        _ => Ok(()),
+    }
+    .at(binary.span())
+}
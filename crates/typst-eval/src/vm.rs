@@ -2,7 +2,7 @@ use comemo::Tracked;
 use ecow::eco_format;
 use typst_library::World;
 use typst_library::diag::{HintedString, warning};
-use typst_library::engine::Engine;
+use typst_library::engine::{Budget, DebugFrame, DebuggerHandle, Engine};
 use typst_library::foundations::{Binding, Context, IntoValue, Scopes, Value};
 use typst_syntax::Span;
 use typst_syntax::ast::{self, AstNode};
@@ -24,6 +24,10 @@ pub struct Vm<'a> {
     pub inspected: Option<Span>,
     /// Data that is contextually made accessible to code behind the scenes.
     pub context: Tracked<'a, Context<'a>>,
+    /// A debugger that is notified as code is evaluated.
+    pub debugger: Option<DebuggerHandle>,
+    /// The resource budget of the compilation.
+    pub budget: Budget,
 }
 
 impl<'a> Vm<'a> {
@@ -35,7 +39,16 @@ impl<'a> Vm<'a> {
         target: Span,
     ) -> Self {
         let inspected = target.id().and_then(|id| engine.traced.get(id));
-        Self { engine, context, flow: None, scopes, inspected }
+        let (debugger, budget) = engine.traced.hooks().unwrap_or_default();
+        Self {
+            engine,
+            context,
+            flow: None,
+            scopes,
+            inspected,
+            debugger,
+            budget,
+        }
     }
 
     /// Access the underlying world.
@@ -73,6 +86,32 @@ impl<'a> Vm<'a> {
         self.scopes.top.bind(var.get().clone(), binding);
     }
 
+    /// Notify the debugger that a module or function body is entered.
+    pub fn enter(&self, name: Option<&str>, span: Span) {
+        if let Some(debugger) = &self.debugger {
+            debugger.enter(name, span);
+        }
+    }
+
+    /// Notify the debugger that the current module or function body is exited.
+    pub fn exit(&self) {
+        if let Some(debugger) = &self.debugger {
+            debugger.exit();
+        }
+    }
+
+    /// Notify the debugger that an expression is about to be evaluated.
+    pub fn step(&self, span: Span) {
+        if let Some(debugger) = &self.debugger {
+            debugger.step(&DebugFrame {
+                span,
+                scopes: &self.scopes,
+                engine: &self.engine,
+                context: self.context,
+            });
+        }
+    }
+
     /// Trace a value.
     #[cold]
     pub fn trace(&mut self, value: Value) {
@@ -17,14 +17,15 @@ use typst_library::layout::{
 };
 use typst_library::model::{
     Attribution, BibliographyElem, CiteElem, CiteGroup, CslIndentElem, CslLightElem,
-    Destination, DirectLinkElem, EmphElem, EnumElem, FigureCaption, FigureElem,
-    FootnoteElem, FootnoteEntry, FootnoteMarker, HeadingElem, LinkElem, LinkTarget,
-    ListElem, OutlineElem, OutlineEntry, OutlineNode, ParElem, ParbreakElem, QuoteElem,
-    RefElem, StrongElem, TableCell, TableElem, TermsElem, TitleElem, Works,
+    Destination, DirectLinkElem, EmphElem, EndnoteElem, EndnoteEntry, EndnotesElem,
+    EnumElem, FigureCaption, FigureElem, FootnoteElem, FootnoteEntry, FootnoteMarker,
+    GlossaryElem, HeadingElem, IndexElem, IndexNode, LinkElem, LinkTarget, ListElem,
+    OutlineElem, OutlineEntry, OutlineNode, ParElem, ParbreakElem, QuoteElem, RefElem,
+    StrongElem, TableCell, TableElem, TermsElem, TitleElem, Works,
 };
 use typst_library::text::{
-    HighlightElem, LinebreakElem, OverlineElem, RawElem, RawLine, SmallcapsElem,
-    SpaceElem, StrikeElem, SubElem, SuperElem, UnderlineElem,
+    DropcapElem, HighlightElem, LinebreakElem, OverlineElem, RawElem, RawLine,
+    SmallcapsElem, SpaceElem, StrikeElem, SubElem, SuperElem, TextElem, UnderlineElem,
 };
 use typst_library::visualize::{Color, ImageElem};
 use typst_macros::elem;
@@ -55,8 +56,13 @@ pub fn register(rules: &mut NativeRuleMap) {
     rules.register(Html, FOOTNOTE_MARKER_RULE);
     rules.register(Html, FOOTNOTE_CONTAINER_RULE);
     rules.register(Html, FOOTNOTE_ENTRY_RULE);
+    rules.register(Html, ENDNOTE_RULE);
+    rules.register(Html, ENDNOTE_ENTRY_RULE);
+    rules.register(Html, ENDNOTES_RULE);
     rules.register(Html, OUTLINE_RULE);
     rules.register(Html, OUTLINE_ENTRY_RULE);
+    rules.register(Html, INDEX_RULE);
+    rules.register(Html, GLOSSARY_RULE);
     rules.register(Html, REF_RULE);
     rules.register(Html, CITE_GROUP_RULE);
     rules.register(Html, BIBLIOGRAPHY_RULE);
@@ -72,6 +78,7 @@ pub fn register(rules: &mut NativeRuleMap) {
     rules.register(Html, STRIKE_RULE);
     rules.register(Html, HIGHLIGHT_RULE);
     rules.register(Html, SMALLCAPS_RULE);
+    rules.register(Html, DROPCAP_RULE);
     rules.register(Html, RAW_RULE);
     rules.register(Html, RAW_LINE_RULE);
 
@@ -449,6 +456,55 @@ const FOOTNOTE_ENTRY_RULE: ShowFn<FootnoteEntry> = |elem, engine, styles| {
     Ok(backlink + body)
 };
 
+const ENDNOTE_RULE: ShowFn<EndnoteElem> = |elem, engine, styles| {
+    let span = elem.span();
+    let (dest, num) = elem.realize(engine, styles)?;
+    let sup = SuperElem::new(num).pack().spanned(span);
+
+    // Link to the endnote entry, if the endnote is displayed.
+    let Some(dest) = dest else { return Ok(HElem::hole().clone() + sup) };
+    let link = LinkElem::new(dest.into(), sup)
+        .pack()
+        .styled(HtmlElem::role.set(Some("doc-noteref".into())));
+
+    Ok(HElem::hole().clone() + link)
+};
+
+const ENDNOTE_ENTRY_RULE: ShowFn<EndnoteEntry> = |elem, engine, styles| {
+    let (prefix, body) = elem.realize(engine, styles)?;
+    let backlink = prefix.styled(HtmlElem::role.set(Some("doc-backlink".into())));
+    Ok(backlink + body)
+};
+
+const ENDNOTES_RULE: ShowFn<EndnotesElem> = |elem, engine, styles| {
+    let span = elem.span();
+    let mut seq = vec![];
+    seq.extend(elem.realize_title(styles));
+
+    let items = elem.notes(engine).into_iter().map(|note| {
+        let loc = note.location().unwrap();
+        HtmlElem::new(tag::li)
+            .with_body(Some(EndnoteEntry::new(note).pack().spanned(span)))
+            .pack()
+            .located(loc.variant(1))
+            .spanned(span)
+    });
+
+    // As for footnotes, the list is already numbered with the superscripts.
+    seq.push(
+        HtmlElem::new(tag::ol)
+            .with_styles(css::Properties::new().with("list-style-type", "none"))
+            .with_body(Some(Content::sequence(items)))
+            .pack(),
+    );
+    seq.extend(elem.realize_reset(styles));
+
+    Ok(HtmlElem::new(tag::section)
+        .with_attr(attr::role, "doc-endnotes")
+        .with_body(Some(Content::sequence(seq)))
+        .pack())
+};
+
 const OUTLINE_RULE: ShowFn<OutlineElem> = |elem, engine, styles| {
     fn convert_list(list: Vec<OutlineNode>) -> Content {
         // The Digital Publishing ARIA spec also proposed to add
@@ -512,6 +568,99 @@ const OUTLINE_ENTRY_RULE: ShowFn<OutlineEntry> = |elem, engine, styles| {
     Ok(LinkElem::new(dest.into(), realized).pack())
 };
 
+const INDEX_RULE: ShowFn<IndexElem> = |elem, engine, styles| {
+    // There are no page numbers in HTML, so each reference to a term is
+    // instead a numbered link to the place where the term was marked.
+    fn convert_list(nodes: &[IndexNode], styles: StyleChain) -> Content {
+        HtmlElem::new(tag::ul)
+            .with_styles(css::Properties::new().with("list-style-type", "none"))
+            .with_body(Some(Content::sequence(
+                nodes.iter().map(|node| convert_node(node, styles)),
+            )))
+            .pack()
+    }
+
+    fn convert_node(node: &IndexNode, styles: StyleChain) -> Content {
+        let mut body = node.term.clone();
+        for (i, r) in node.refs.iter().enumerate() {
+            let dest = Destination::Location(r.loc);
+            let mut num = TextElem::packed(eco_format!("{}", i + 1));
+            if r.main {
+                num = num.strong();
+            }
+            body += TextElem::packed(", ");
+            body += LinkElem::new(dest.into(), num).pack();
+        }
+
+        if let Some(cross_refs) = node.realize_cross_refs(styles) {
+            body += TextElem::packed(if node.refs.is_empty() { ", " } else { "; " });
+            body += cross_refs;
+        }
+
+        if !node.children.is_empty() {
+            body += convert_list(&node.children, styles);
+        }
+
+        HtmlElem::new(tag::li).with_body(Some(body)).pack()
+    }
+
+    let mut seq = vec![];
+    seq.extend(elem.realize_title(styles));
+
+    for group in elem.realize_groups(engine, styles)? {
+        if let Some(letter) = group.letter {
+            seq.push(
+                HtmlElem::new(tag::div)
+                    .with_attr(attr::class, "letter")
+                    .with_body(Some(TextElem::packed(letter)))
+                    .pack(),
+            );
+        }
+        seq.push(convert_list(&group.nodes, styles));
+    }
+
+    Ok(HtmlElem::new(tag::section)
+        .with_attr(attr::role, "doc-index")
+        .with_body(Some(Content::sequence(seq)))
+        .pack())
+};
+
+const GLOSSARY_RULE: ShowFn<GlossaryElem> = |elem, engine, styles| {
+    let mut seq = vec![];
+    seq.extend(elem.realize_title(styles));
+
+    // HTML has no pages to list, so each use of a term gets a numbered link
+    // after the term's description instead.
+    let items = elem.realize_items(engine, styles)?;
+    let body = Content::sequence(items.iter().flat_map(|item| {
+        let mut description = item.realize_description();
+        for (i, &loc) in item.uses.iter().enumerate() {
+            let num = TextElem::packed(eco_format!("{}", i + 1));
+            if !description.is_empty() {
+                description += TextElem::packed(", ");
+            }
+            description += LinkElem::new(Destination::Location(loc).into(), num).pack();
+        }
+
+        [
+            HtmlElem::new(tag::dt)
+                .with_body(Some(item.entry.short.clone()))
+                .pack()
+                .located(item.target),
+            HtmlElem::new(tag::dd).with_body(Some(description)).pack(),
+        ]
+    }));
+
+    if !items.is_empty() {
+        seq.push(HtmlElem::new(tag::dl).with_body(Some(body)).pack());
+    }
+
+    Ok(HtmlElem::new(tag::section)
+        .with_attr(attr::role, "doc-glossary")
+        .with_body(Some(Content::sequence(seq)))
+        .pack())
+};
+
 const REF_RULE: ShowFn<RefElem> = |elem, engine, styles| elem.realize(engine, styles);
 
 const CITE_GROUP_RULE: ShowFn<CiteGroup> = |elem, engine, _| {
@@ -741,6 +890,17 @@ const SMALLCAPS_RULE: ShowFn<SmallcapsElem> = |elem, _, styles| {
         .pack())
 };
 
+const DROPCAP_RULE: ShowFn<DropcapElem> = |elem, _, styles| {
+    Ok(HtmlElem::new(tag::span)
+        .with_styles(
+            css::Properties::new()
+                .with("initial-letter", elem.lines.get(styles))
+                .with("margin-inline-end", css::length(elem.gap.get(styles))),
+        )
+        .with_body(Some(elem.body.clone()))
+        .pack())
+};
+
 const RAW_RULE: ShowFn<RawElem> = |elem, _, styles| {
     let lines = elem.lines.as_deref().unwrap_or_default();
 
@@ -5,13 +5,13 @@ use ecow::{EcoString, eco_format};
 use rustc_hash::FxHashSet;
 use serde::{Deserialize, Serialize};
 use typst::foundations::{
-    AutoValue, CastInfo, Func, Label, NativeElement, NoneValue, ParamInfo, Repr,
-    StyleChain, Styles, Type, Value, fields_on, repr,
+    AutoValue, CastInfo, ClosureNode, Func, Label, NativeElement, NoneValue, ParamInfo,
+    Repr, StyleChain, Styles, Type, Value, fields_on, repr,
 };
 use typst::layout::{Alignment, Dir};
 use typst::syntax::ast::AstNode;
 use typst::syntax::{
-    FileId, LinkedNode, Side, Source, SyntaxKind, ast, is_id_continue, is_id_start,
+    FileId, LinkedNode, Side, Source, Span, SyntaxKind, ast, is_id_continue, is_id_start,
     is_ident,
 };
 use typst::text::{FontFlags, RawElem};
@@ -20,7 +20,8 @@ use typst::{AsDocument, Document};
 use unscanny::Scanner;
 
 use crate::utils::{
-    check_value_recursively, globals, plain_docs_sentence, summarize_font_family,
+    check_value_recursively, closure_docs, globals, plain_docs_sentence, resolve_callee,
+    summarize_font_family,
 };
 use crate::{IdeWorld, analyze_expr, analyze_import, analyze_labels, named_items};
 
@@ -712,7 +713,7 @@ fn complete_params(ctx: &mut CompletionContext) -> bool {
             ctx.from = ctx.cursor.min(next.offset());
         }
 
-        named_param_value_completions(ctx, callee, &param);
+        named_param_value_completions(ctx, callee, args_linked, &param);
         return true;
     }
 
@@ -741,9 +742,6 @@ fn param_completions<'a>(
     args: ast::Args<'a>,
     args_linked: &'a LinkedNode<'a>,
 ) {
-    let Some(func) = resolve_global_callee(ctx, callee) else { return };
-    let Some(params) = func.params() else { return };
-
     // Determine which arguments are already present.
     let mut existing_positional = 0;
     let mut existing_named = FxHashSet::default();
@@ -762,6 +760,18 @@ fn param_completions<'a>(
         }
     }
 
+    let Some(func) = resolve_global_callee(ctx, callee) else {
+        if !set
+            && let Some(call) = args_linked.parent()
+            && let Some(func) = resolve_callee(ctx.world, call, callee)
+        {
+            closure_param_completions(ctx, &func, existing_positional, &existing_named);
+        }
+        return;
+    };
+
+    let Some(params) = func.params() else { return };
+
     let mut skipped_positional = 0;
     for param in params {
         if set && !param.settable {
@@ -802,13 +812,79 @@ fn param_completions<'a>(
     }
 }
 
+/// Add completions for the parameters of a user-defined function, based on
+/// their type annotations and documentation comment.
+fn closure_param_completions(
+    ctx: &mut CompletionContext,
+    func: &Func,
+    existing_positional: usize,
+    existing_named: &FxHashSet<&str>,
+) {
+    let Some(closure) = func.to_closure() else { return };
+    let ClosureNode::Closure(node) = &closure.node else { return };
+    let Some(params) = node.cast::<ast::Closure>().map(|closure| closure.params()) else {
+        return;
+    };
+
+    let docs = closure_docs(ctx.world, func);
+    let mut skipped_positional = 0;
+    for (param, ty) in params.children().zip(&closure.types) {
+        match param {
+            ast::Param::Pos(_) => {
+                if skipped_positional < existing_positional {
+                    skipped_positional += 1;
+                    continue;
+                }
+
+                if let Some(ty) = ty {
+                    ctx.cast_completions(ty);
+                }
+            }
+            ast::Param::Named(named) => {
+                let name = named.name();
+                if existing_named.contains(name.as_str()) {
+                    continue;
+                }
+
+                ctx.completions.push(Completion {
+                    kind: CompletionKind::Param,
+                    label: name.get().clone(),
+                    apply: Some(eco_format!("{}: ${{}}", name.as_str())),
+                    detail: docs
+                        .as_ref()
+                        .and_then(|docs| docs.param(&name))
+                        .map(|param| plain_docs_sentence(&param.docs)),
+                });
+            }
+            ast::Param::Spread(_) => {}
+        }
+    }
+
+    if ctx.before.ends_with(',') {
+        ctx.enrich(" ", "");
+    }
+}
+
 /// Add completions for the values of a named function parameter.
 fn named_param_value_completions<'a>(
     ctx: &mut CompletionContext<'a>,
     callee: ast::Expr<'a>,
+    args_linked: &'a LinkedNode<'a>,
     name: &str,
 ) {
-    let Some(func) = resolve_global_callee(ctx, callee) else { return };
+    let Some(func) = resolve_global_callee(ctx, callee) else {
+        // Complete values of annotated parameters of user-defined functions.
+        if let Some(call) = args_linked.parent()
+            && let Some(func) = resolve_callee(ctx.world, call, callee)
+            && let Some(ty) = closure_param_type(&func, name)
+        {
+            ctx.cast_completions(ty);
+            if ctx.before.ends_with(':') {
+                ctx.enrich(" ", "");
+            }
+        }
+        return;
+    };
     let Some(param) = func.param(name) else { return };
     if !param.named {
         return;
@@ -864,6 +940,20 @@ fn path_completion(func: &Func, param: &ParamInfo) -> Option<&'static [&'static
     })
 }
 
+/// The type annotation of the named parameter of a user-defined function.
+fn closure_param_type<'f>(func: &'f Func, name: &str) -> Option<&'f CastInfo> {
+    let closure = func.to_closure()?;
+    let ClosureNode::Closure(node) = &closure.node else { return None };
+    let params = node.cast::<ast::Closure>()?.params();
+    params
+        .children()
+        .zip(&closure.types)
+        .find_map(|(param, ty)| match param {
+            ast::Param::Named(named) if named.name().as_str() == name => ty.as_ref(),
+            _ => None,
+        })
+}
+
 /// Resolve a callee expression to a global function.
 fn resolve_global_callee<'a>(
     ctx: &CompletionContext<'a>,
@@ -1348,7 +1438,11 @@ impl<'a> CompletionContext<'a> {
 
         let detail = detail.map(Into::into).or_else(|| match value {
             Value::Symbol(_) => None,
-            Value::Func(func) => func.docs().map(plain_docs_sentence),
+            Value::Func(func) => func.docs().map(plain_docs_sentence).or_else(|| {
+                closure_docs(self.world, func)
+                    .filter(|docs| !docs.body.is_empty())
+                    .map(|docs| plain_docs_sentence(&docs.body))
+            }),
             Value::Type(ty) => Some(plain_docs_sentence(ty.docs())),
             v => {
                 let repr = v.repr();
@@ -1392,7 +1486,7 @@ impl<'a> CompletionContext<'a> {
     }
 
     /// Add completions for a castable.
-    fn cast_completions(&mut self, cast: &'a CastInfo) {
+    fn cast_completions(&mut self, cast: &CastInfo) {
         // Prevent duplicate completions from appearing.
         if !self.seen_casts.insert(typst::utils::hash128(cast)) {
             return;
@@ -1479,6 +1573,24 @@ impl<'a> CompletionContext<'a> {
         }
     }
 
+    /// Summarize the documentation comment of the let binding that defines the
+    /// identifier with the given span.
+    fn binding_docs(&self, span: Span) -> Option<EcoString> {
+        let mut root = self.leaf.clone();
+        while let Some(parent) = root.parent() {
+            root = parent.clone();
+        }
+
+        let ident = root.find(span)?;
+        let mut binding = ident.parent()?.clone();
+        if binding.kind() == SyntaxKind::Closure {
+            binding = binding.parent()?.clone();
+        }
+
+        let docs = binding.doc_comment()?;
+        (!docs.body.is_empty()).then(|| plain_docs_sentence(&docs.body))
+    }
+
     /// Add completions for definitions that are available at the cursor.
     ///
     /// Filters the global/math scope with the given filter.
@@ -1489,17 +1601,17 @@ impl<'a> CompletionContext<'a> {
         // colors.
         let filter = |value: &Value| check_value_recursively(value, &filter);
 
-        let mut defined = BTreeMap::<EcoString, Option<Value>>::new();
+        let mut defined = BTreeMap::<EcoString, (Option<Value>, Span)>::new();
         named_items(self.world, self.leaf.clone(), |item| {
             let name = item.name();
             if !name.is_empty() && item.value().as_ref().is_none_or(filter) {
-                defined.insert(name.clone(), item.value());
+                defined.insert(name.clone(), (item.value(), item.span()));
             }
 
             None::<()>
         });
 
-        for (name, value) in &defined {
+        for (name, (value, span)) in &defined {
             if let Some(value) = value {
                 self.value_completion(name.clone(), value);
             } else {
@@ -1507,7 +1619,7 @@ impl<'a> CompletionContext<'a> {
                     kind: CompletionKind::Constant,
                     label: name.clone(),
                     apply: None,
-                    detail: None,
+                    detail: self.binding_docs(*span),
                 });
             }
         }
@@ -1582,6 +1694,11 @@ mod tests {
         fn must_exclude<'a>(&self, excludes: impl IntoIterator<Item = &'a str>) -> &Self;
         fn must_apply<'a>(&self, label: &str, apply: impl Into<Option<&'a str>>)
         -> &Self;
+        fn must_detail<'a>(
+            &self,
+            label: &str,
+            detail: impl Into<Option<&'a str>>,
+        ) -> &Self;
     }
 
     impl ResponseExt for Response {
@@ -1643,6 +1760,20 @@ mod tests {
             assert_eq!(completion.apply.as_deref(), apply.into());
             self
         }
+
+        #[track_caller]
+        fn must_detail<'a>(
+            &self,
+            label: &str,
+            detail: impl Into<Option<&'a str>>,
+        ) -> &Self {
+            let Some(completion) = self.completions().iter().find(|c| c.label == label)
+            else {
+                panic!("found no completion for {label:?}");
+            };
+            assert_eq!(completion.detail.as_deref(), detail.into());
+            self
+        }
     }
 
     #[track_caller]
@@ -1698,6 +1829,32 @@ mod tests {
         test("#{ let x = (1, 2, 3); x. }", -3).must_include(["at", "push", "pop"]);
     }
 
+    #[test]
+    fn test_autocomplete_doc_comment() {
+        test("/// Greets someone. At length.\n#let greet(name) = name\n#g", -1)
+            .must_detail("greet", "Greets someone.");
+        test("// Greets someone.\n#let greet(name) = name\n#g", -1)
+            .must_detail("greet", None);
+
+        let world = TestWorld::new("#import \"other.typ\": greet\n#g")
+            .with_source("other.typ", "/// Greets someone.\n#let greet(name) = name");
+        test(&world, -1).must_detail("greet", "Greets someone.");
+    }
+
+    #[test]
+    fn test_autocomplete_typed_params() {
+        let world = TestWorld::new("#import \"other.typ\": f\n#f(").with_source(
+            "other.typ",
+            "/// - gap: The spacing.\n#let f(x as bool, gap: 1pt as (length, auto)) = x",
+        );
+        test(&world, -1)
+            .must_include(["true", "false", "gap"])
+            .must_detail("gap", "The spacing.");
+        test("#let f(x as bool, gap: 1pt as (length, auto)) = x\n#f(true, gap: ", -1)
+            .must_include(["auto"])
+            .must_exclude(["true"]);
+    }
+
     /// Test that extra space before '.' is handled correctly.
     #[test]
     fn test_autocomplete_whitespace() {
@@ -35,7 +35,10 @@ pub trait IdeWorld: World {
     /// This function is **optional** to implement. It enhances the user
     /// experience by enabling autocompletion for packages. Details about
     /// packages from the `@preview` namespace are available from
-    /// `https://packages.typst.org/preview/index.json`.
+    /// `https://packages.typst.org/preview/index.json`. Registries for other
+    /// namespaces serve an index in the same format, and `typst-kit`'s
+    /// `SystemPackages::list` collects the packages of all configured
+    /// registries.
     fn packages(&self) -> &[(PackageSpec, Option<EcoString>)] {
         &[]
     }
@@ -3,14 +3,16 @@ use std::fmt::Write;
 use ecow::{EcoString, eco_format};
 use typst::AsDocument;
 use typst::engine::Sink;
-use typst::foundations::{Binding, Capturer, CastInfo, Repr, Value, repr};
+use typst::foundations::{Capturer, CastInfo, Repr, Value, repr};
 use typst::layout::Length;
 use typst::syntax::ast::AstNode;
 use typst::syntax::{LinkedNode, Side, Source, SyntaxKind, ast};
 use typst::utils::{Numeric, round_with_precision};
 use typst_eval::CapturesVisitor;
 
-use crate::utils::{plain_docs_sentence, summarize_font_family};
+use crate::utils::{
+    closure_docs, plain_docs_sentence, resolve_callee, summarize_font_family,
+};
 use crate::{IdeWorld, analyze_expr, analyze_import, analyze_labels};
 
 /// Describe the item under the cursor.
@@ -66,6 +68,13 @@ fn expr_tooltip(world: &dyn IdeWorld, leaf: &LinkedNode) -> Option<Tooltip> {
             return Some(Tooltip::Text(plain_docs_sentence(docs)));
         }
 
+        if let Value::Func(func) = value
+            && let Some(docs) = closure_docs(world, func)
+            && !docs.body.is_empty()
+        {
+            return Some(Tooltip::Text(docs.body));
+        }
+
         if let &Value::Length(length) = value
             && let Some(tooltip) = length_tooltip(length)
         {
@@ -197,28 +206,30 @@ fn named_param_tooltip(world: &dyn IdeWorld, leaf: &LinkedNode) -> Option<Toolti
         && matches!(grand.kind(), SyntaxKind::Args)
         && let Some(grand_grand) = grand.parent()
         && let Some(expr) = grand_grand.cast::<ast::Expr>()
-        && let Some(ast::Expr::Ident(callee)) = match expr {
+        && let Some(callee) = match expr {
             ast::Expr::FuncCall(call) => Some(call.callee()),
             ast::Expr::SetRule(set) => Some(set.target()),
             _ => None,
         }
 
         // Find metadata about the function.
-        && let Some(Value::Func(func)) = world
-            .library()
-            .global
-            .scope()
-            .get(&callee)
-            .map(Binding::read)
+        && let Some(func) = resolve_callee(world, grand_grand, callee)
          { (func, named) }
         else { return None; };
 
     // Hovering over the parameter name.
     if leaf.index() == 0
         && let Some(ident) = leaf.cast::<ast::Ident>()
-        && let Some(param) = func.param(&ident)
     {
-        return Some(Tooltip::Text(plain_docs_sentence(param.docs)));
+        if let Some(param) = func.param(&ident) {
+            return Some(Tooltip::Text(plain_docs_sentence(param.docs)));
+        }
+
+        if let Some(docs) = closure_docs(world, &func)
+            && let Some(param) = docs.param(&ident)
+        {
+            return Some(Tooltip::Text(param.docs.clone()));
+        }
     }
 
     // Hovering over a string parameter value.
@@ -370,6 +381,18 @@ mod tests {
         test(&world, -4, Side::After).must_be_code("(..) => ..");
     }
 
+    #[test]
+    fn test_tooltip_doc_comment() {
+        let world = TestWorld::new("#import \"other.typ\": f\n#f(size: 1)").with_source(
+            "other.typ",
+            "/// Makes a box.\n/// - size (length): How large.\n#let f(size: 0pt) = 1",
+        );
+        test(&world, -10, Side::After).must_be_text("Makes a box.");
+        test(&world, -7, Side::After).must_be_text("How large.");
+        test("// Not docs\n#let f() = 1\n#f()", -3, Side::After)
+            .must_be_code("(..) => ..");
+    }
+
     #[test]
     fn test_tooltip_reference() {
         test("#figure(caption: [Hi])[]<f> @f", -1, Side::Before).must_be_text("Hi");
@@ -4,13 +4,14 @@ use std::ops::ControlFlow;
 use comemo::Track;
 use ecow::{EcoString, eco_format};
 use typst::engine::{Engine, Route, Sink, Traced};
-use typst::foundations::{Scope, Value};
+use typst::foundations::{Binding, Func, Scope, Value};
 use typst::introspection::Introspector;
-use typst::syntax::{LinkedNode, SyntaxKind};
+use typst::syntax::ast::AstNode;
+use typst::syntax::{DocComment, LinkedNode, SyntaxKind, ast};
 use typst::text::{FontInfo, FontStyle};
 use typst::utils::Protected;
 
-use crate::IdeWorld;
+use crate::{IdeWorld, analyze_expr};
 
 /// Create a temporary engine and run a task on it.
 pub fn with_engine<F, T>(world: &dyn IdeWorld, f: F) -> T
@@ -32,6 +33,44 @@ where
     f(&mut engine)
 }
 
+/// Find the documentation comment of a user-defined function, i.e. the `///`
+/// comments before the let binding that defines it.
+pub fn closure_docs(world: &dyn IdeWorld, func: &Func) -> Option<DocComment> {
+    // The span of a closure points to its parameters.
+    let span = func.span();
+    let source = world.source(span.id()?).ok()?;
+    let params = LinkedNode::new(source.root()).find(span)?;
+    let closure = params.parent()?;
+    if closure.kind() != SyntaxKind::Closure {
+        return None;
+    }
+
+    closure.parent()?.doc_comment()
+}
+
+/// Find the function called through the given callee expression.
+pub fn resolve_callee(
+    world: &dyn IdeWorld,
+    node: &LinkedNode,
+    callee: ast::Expr,
+) -> Option<Func> {
+    if let ast::Expr::Ident(ident) = callee
+        && let Some(Value::Func(func)) =
+            world.library().global.scope().get(&ident).map(Binding::read)
+    {
+        return Some(func.clone());
+    }
+
+    // Fall back to analyzing the callee for user-defined functions.
+    let callee = node.find(callee.span())?;
+    analyze_expr(world, &callee)
+        .into_iter()
+        .find_map(|(value, _)| match value {
+            Value::Func(func) => Some(func),
+            _ => None,
+        })
+}
+
 /// Extract the first sentence of plain text of a piece of documentation.
 ///
 /// Removes Markdown formatting.
@@ -3,12 +3,13 @@
 #![cfg(feature = "emit-diagnostics")]
 
 use std::collections::HashMap;
-use std::io;
+use std::io::{self, Write};
 use std::ops::Range;
 
 use codespan_reporting::diagnostic::{Diagnostic, Label};
 use codespan_reporting::term;
 use ecow::eco_format;
+use serde_json::{Value as Json, json};
 use term::termcolor::WriteColor;
 use typst_library::World;
 use typst_library::diag::{FileError, Severity, SourceDiagnostic};
@@ -35,6 +36,27 @@ pub enum DiagnosticFormat {
     Human,
     /// Displays a short single-line diagnostic.
     Short,
+    /// Displays each diagnostic as a JSON object on its own line.
+    ///
+    /// Each object has the fields `severity` (`"error"` or `"warning"`),
+    /// `message`, `span`, `hints`, and `trace`. A span is either `null` or an
+    /// object with the `file` path, the byte `range`, and the `start` and `end`
+    /// positions as 1-based `line` and `column` (counted in characters). The
+    /// end is exclusive. Hints have a `message` and a `span`, tracepoints
+    /// additionally have the `args` of the traced call (or `null`).
+    Json,
+    /// Displays all diagnostics as a single SARIF 2.1.0 log.
+    Sarif,
+}
+
+/// Options for emitting diagnostics.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq)]
+pub struct DiagnosticOptions {
+    /// The format to emit diagnostics in.
+    pub format: DiagnosticFormat,
+    /// Whether traced calls of user-defined functions also show the arguments
+    /// they were called with in the human-readable formats.
+    pub trace_args: bool,
 }
 
 /// Emits diagnostic messages to a writable, colorized output.
@@ -42,10 +64,17 @@ pub fn emit<'a>(
     dest: &mut dyn WriteColor,
     world: &dyn DiagnosticWorld,
     diagnostics: impl IntoIterator<Item = &'a SourceDiagnostic>,
-    format: DiagnosticFormat,
+    options: DiagnosticOptions,
 ) -> Result<(), codespan_reporting::files::Error> {
     let mut files = WorldFiles { world, sources: HashMap::new() };
 
+    let DiagnosticOptions { format, trace_args } = options;
+    match format {
+        DiagnosticFormat::Json => return emit_json(dest, &mut files, diagnostics),
+        DiagnosticFormat::Sarif => return emit_sarif(dest, &mut files, diagnostics),
+        DiagnosticFormat::Human | DiagnosticFormat::Short => {}
+    }
+
     let mut config = term::Config { tab_width: 2, ..Default::default() };
     if format == DiagnosticFormat::Short {
         config.display_style = term::DisplayStyle::Short;
@@ -80,7 +109,10 @@ pub fn emit<'a>(
 
         // Stacktrace-like helper diagnostics.
         for point in &diagnostic.trace {
-            let message = point.v.to_string();
+            let message = match point.v.args() {
+                Some(args) if trace_args => format!("{} with arguments {args}", point.v),
+                _ => point.v.to_string(),
+            };
             let help = Diagnostic::help()
                 .with_message(message)
                 .with_labels(label(&mut files, point.span).into_iter().collect());
@@ -92,6 +124,169 @@ pub fn emit<'a>(
     Ok(())
 }
 
+/// Emits diagnostics as JSON lines.
+fn emit_json<'a>(
+    dest: &mut dyn WriteColor,
+    files: &mut WorldFiles,
+    diagnostics: impl IntoIterator<Item = &'a SourceDiagnostic>,
+) -> CodespanResult<()> {
+    for diagnostic in diagnostics {
+        let hints: Vec<Json> = diagnostic
+            .hints
+            .iter()
+            .map(|hint| {
+                json!({
+                    "message": hint.v.as_str(),
+                    "span": json_span(files, hint.span),
+                })
+            })
+            .collect();
+        let trace: Vec<Json> = diagnostic
+            .trace
+            .iter()
+            .map(|point| {
+                json!({
+                    "message": point.v.to_string(),
+                    "args": point.v.args(),
+                    "span": json_span(files, point.span),
+                })
+            })
+            .collect();
+        let object = json!({
+            "severity": severity_name(diagnostic.severity),
+            "message": diagnostic.message.as_str(),
+            "span": json_span(files, diagnostic.span),
+            "hints": hints,
+            "trace": trace,
+        });
+        serde_json::to_writer(&mut *dest, &object)
+            .map_err(|err| CodespanError::Io(err.into()))?;
+        writeln!(dest).map_err(CodespanError::Io)?;
+    }
+    Ok(())
+}
+
+/// Emits diagnostics as a SARIF log.
+fn emit_sarif<'a>(
+    dest: &mut dyn WriteColor,
+    files: &mut WorldFiles,
+    diagnostics: impl IntoIterator<Item = &'a SourceDiagnostic>,
+) -> CodespanResult<()> {
+    let mut results = vec![];
+    for diagnostic in diagnostics {
+        let mut result = json!({
+            "level": severity_name(diagnostic.severity),
+            "message": { "text": diagnostic.message.as_str() },
+        });
+
+        if let Some(location) = sarif_location(files, diagnostic.span, None) {
+            result["locations"] = json!([location]);
+        }
+
+        let related: Vec<Json> = diagnostic
+            .hints
+            .iter()
+            .filter_map(|hint| sarif_location(files, hint.span, Some(&hint.v)))
+            .collect();
+        if !related.is_empty() {
+            result["relatedLocations"] = related.into();
+        }
+
+        let hints: Vec<&str> = diagnostic
+            .hints
+            .iter()
+            .filter(|hint| hint.span.is_detached())
+            .map(|hint| hint.v.as_str())
+            .collect();
+        if !hints.is_empty() {
+            result["properties"] = json!({ "hints": hints });
+        }
+
+        // The trace lists the innermost frame first, like a call stack.
+        let frames: Vec<Json> = diagnostic
+            .trace
+            .iter()
+            .filter_map(|point| {
+                let message = point.v.to_string();
+                let location = sarif_location(files, point.span, Some(&message))?;
+                let mut frame = json!({ "location": location });
+                if let Some(args) = point.v.args() {
+                    frame["parameters"] = json!([args]);
+                }
+                Some(frame)
+            })
+            .collect();
+        if !frames.is_empty() {
+            result["stacks"] = json!([{ "frames": frames }]);
+        }
+
+        results.push(result);
+    }
+
+    let log = json!({
+        "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "typst",
+                    "informationUri": "https://typst.app",
+                },
+            },
+            "results": results,
+        }],
+    });
+    serde_json::to_writer_pretty(&mut *dest, &log)
+        .map_err(|err| CodespanError::Io(err.into()))?;
+    writeln!(dest).map_err(CodespanError::Io)?;
+    Ok(())
+}
+
+/// The user-facing name of a severity.
+fn severity_name(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Error => "error",
+        Severity::Warning => "warning",
+    }
+}
+
+/// Describes a span in the JSON diagnostic format.
+fn json_span(files: &mut WorldFiles, span: Span) -> Json {
+    let Some(location) = files.locate(span) else { return Json::Null };
+    json!({
+        "file": location.file,
+        "range": { "start": location.range.start, "end": location.range.end },
+        "start": { "line": location.start.0, "column": location.start.1 },
+        "end": { "line": location.end.0, "column": location.end.1 },
+    })
+}
+
+/// Describes a span as a SARIF location, optionally with a message.
+fn sarif_location(
+    files: &mut WorldFiles,
+    span: Span,
+    message: Option<&str>,
+) -> Option<Json> {
+    let location = files.locate(span)?;
+    let mut json = json!({
+        "physicalLocation": {
+            "artifactLocation": { "uri": location.file.replace('\\', "/") },
+            "region": {
+                "startLine": location.start.0,
+                "startColumn": location.start.1,
+                "endLine": location.end.0,
+                "endColumn": location.end.1,
+                "byteOffset": location.range.start,
+                "byteLength": location.range.len(),
+            },
+        },
+    });
+    if let Some(message) = message {
+        json["message"] = json!({ "text": message });
+    }
+    Some(json)
+}
+
 /// Create a label for a span.
 fn label(files: &mut WorldFiles, span: Span) -> Option<Label<FileId>> {
     Some(Label::primary(span.id()?, files.range(span)?))
@@ -116,6 +311,23 @@ impl WorldFiles<'_> {
         })
     }
 
+    /// Resolve a span to a file name and 1-based line and column positions.
+    fn locate(&mut self, span: Span) -> Option<Location> {
+        let id = span.id()?;
+        let range = self.range(span)?;
+        let lines = self.lines(id).ok()?;
+        let position = |byte| {
+            let (line, column) = lines.byte_to_line_column(byte)?;
+            Some((line + 1, column + 1))
+        };
+        Some(Location {
+            file: self.world.name(id),
+            start: position(range.start)?,
+            end: position(range.end)?,
+            range,
+        })
+    }
+
     /// Lookup line metadata for a file by id. If a source file was remembered,
     /// it will be used. Otherwise, we load as a file as compute line metadata.
     fn lines(&self, id: FileId) -> CodespanResult<Lines<String>> {
@@ -133,6 +345,18 @@ impl WorldFiles<'_> {
     }
 }
 
+/// A span resolved to positions in a file.
+struct Location {
+    /// The user-facing name of the file.
+    file: String,
+    /// The byte range in the file.
+    range: Range<usize>,
+    /// The 1-based line and column of the start.
+    start: (usize, usize),
+    /// The 1-based line and column of the (exclusive) end.
+    end: (usize, usize),
+}
+
 impl<'a> codespan_reporting::files::Files<'a> for WorldFiles<'_> {
     type FileId = FileId;
     type Name = String;
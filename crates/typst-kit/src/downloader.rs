@@ -2,7 +2,8 @@
 //!
 //! All `typst-kit` functionality that may trigger downloads goes through the
 //! [`Downloader`] trait. A built-in implementation is provided through the
-//! [`SystemDownloader`].
+//! [`SystemDownloader`]. The [`OfflineDownloader`] can be used to rule out
+//! network access entirely.
 //!
 //! Downloads can optionally be tracked by wrapping an existing downloader in a
 //! [`ProgressDownloader`]. All downloads are identified by a dynamic key, so
@@ -55,7 +56,7 @@ pub trait Downloader: Send + Sync + 'static {
     }
 }
 
-impl<T: Downloader> Downloader for Box<T> {
+impl<T: Downloader + ?Sized> Downloader for Box<T> {
     fn stream(
         &self,
         key: &dyn Any,
@@ -69,7 +70,7 @@ impl<T: Downloader> Downloader for Box<T> {
     }
 }
 
-impl<T: Downloader> Downloader for Arc<T> {
+impl<T: Downloader + ?Sized> Downloader for Arc<T> {
     fn stream(
         &self,
         key: &dyn Any,
@@ -83,6 +84,19 @@ impl<T: Downloader> Downloader for Arc<T> {
     }
 }
 
+/// A downloader that refuses all downloads.
+///
+/// Can be used in place of another downloader to ensure that no network
+/// access happens, e.g. for offline builds.
+#[derive(Debug, Default, Copy, Clone)]
+pub struct OfflineDownloader;
+
+impl Downloader for OfflineDownloader {
+    fn stream(&self, _: &dyn Any, _: &str) -> io::Result<(Option<usize>, Box<dyn Read>)> {
+        Err(io::Error::other("network access is disabled in offline mode"))
+    }
+}
+
 /// A minimal HTTPS client for downloads.
 ///
 /// Uses system-native TLS and respects proxying environment variables.
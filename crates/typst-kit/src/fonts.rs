@@ -1,14 +1,22 @@
 //! Font loading and management.
 //!
 //! This provides implementations to discover fonts [in directories](scan) and
-//! [from system](system) and can also serve standard [embedded] fonts.
+//! [from system](system) and can also serve standard [embedded] fonts. A
+//! [`FontIndex`] can speed up repeated discovery by remembering the metadata
+//! of font files that did not change.
 
 use std::any::Any;
+#[cfg(feature = "scan-fonts")]
+use std::collections::{HashMap, HashSet};
 use std::fs;
+#[cfg(feature = "scan-fonts")]
+use std::path::Path;
 use std::path::PathBuf;
 use std::sync::OnceLock;
 
 use typst_library::foundations::Bytes;
+#[cfg(feature = "scan-fonts")]
+use typst_library::text::woff;
 use typst_library::text::{Font, FontBook, FontInfo};
 use typst_utils::LazyHash;
 
@@ -24,6 +32,8 @@ use typst_utils::LazyHash;
 pub struct FontStore {
     book: LazyHash<FontBook>,
     slots: Vec<FontSlot>,
+    #[cfg(feature = "scan-fonts")]
+    index: Option<FontIndex>,
 }
 
 impl FontStore {
@@ -32,6 +42,55 @@ impl FontStore {
         Self {
             book: LazyHash::new(FontBook::new()),
             slots: Vec::new(),
+            #[cfg(feature = "scan-fonts")]
+            index: None,
+        }
+    }
+
+    /// Uses the given index for [`search_system`](Self::search_system) and
+    /// [`search_dir`](Self::search_dir).
+    ///
+    /// The index is only read from disk once fonts are searched.
+    #[cfg(feature = "scan-fonts")]
+    pub fn with_index(mut self, index: FontIndex) -> Self {
+        self.index = Some(index);
+        self
+    }
+
+    /// Adds the system fonts to the store.
+    ///
+    /// Like [`system`], but goes through the font index if there is one.
+    #[cfg(feature = "scan-fonts")]
+    pub fn search_system(&mut self) {
+        match &mut self.index {
+            Some(index) => {
+                let fonts = index.search_system();
+                self.extend(fonts);
+            }
+            None => self.extend(system()),
+        }
+    }
+
+    /// Adds the fonts in a directory to the store.
+    ///
+    /// Like [`scan`], but goes through the font index if there is one.
+    #[cfg(feature = "scan-fonts")]
+    pub fn search_dir(&mut self, path: &Path) {
+        match &mut self.index {
+            Some(index) => {
+                let fonts = index.search_dir(path);
+                self.extend(fonts);
+            }
+            None => self.extend(scan(path)),
+        }
+    }
+
+    /// Writes the font index back to disk if searching for fonts changed it.
+    #[cfg(feature = "scan-fonts")]
+    pub fn save_index(&mut self) -> std::io::Result<()> {
+        match &mut self.index {
+            Some(index) => index.save(),
+            None => Ok(()),
         }
     }
 
@@ -147,42 +206,369 @@ pub fn embedded() -> impl Iterator<Item = (Font, FontInfo)> {
 #[cfg(feature = "scan-fonts")]
 pub fn system() -> impl Iterator<Item = (FontPath, FontInfo)> {
     let _scope = typst_timing::TimingScope::new("scan system fonts");
-    with_db(|db| db.load_system_fonts())
+    parse_font_files(collect_font_files(system_font_dirs())).into_iter()
 }
 
 /// Scans for fonts in a directory.
 ///
 /// The directory is searched recursively.
 #[cfg(feature = "scan-fonts")]
-pub fn scan(path: &std::path::Path) -> impl Iterator<Item = (FontPath, FontInfo)> {
+pub fn scan(path: &Path) -> impl Iterator<Item = (FontPath, FontInfo)> {
     let _scope = typst_timing::TimingScope::new("scan system fonts");
-    with_db(move |db| db.load_fonts_dir(path))
-}
-
-/// Discovers fonts via `fontdb`.
-#[cfg(feature = "scan-fonts")]
-fn with_db(
-    f: impl FnOnce(&mut fontdb::Database),
-) -> impl Iterator<Item = (FontPath, FontInfo)> {
-    let mut db = fontdb::Database::new();
-    f(&mut db);
-    db.faces()
-        .filter_map(|face| {
-            let path = match &face.source {
-                fontdb::Source::File(path) | fontdb::Source::SharedFile(path, _) => path,
-                // We never add binary sources to the database, so there
-                // shouldn't be any.
-                fontdb::Source::Binary(_) => return None,
+    parse_font_files(collect_font_files([path])).into_iter()
+}
+
+/// Parses the fonts in the given files.
+#[cfg(feature = "scan-fonts")]
+fn parse_font_files(files: Vec<PathBuf>) -> Vec<(FontPath, FontInfo)> {
+    files
+        .into_iter()
+        .flat_map(|path| {
+            parse_font_file(&path)
+                .into_iter()
+                .map(move |(index, info)| (FontPath { path: path.clone(), index }, info))
+        })
+        .collect()
+}
+
+/// An on-disk index of the fonts in font files.
+///
+/// Computing the metadata of a font requires parsing it, which adds up for
+/// systems with thousands of fonts. The index remembers the metadata of each
+/// font file along with its modification time and size, so that only new or
+/// changed files need to be parsed. Which files are searched is not affected
+/// by the index.
+#[cfg(feature = "scan-fonts")]
+pub struct FontIndex {
+    path: PathBuf,
+    data: Option<IndexData>,
+    changed: bool,
+}
+
+#[cfg(feature = "scan-fonts")]
+impl FontIndex {
+    /// Creates an index that is stored at the given path.
+    ///
+    /// The file is only read once the index is used.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into(), data: None, changed: false }
+    }
+
+    /// Creates an index in the environment-defined standard cache directory.
+    ///
+    /// This is:
+    /// - `$XDG_CACHE_HOME/typst/fonts.json` or `~/.cache/typst/fonts.json` on
+    ///   Linux
+    /// - `~/Library/Caches/typst/fonts.json` on macOS
+    /// - `%LOCALAPPDATA%/typst/fonts.json` on Windows
+    pub fn system() -> Option<Self> {
+        dirs::cache_dir().map(|dir| Self::new(dir.join("typst/fonts.json")))
+    }
+
+    /// The path at which the index is stored.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Discards all indexed fonts, so that all font files are parsed again.
+    pub fn invalidate(&mut self) {
+        self.data = Some(IndexData::default());
+        self.changed = true;
+    }
+
+    /// Writes the index to disk if it changed since it was read.
+    ///
+    /// Files that no longer exist are dropped from the index.
+    pub fn save(&mut self) -> std::io::Result<()> {
+        let Some(data) = &mut self.data else { return Ok(()) };
+        if !self.changed {
+            return Ok(());
+        }
+
+        data.files.retain(|path, _| path.exists());
+        let json = serde_json::to_vec(data).map_err(std::io::Error::other)?;
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        // Write to a temporary file first so that concurrent readers never
+        // observe a partially written index.
+        let temp = self.path.with_extension(format!("{}.tmp", std::process::id()));
+        fs::write(&temp, json)?;
+        fs::rename(&temp, &self.path)?;
+        self.changed = false;
+        Ok(())
+    }
+
+    /// Discovers system fonts like [`system`].
+    fn search_system(&mut self) -> Vec<(FontPath, FontInfo)> {
+        let _scope = typst_timing::TimingScope::new("search indexed system fonts");
+        self.fonts_in(collect_font_files(system_font_dirs()))
+    }
+
+    /// Discovers fonts in a directory like [`scan`].
+    fn search_dir(&mut self, path: &Path) -> Vec<(FontPath, FontInfo)> {
+        let _scope = typst_timing::TimingScope::new("search indexed fonts");
+        self.fonts_in(collect_font_files([path]))
+    }
+
+    /// Returns the fonts in the given files, parsing only those files that
+    /// are not indexed or changed since they were indexed.
+    fn fonts_in(&mut self, mut files: Vec<PathBuf>) -> Vec<(FontPath, FontInfo)> {
+        files.sort();
+        files.dedup();
+
+        let mut fonts = vec![];
+        for path in files {
+            let Some((modified, size)) = stamp(&path) else { continue };
+            let data = self.data();
+            let entry = match data.files.get(&path) {
+                Some(entry) if entry.modified == modified && entry.size == size => {
+                    entry.fonts.clone()
+                }
+                _ => {
+                    let entry = IndexedFile::parse(&path, modified, size);
+                    let fonts = entry.fonts.clone();
+                    // Paths that can't be represented in the index are simply
+                    // parsed every time.
+                    if path.to_str().is_some() {
+                        data.files.insert(path.clone(), entry);
+                        self.changed = true;
+                    }
+                    fonts
+                }
             };
 
-            let info = db
-                .with_face_data(face.id, FontInfo::new)
-                .expect("database must contain this font")?;
+            fonts.extend(
+                entry
+                    .into_iter()
+                    .map(|(index, info)| (FontPath { path: path.clone(), index }, info)),
+            );
+        }
 
-            let path = FontPath { path: path.clone(), index: face.index };
+        fonts
+    }
 
-            Some((path, info))
+    /// The contents of the index, which are read on first access.
+    fn data(&mut self) -> &mut IndexData {
+        self.data.get_or_insert_with(|| {
+            fs::read(&self.path)
+                .ok()
+                .and_then(|bytes| serde_json::from_slice::<IndexData>(&bytes).ok())
+                .filter(|data| data.version == env!("CARGO_PKG_VERSION"))
+                .unwrap_or_default()
         })
-        .collect::<Vec<_>>()
-        .into_iter()
+    }
+}
+
+/// The serialized contents of a [`FontIndex`].
+#[cfg(feature = "scan-fonts")]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct IndexData {
+    /// The version of the crate that wrote the index. Font metadata may
+    /// change between versions, so indices from other versions are discarded.
+    version: String,
+    /// The indexed font files.
+    files: HashMap<PathBuf, IndexedFile>,
+}
+
+#[cfg(feature = "scan-fonts")]
+impl Default for IndexData {
+    fn default() -> Self {
+        Self {
+            version: env!("CARGO_PKG_VERSION").into(),
+            files: HashMap::new(),
+        }
+    }
+}
+
+/// The fonts in a single font file.
+#[cfg(feature = "scan-fonts")]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct IndexedFile {
+    /// The modification time of the file when it was indexed.
+    modified: std::time::SystemTime,
+    /// The size of the file when it was indexed.
+    size: u64,
+    /// The metadata of the fonts in the file along with their index in the
+    /// collection.
+    fonts: Vec<(u32, FontInfo)>,
+}
+
+#[cfg(feature = "scan-fonts")]
+impl IndexedFile {
+    /// Parses the fonts in a file.
+    fn parse(path: &Path, modified: std::time::SystemTime, size: u64) -> Self {
+        let _scope = typst_timing::TimingScope::new("index font file");
+        Self { modified, size, fonts: parse_font_file(path) }
+    }
+}
+
+/// The modification time and size of a file, which identify the version of
+/// the file that was indexed.
+#[cfg(feature = "scan-fonts")]
+fn stamp(path: &Path) -> Option<(std::time::SystemTime, u64)> {
+    let metadata = fs::metadata(path).ok()?;
+    Some((metadata.modified().ok()?, metadata.len()))
+}
+
+/// Parses the fonts in a font file along with their index in the collection.
+///
+/// WOFF and WOFF2 files are decoded first.
+#[cfg(feature = "scan-fonts")]
+fn parse_font_file(path: &Path) -> Vec<(u32, FontInfo)> {
+    let Ok(mut data) = fs::read(path) else { return vec![] };
+    if woff::is_woff(&data) {
+        let Some(decoded) = woff::decode(&data) else { return vec![] };
+        data = decoded;
+    }
+
+    let count = ttf_parser::fonts_in_collection(&data).unwrap_or(1);
+    (0..count)
+        .filter_map(|index| Some((index, FontInfo::new(&data, index)?)))
+        .collect()
+}
+
+/// Recursively collects the font files in the given directories.
+///
+/// Symbolic links are followed, but each directory is only walked once, even
+/// if it is reachable through multiple paths or a symbolic link loop.
+#[cfg(feature = "scan-fonts")]
+fn collect_font_files<P: AsRef<Path>>(dirs: impl IntoIterator<Item = P>) -> Vec<PathBuf> {
+    let mut visited = HashSet::new();
+    let mut files = vec![];
+    for dir in dirs {
+        collect_font_files_in(dir.as_ref(), &mut visited, &mut files);
+    }
+    files
+}
+
+/// Collects the font files in a directory and its unvisited subdirectories.
+#[cfg(feature = "scan-fonts")]
+fn collect_font_files_in(
+    dir: &Path,
+    visited: &mut HashSet<PathBuf>,
+    files: &mut Vec<PathBuf>,
+) {
+    let Ok(canonical) = fs::canonicalize(dir) else { return };
+    if !visited.insert(canonical) {
+        return;
+    }
+
+    let Ok(entries) = fs::read_dir(dir) else { return };
+    for entry in entries.filter_map(|entry| entry.ok()) {
+        let Ok(mut file_type) = entry.file_type() else { continue };
+        let path = entry.path();
+        if file_type.is_symlink() {
+            let Ok(metadata) = fs::metadata(&path) else { continue };
+            file_type = metadata.file_type();
+        }
+
+        if file_type.is_dir() {
+            collect_font_files_in(&path, visited, files);
+        } else if file_type.is_file() && is_font_file(&path) {
+            files.push(path);
+        }
+    }
+}
+
+/// The directories in which the operating system keeps fonts.
+#[cfg(feature = "scan-fonts")]
+fn system_font_dirs() -> Vec<PathBuf> {
+    let mut dirs = vec![];
+
+    #[cfg(target_os = "windows")]
+    {
+        if let Some(root) = std::env::var_os("SYSTEMROOT") {
+            dirs.push(Path::new(&root).join("Fonts"));
+        } else {
+            dirs.push(PathBuf::from("C:\\Windows\\Fonts"));
+        }
+
+        if let Some(profile) = std::env::var_os("USERPROFILE") {
+            let profile = Path::new(&profile);
+            dirs.push(profile.join("AppData\\Local\\Microsoft\\Windows\\Fonts"));
+            dirs.push(profile.join("AppData\\Roaming\\Microsoft\\Windows\\Fonts"));
+        }
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        dirs.push(PathBuf::from("/Library/Fonts"));
+        dirs.push(PathBuf::from("/System/Library/Fonts"));
+
+        // Downloadable fonts, whose location varies between releases.
+        if let Ok(entries) = fs::read_dir("/System/Library/AssetsV2") {
+            dirs.extend(
+                entries
+                    .filter_map(|entry| entry.ok())
+                    .filter(|entry| {
+                        entry
+                            .file_name()
+                            .to_string_lossy()
+                            .starts_with("com_apple_MobileAsset_Font")
+                    })
+                    .map(|entry| entry.path()),
+            );
+        }
+
+        dirs.push(PathBuf::from("/Network/Library/Fonts"));
+        if let Some(home) = std::env::var_os("HOME") {
+            dirs.push(Path::new(&home).join("Library/Fonts"));
+        }
+    }
+
+    #[cfg(target_os = "android")]
+    dirs.push(PathBuf::from("/system/fonts"));
+
+    #[cfg(target_os = "redox")]
+    dirs.push(PathBuf::from("/ui/fonts"));
+
+    #[cfg(all(
+        unix,
+        not(any(target_os = "macos", target_os = "android", target_os = "redox"))
+    ))]
+    {
+        let home = std::env::var_os("HOME").map(PathBuf::from);
+
+        // Prefer the directories configured for fontconfig.
+        let mut config = fontconfig_parser::FontConfig::default();
+        match std::env::var_os("FONTCONFIG_FILE") {
+            Some(file) => {
+                config.merge_config(Path::new(&file)).ok();
+            }
+            None => {
+                config.merge_config(Path::new("/etc/fonts/fonts.conf")).ok();
+            }
+        }
+
+        for dir in config.dirs {
+            // Fontconfig expands a leading tilde to the home directory.
+            match dir.path.strip_prefix("~") {
+                Ok(rest) => dirs.extend(home.as_ref().map(|home| home.join(rest))),
+                Err(_) => dirs.push(dir.path),
+            }
+        }
+
+        if dirs.is_empty() {
+            dirs.push(PathBuf::from("/usr/share/fonts"));
+            dirs.push(PathBuf::from("/usr/local/share/fonts"));
+            if let Some(home) = &home {
+                dirs.push(home.join(".local/share/fonts"));
+            }
+        }
+    }
+
+    dirs
+}
+
+/// Whether a path has the extension of a font file.
+#[cfg(feature = "scan-fonts")]
+fn is_font_file(path: &Path) -> bool {
+    path.extension().and_then(|ext| ext.to_str()).is_some_and(|ext| {
+        matches!(
+            ext.to_ascii_lowercase().as_str(),
+            "ttf" | "otf" | "ttc" | "otc" | "woff" | "woff2"
+        )
+    })
 }
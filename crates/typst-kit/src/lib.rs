@@ -11,7 +11,8 @@
 //! - `embedded-fonts`: Enables loading of embedded fonts via
 //!   [`fonts::embedded`].
 //! - `scan-fonts`: Enables font discovery at paths and from the system via
-//!   [`fonts::scan`] and [`fonts::system`].
+//!   [`fonts::scan`] and [`fonts::system`], optionally sped up by a
+//!   [`fonts::FontIndex`].
 //! - `system-files`: Enables loading of files from standard locations via
 //!   [`files::SystemFiles`].
 //! - `system-packages`: Enables loading of packages from standard locations via
@@ -2,7 +2,7 @@
 
 use std::path::{Path, PathBuf};
 
-use ecow::eco_format;
+use ecow::{EcoString, eco_format};
 use typst_syntax::package::{PackageSpec, PackageVersion, VersionlessPackageSpec};
 
 use crate::files::FsRoot;
@@ -12,28 +12,45 @@ use {
     crate::downloader::Downloader,
     once_cell::sync::OnceCell,
     serde::Deserialize,
+    std::any::Any,
     std::io::{Cursor, Read},
     typst_library::diag::{PackageError, PackageResult, StrResult, bail},
 };
+#[cfg(feature = "system-packages")]
+use {
+    parking_lot::{Mutex, MutexGuard},
+    sha2::{Digest, Sha256},
+    std::collections::{BTreeMap, HashSet},
+};
 
 /// Serves packages from standard locations.
 ///
 /// In order of priority, this tries to obtain a package from
 ///
+/// - a project-local vendor directory (if configured via
+///   [`with_vendor`](Self::with_vendor))
 /// - a package data directory (that is intended for system-wide storage of user
 ///   packages)
 /// - a package cache directory (that is intended for caching of automatically
 ///   downloaded packages)
+/// - by downloading it from a registry configured for its namespace via
+///   [`with_registry`](Self::with_registry)
 /// - by downloading it from Typst Universe or a mirror of it (if it's namespace
 ///   matches the one Typst Universe serves)
 ///
 /// With default configuration, this loads packages from the same sources as the
 /// CLI.
+///
+/// If a [`PackageLock`] is attached, the contents of all obtained packages are
+/// verified against it.
 #[cfg(feature = "system-packages")]
 pub struct SystemPackages {
+    vendor: Option<FsPackages>,
     data: Option<FsPackages>,
     cache: Option<FsPackages>,
     universe: UniversePackages,
+    registries: Vec<UniversePackages>,
+    lock: Option<Mutex<PackageLock>>,
 }
 
 #[cfg(feature = "system-packages")]
@@ -61,7 +78,51 @@ impl SystemPackages {
         cache: Option<FsPackages>,
         universe: UniversePackages,
     ) -> Self {
-        Self { data, cache, universe }
+        Self {
+            vendor: None,
+            data,
+            cache,
+            universe,
+            registries: Vec::new(),
+            lock: None,
+        }
+    }
+
+    /// Adds a directory with vendored packages that takes precedence over all
+    /// other sources.
+    pub fn with_vendor(mut self, vendor: FsPackages) -> Self {
+        self.vendor = Some(vendor);
+        self
+    }
+
+    /// Adds a registry that serves packages for its namespace.
+    ///
+    /// Takes precedence over previously added registries and Typst Universe
+    /// for the same namespace.
+    pub fn with_registry(mut self, registry: UniversePackages) -> Self {
+        self.registries
+            .retain(|other| other.namespace() != registry.namespace());
+        self.registries.push(registry);
+        self
+    }
+
+    /// Attaches a lock against which all obtained packages are verified.
+    ///
+    /// Packages that are not yet in the lock are added to it, unless it is
+    /// [frozen](PackageLock::frozen).
+    pub fn with_lock(mut self, lock: PackageLock) -> Self {
+        self.lock = Some(Mutex::new(lock));
+        self
+    }
+
+    /// Returns a handle to the attached lock, if any.
+    pub fn lock(&self) -> Option<MutexGuard<'_, PackageLock>> {
+        self.lock.as_ref().map(Mutex::lock)
+    }
+
+    /// Returns a handle to the vendor package directory.
+    pub fn vendor(&self) -> Option<&FsPackages> {
+        self.vendor.as_ref()
     }
 
     /// Returns a handle to the data package directory.
@@ -79,6 +140,29 @@ impl SystemPackages {
         &self.universe
     }
 
+    /// Returns a handle to the registry that serves the given namespace, if
+    /// any.
+    pub fn registry(&self, namespace: &str) -> Option<&UniversePackages> {
+        self.registries
+            .iter()
+            .chain(std::iter::once(&self.universe))
+            .find(|registry| registry.namespace() == namespace)
+    }
+
+    /// Lists the packages available from all registries along with their
+    /// descriptions.
+    ///
+    /// This is suitable for providing package completions. Registries whose
+    /// index cannot be fetched are skipped.
+    pub fn list(&self) -> Vec<(PackageSpec, Option<EcoString>)> {
+        self.registries
+            .iter()
+            .chain(std::iter::once(&self.universe))
+            .filter_map(|registry| registry.list().ok())
+            .flatten()
+            .collect()
+    }
+
     /// Returns the file system root from which the given package's content can
     /// be loaded.
     ///
@@ -90,26 +174,36 @@ impl SystemPackages {
     /// efficiency, it may be desirable to avoid them. If you use the
     /// [`FileStore`](crate::files::FileStore), this is already the case since
     /// it acquires a lock during file loading.
+    ///
+    /// If a lock is attached, the package's contents are verified against it.
+    /// Downloaded packages are verified before they are stored in the cache.
     pub fn obtain(&self, spec: &PackageSpec) -> PackageResult<FsRoot> {
-        if let Some(packages) = &self.data
-            && let Some(root) = packages.obtain(spec)
-        {
+        if let Some(lock) = self.lock() {
+            lock.ensure_known(spec)?;
+        }
+
+        // Vendored packages take precedence over local ones.
+        let mut local = self.vendor.iter().chain(&self.data);
+        if let Some(root) = local.find_map(|packages| packages.obtain(spec)) {
+            self.verify(spec, root.path())?;
             return Ok(root);
         }
 
         if let Some(cache) = &self.cache {
             if let Some(root) = cache.obtain(spec) {
+                self.verify(spec, root.path())?;
                 return Ok(root);
             }
 
-            // Download from network if it doesn't exist yet.
-            if spec.namespace == UniversePackages::NAMESPACE {
-                let mut archive = self.universe.package(spec)?;
+            // Download from a registry if it doesn't exist yet.
+            if let Some(registry) = self.registry(&spec.namespace) {
+                let mut archive = registry.package(spec)?;
 
                 cache.store(spec, |tempdir| {
                     archive.unpack(tempdir).map_err(|err| {
                         PackageError::MalformedArchive(Some(eco_format!("{err}")))
-                    })
+                    })?;
+                    self.verify(spec, tempdir)
                 })?;
 
                 if let Some(root) = cache.obtain(spec) {
@@ -121,13 +215,28 @@ impl SystemPackages {
         Err(PackageError::NotFound(spec.clone()))
     }
 
+    /// Verifies the contents of a package directory against the lock.
+    fn verify(&self, spec: &PackageSpec, dir: &Path) -> PackageResult<()> {
+        let Some(lock) = &self.lock else { return Ok(()) };
+        if lock.lock().is_verified(spec) {
+            return Ok(());
+        }
+
+        // Hash without holding the lock, since this may take a while.
+        let hash = PackageLock::hash_dir(dir).map_err(|err| {
+            PackageError::Other(Some(eco_format!("failed to hash package: {err}")))
+        })?;
+
+        lock.lock().check(spec, hash)
+    }
+
     /// Tries to determine the latest version of a package.
     pub fn latest_version(
         &self,
         spec: &VersionlessPackageSpec,
     ) -> StrResult<PackageVersion> {
-        if spec.namespace == UniversePackages::NAMESPACE {
-            self.universe.latest_version(spec)
+        if let Some(registry) = self.registry(&spec.namespace) {
+            registry.latest_version(spec)
         } else {
             // For other namespaces, search locally. We only search in the data
             // directory and not the cache directory, because the latter is not
@@ -140,6 +249,171 @@ impl SystemPackages {
     }
 }
 
+/// Records the exact contents of the packages a project depends on.
+///
+/// Each package is identified by its specification and a content hash. The
+/// hash covers the relative paths and contents of all files in the package
+/// directory, so it does not depend on where or how a package was obtained.
+#[cfg(feature = "system-packages")]
+#[derive(Debug, Default, Clone)]
+pub struct PackageLock {
+    /// The locked packages and their content hashes.
+    packages: BTreeMap<EcoString, EcoString>,
+    /// Packages whose contents were verified in this session.
+    verified: HashSet<EcoString>,
+    /// Whether packages may not be added to or updated in the lock.
+    frozen: bool,
+    /// Whether packages were added, updated, or removed since the lock was
+    /// created.
+    changed: bool,
+    /// Warnings about updated packages that were not yet taken.
+    warnings: Vec<EcoString>,
+}
+
+#[cfg(feature = "system-packages")]
+impl PackageLock {
+    /// Creates a lock from the given package specifications and hashes.
+    pub fn new(packages: impl IntoIterator<Item = (EcoString, EcoString)>) -> Self {
+        Self {
+            packages: packages.into_iter().collect(),
+            ..Default::default()
+        }
+    }
+
+    /// Forbids changing the lock. Obtaining a package that is not in the lock
+    /// or whose contents differ from the locked ones will then fail.
+    pub fn frozen(mut self) -> Self {
+        self.frozen = true;
+        self
+    }
+
+    /// Returns the locked package specifications and their hashes.
+    pub fn packages(&self) -> impl Iterator<Item = (&EcoString, &EcoString)> {
+        self.packages.iter()
+    }
+
+    /// Removes all packages for which the predicate returns false, unless the
+    /// lock is frozen.
+    pub fn retain(&mut self, mut f: impl FnMut(&EcoString) -> bool) {
+        if self.frozen {
+            return;
+        }
+
+        let len = self.packages.len();
+        self.packages.retain(|spec, _| f(spec));
+        self.changed |= self.packages.len() != len;
+    }
+
+    /// Returns the warnings about packages whose locked hash was updated
+    /// because their contents changed, clearing them.
+    pub fn take_warnings(&mut self) -> Vec<EcoString> {
+        std::mem::take(&mut self.warnings)
+    }
+
+    /// Whether packages were added, updated, or removed since the lock was
+    /// created.
+    pub fn is_changed(&self) -> bool {
+        self.changed
+    }
+
+    /// Marks the lock as unchanged, e.g. after it was written to disk.
+    pub fn mark_unchanged(&mut self) {
+        self.changed = false;
+    }
+
+    /// Computes the content hash of a package directory.
+    pub fn hash_dir(dir: &Path) -> std::io::Result<EcoString> {
+        let mut files = vec![];
+        collect_files(dir, dir, &mut files)?;
+        files.sort();
+
+        let mut hasher = Sha256::new();
+        for (name, path) in files {
+            let data = std::fs::read(path)?;
+            hasher.update(name.as_bytes());
+            hasher.update([0]);
+            hasher.update((data.len() as u64).to_le_bytes());
+            hasher.update(&data);
+        }
+
+        let mut hash = EcoString::from("sha256:");
+        for byte in hasher.finalize() {
+            hash.push_str(&eco_format!("{byte:02x}"));
+        }
+        Ok(hash)
+    }
+
+    /// Fails if the lock is frozen and does not contain the package.
+    fn ensure_known(&self, spec: &PackageSpec) -> PackageResult<()> {
+        if self.frozen && !self.packages.contains_key(&eco_format!("{spec}")) {
+            return Err(PackageError::Other(Some(eco_format!(
+                "{spec} is not in the lockfile and it may not be updated"
+            ))));
+        }
+        Ok(())
+    }
+
+    /// Whether the package's contents were already verified.
+    fn is_verified(&self, spec: &PackageSpec) -> bool {
+        self.verified.contains(&eco_format!("{spec}"))
+    }
+
+    /// Checks a package's content hash against the lock or adds it.
+    ///
+    /// If the hash differs from the locked one, the lock is updated with a
+    /// warning, unless it is frozen.
+    fn check(&mut self, spec: &PackageSpec, hash: EcoString) -> PackageResult<()> {
+        let key = eco_format!("{spec}");
+        match self.packages.get(&key) {
+            Some(expected) if *expected != hash => {
+                if self.frozen {
+                    return Err(PackageError::Other(Some(eco_format!(
+                        "contents of {spec} do not match the lockfile \
+                         (expected {expected}, found {hash})"
+                    ))));
+                }
+                self.warnings.push(eco_format!(
+                    "contents of {spec} do not match the lockfile, updating it"
+                ));
+                self.packages.insert(key.clone(), hash);
+                self.changed = true;
+            }
+            Some(_) => {}
+            None => {
+                self.ensure_known(spec)?;
+                self.packages.insert(key.clone(), hash);
+                self.changed = true;
+            }
+        }
+        self.verified.insert(key);
+        Ok(())
+    }
+}
+
+/// Collects all files in a directory tree along with their relative paths,
+/// using forward slashes as separators.
+#[cfg(feature = "system-packages")]
+fn collect_files(
+    base: &Path,
+    dir: &Path,
+    files: &mut Vec<(String, PathBuf)>,
+) -> std::io::Result<()> {
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            collect_files(base, &path, files)?;
+        } else if let Ok(relative) = path.strip_prefix(base) {
+            let name = relative
+                .components()
+                .map(|component| component.as_os_str().to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/");
+            files.push((name, path));
+        }
+    }
+    Ok(())
+}
+
 /// Serves packages from a well-structured directory on the file system.
 ///
 /// This directory should be structured as follows:
@@ -191,6 +465,37 @@ impl FsPackages {
         dir.exists().then_some(FsRoot::new(dir))
     }
 
+    /// Lists all packages in the directory tree.
+    ///
+    /// Directories that do not follow the expected layout are skipped. The
+    /// packages are returned in arbitrary order.
+    pub fn list(&self) -> Vec<PackageSpec> {
+        fn subdirs(path: &Path) -> impl Iterator<Item = (EcoString, PathBuf)> {
+            std::fs::read_dir(path)
+                .into_iter()
+                .flatten()
+                .filter_map(|entry| entry.ok())
+                .filter(|entry| entry.file_type().is_ok_and(|ty| ty.is_dir()))
+                .filter_map(|entry| {
+                    let name = entry.file_name().to_str()?.into();
+                    Some((name, entry.path()))
+                })
+        }
+
+        let mut specs = Vec::new();
+        for (namespace, path) in subdirs(self.path()) {
+            for (name, path) in subdirs(&path) {
+                for (version, _) in subdirs(&path) {
+                    let Ok(version) = version.parse() else { continue };
+                    let namespace = namespace.clone();
+                    let name = name.clone();
+                    specs.push(PackageSpec { namespace, name, version });
+                }
+            }
+        }
+        specs
+    }
+
     /// Tries to determine the latest version of a particular package in the
     /// directory tree.
     pub fn latest_version(
@@ -302,12 +607,23 @@ impl AsRef<Path> for Tempdir {
     }
 }
 
-/// Serves packages from the Typst Universe registry.
+/// Serves packages from the Typst Universe registry or another registry with
+/// the same layout.
 ///
 /// There is no standardized registry protocol. This is merely designed to work
-/// with the official Typst Universe package registry.
+/// with the official Typst Universe package registry. A registry serves a
+/// single namespace and provides
+///
+/// - the package archives at `{url}/{namespace}/{name}-{version}.tar.gz`
+/// - an index of all packages at `{url}/{namespace}/index.json`
+///
+/// If the URL does not start with `http://` or `https://`, it is interpreted
+/// as a local directory (optionally prefixed with `file://`) laid out in the
+/// same way, which is useful for offline mirrors.
 #[cfg(feature = "universe-packages")]
 pub struct UniversePackages {
+    /// The namespace served by the registry.
+    namespace: EcoString,
     /// The url of the registry.
     url: String,
     /// A downloader with which we can download from the registry.
@@ -329,13 +645,29 @@ impl UniversePackages {
 
     /// Creates a new handle which serves packages from an alternative mirror.
     pub fn with_url(downloader: impl Downloader, url: impl Into<String>) -> Self {
+        Self::registry(downloader, Self::NAMESPACE, url)
+    }
+
+    /// Creates a new handle for a custom registry which serves packages for
+    /// the given namespace.
+    pub fn registry(
+        downloader: impl Downloader,
+        namespace: impl Into<EcoString>,
+        url: impl Into<String>,
+    ) -> Self {
         Self {
+            namespace: namespace.into(),
             url: url.into(),
             downloader: Box::new(downloader),
             index: OnceCell::new(),
         }
     }
 
+    /// Returns the namespace served by the registry.
+    pub fn namespace(&self) -> &str {
+        &self.namespace
+    }
+
     /// Returns the registry's URL.
     pub fn url(&self) -> &str {
         &self.url
@@ -348,19 +680,12 @@ impl UniversePackages {
         &self,
         spec: &PackageSpec,
     ) -> PackageResult<tar::Archive<impl Read + use<>>> {
-        if spec.namespace != Self::NAMESPACE {
+        if spec.namespace != self.namespace {
             return Err(PackageError::NotFound(spec.clone()));
         }
 
-        let url = format!(
-            "{}/{}/{}-{}.tar.gz",
-            self.url,
-            Self::NAMESPACE,
-            spec.name,
-            spec.version,
-        );
-
-        match self.downloader.download(spec, &url) {
+        let path = format!("{}/{}-{}.tar.gz", self.namespace, spec.name, spec.version);
+        match self.fetch(spec, &path) {
             Ok(data) => {
                 let decompressed = flate2::read::GzDecoder::new(Cursor::new(data));
                 Ok(tar::Archive::new(decompressed))
@@ -390,11 +715,11 @@ impl UniversePackages {
             version: PackageVersion,
         }
 
-        if spec.namespace != Self::NAMESPACE {
+        if spec.namespace != self.namespace {
             bail!(
                 "failed to determine latest version \
                  (an index is only available for the `{}` namespace)",
-                Self::NAMESPACE
+                self.namespace
             )
         }
 
@@ -407,8 +732,36 @@ impl UniversePackages {
             .ok_or_else(|| eco_format!("failed to find package {spec}"))
     }
 
-    /// Downloads the package index for the default namespace from the registry
-    /// or serves it from its in-memory cache.
+    /// Lists all packages in the registry's index along with their
+    /// descriptions.
+    ///
+    /// Will invoke the downloader with the key `"package index"`.
+    pub fn list(&self) -> StrResult<Vec<(PackageSpec, Option<EcoString>)>> {
+        /// Information about a package that is relevant for listing it.
+        #[derive(Deserialize)]
+        struct ListedPackageInfo {
+            name: EcoString,
+            version: PackageVersion,
+            description: Option<EcoString>,
+        }
+
+        Ok(self
+            .index()?
+            .iter()
+            .filter_map(|value| ListedPackageInfo::deserialize(value).ok())
+            .map(|package| {
+                let spec = PackageSpec {
+                    namespace: self.namespace.clone(),
+                    name: package.name,
+                    version: package.version,
+                };
+                (spec, package.description)
+            })
+            .collect())
+    }
+
+    /// Downloads the package index for the registry's namespace or serves it
+    /// from its in-memory cache.
     ///
     /// For compatibility, the individual entries are left unserialized. This
     /// way, packages that cannot be deserialized with this compiler version can
@@ -419,8 +772,8 @@ impl UniversePackages {
     fn index(&self) -> StrResult<&[serde_json::Value]> {
         self.index
             .get_or_try_init(|| {
-                let url = format!("{}/{}/index.json", self.url, Self::NAMESPACE);
-                match self.downloader.download(&"package index", &url) {
+                let path = format!("{}/index.json", self.namespace);
+                match self.fetch(&"package index", &path) {
                     Ok(data) => serde_json::from_slice(&data).map_err(|err| {
                         eco_format!("failed to parse package index: {err}")
                     }),
@@ -432,6 +785,18 @@ impl UniversePackages {
             })
             .map(AsRef::as_ref)
     }
+
+    /// Fetches a file from the registry, either with the downloader or from
+    /// the local file system.
+    fn fetch(&self, key: &dyn Any, path: &str) -> std::io::Result<Vec<u8>> {
+        if self.url.starts_with("http://") || self.url.starts_with("https://") {
+            let url = format!("{}/{path}", self.url);
+            self.downloader.download(key, &url)
+        } else {
+            let dir = self.url.strip_prefix("file://").unwrap_or(&self.url);
+            std::fs::read(Path::new(dir).join(path))
+        }
+    }
 }
 
 #[cfg(test)]
@@ -485,4 +850,54 @@ mod tests {
             Err("failed to find package @preview/unequivocal-ams".into())
         )
     }
+
+    #[test]
+    #[cfg(feature = "universe-packages")]
+    fn custom_registry() {
+        use super::*;
+        use std::any::Any;
+
+        /// Stands in for an HTTP server that only serves the `@acme` index.
+        struct StandIn;
+
+        impl Downloader for StandIn {
+            fn stream(
+                &self,
+                _: &dyn Any,
+                url: &str,
+            ) -> std::io::Result<(Option<usize>, Box<dyn Read>)> {
+                if url != "https://packages.acme.com/acme/index.json" {
+                    return Err(std::io::ErrorKind::NotFound.into());
+                }
+                let index = serde_json::json!([
+                    { "name": "widgets", "version": "0.1.0", "description": "Old" },
+                    { "name": "widgets", "version": "0.2.0", "description": "New" },
+                ]);
+                Ok((None, Box::new(Cursor::new(index.to_string().into_bytes()))))
+            }
+        }
+
+        let registry =
+            UniversePackages::registry(StandIn, "acme", "https://packages.acme.com");
+
+        let list = registry.list().unwrap();
+        assert_eq!(list.len(), 2);
+        assert_eq!(list[1].0.to_string(), "@acme/widgets:0.2.0");
+        assert_eq!(list[1].1.as_deref(), Some("New"));
+
+        let version = registry.latest_version(&VersionlessPackageSpec {
+            namespace: "acme".into(),
+            name: "widgets".into(),
+        });
+        assert_eq!(version, Ok(PackageVersion { major: 0, minor: 2, patch: 0 }));
+
+        let spec: PackageSpec = "@acme/widgets:0.3.0".parse().unwrap();
+        assert!(matches!(
+            registry.package(&spec),
+            Err(PackageError::VersionNotFound(_, PackageVersion { minor: 2, .. }))
+        ));
+
+        let spec: PackageSpec = "@preview/widgets:0.2.0".parse().unwrap();
+        assert!(matches!(registry.package(&spec), Err(PackageError::NotFound(_))));
+    }
 }
@@ -119,15 +119,29 @@ impl Watcher {
 
     /// Wait until there is a change to a watched path.
     pub fn wait(&mut self) -> StrResult<()> {
+        self.wait_until(None).map(drop)
+    }
+
+    /// Wait until there is a change to a watched path or the timeout elapses.
+    ///
+    /// Returns whether there was a change.
+    pub fn wait_timeout(&mut self, timeout: Duration) -> StrResult<bool> {
+        self.wait_until(Some(Instant::now() + timeout))
+    }
+
+    /// Wait until there is a change to a watched path or the deadline is
+    /// reached, returning whether there was a change.
+    fn wait_until(&mut self, deadline: Option<Instant>) -> StrResult<bool> {
         loop {
             // Wait for an initial event. If there are missing files, we need to
             // poll those regularly to check whether they are created, so we
             // wait with a smaller timeout.
-            let first = self.rx.recv_timeout(if self.missing.is_empty() {
-                Duration::MAX
-            } else {
-                Self::POLL_INTERVAL
-            });
+            let mut timeout =
+                if self.missing.is_empty() { Duration::MAX } else { Self::POLL_INTERVAL };
+            if let Some(deadline) = deadline {
+                timeout = timeout.min(deadline.saturating_duration_since(Instant::now()));
+            }
+            let first = self.rx.recv_timeout(timeout);
 
             // Watch for file system events. If multiple events happen
             // consecutively all within a certain duration, then they are
@@ -186,7 +200,11 @@ impl Watcher {
             // If we found a relevant event or if any of the missing files now
             // exists, stop waiting.
             if relevant || self.missing.iter().any(|path| path.exists()) {
-                return Ok(());
+                return Ok(true);
+            }
+
+            if deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+                return Ok(false);
             }
         }
     }
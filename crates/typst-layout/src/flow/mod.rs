@@ -29,6 +29,7 @@ use typst_library::model::{FootnoteElem, FootnoteEntry, LineNumberingScope, ParL
 use typst_library::pdf::ArtifactKind;
 use typst_library::routines::{Arenas, FragmentKind, Pair, RealizationKind, Routines};
 use typst_library::text::TextElem;
+use typst_syntax::Span;
 use typst_utils::{NonZeroExt, Numeric, Protected};
 
 use self::block::{layout_multi_block, layout_single_block};
@@ -201,6 +202,15 @@ pub fn layout_flow<'a>(
     // Prepare configuration that is shared across the whole flow.
     let config = configuration(shared, regions, columns, column_gutter, mode);
 
+    // The budget is checked once per region. Errors point to the start of
+    // the flow.
+    let budget = engine.traced.budget();
+    let span = children
+        .iter()
+        .map(|(child, _)| child.span())
+        .find(|span| !span.is_detached())
+        .unwrap_or_else(Span::detached);
+
     // Collect the elements into pre-processed children. These are much easier
     // to handle than the raw elements.
     let bump = Bump::new();
@@ -222,6 +232,11 @@ pub fn layout_flow<'a>(
         let frame = compose(engine, &mut work, &config, locator.next(&()), regions)?;
         finished.push(frame);
 
+        budget.check(engine.traced).at(span)?;
+        if mode == FlowMode::Root {
+            budget.check_pages(finished.len()).at(span)?;
+        }
+
         // Terminate the loop when everything is processed, though draining the
         // backlog if necessary.
         if work.done() && (!regions.expand.y || regions.backlog.is_empty()) {
@@ -2,12 +2,13 @@ use typst_library::diag::warning;
 use typst_library::foundations::{Packed, Resolve};
 use typst_library::introspection::{SplitLocator, Tag, TagElem};
 use typst_library::layout::{
-    Abs, BoxElem, Dir, Fr, Frame, HElem, InlineElem, InlineItem, Sizing, Spacing,
+    Abs, Axes, BoxElem, Dir, Fr, Frame, HElem, InlineElem, InlineItem, Region, Sizing,
+    Spacing,
 };
 use typst_library::routines::Pair;
 use typst_library::text::{
-    LinebreakElem, SmartQuoteElem, SmartQuoter, SmartQuotes, SpaceElem, TextElem,
-    is_default_ignorable,
+    DropcapElem, LinebreakElem, SmartQuoteElem, SmartQuoter, SmartQuotes, SpaceElem,
+    TextElem, is_default_ignorable,
 };
 use typst_syntax::Span;
 use typst_utils::Numeric;
@@ -121,18 +122,30 @@ impl Segment<'_> {
 /// Collects all text into one string and a collection of segments that
 /// correspond to pieces of that string. This also performs string-level
 /// preprocessing like case transformations.
+///
+/// A drop cap at the very start is not part of the segments. It is laid out
+/// separately and returned alongside them.
 #[typst_macros::time]
 pub fn collect<'a>(
     children: &[Pair<'a>],
     engine: &mut Engine<'_>,
     locator: &mut SplitLocator<'a>,
+    shared: StyleChain<'a>,
     config: &Config,
     region: Size,
-) -> SourceResult<(String, Vec<Segment<'a>>, SpanMapper)> {
+) -> SourceResult<(String, Vec<Segment<'a>>, SpanMapper, Option<Dropcap>)> {
     let mut collector = Collector::new(2 + children.len());
     let mut quoter = SmartQuoter::new();
+    let mut dropcap = None;
 
-    if !config.first_line_indent.is_zero() {
+    // A drop cap must come first, before any text. It takes the place of the
+    // first-line indent.
+    let dropcap_idx = children
+        .iter()
+        .position(|(child, _)| !child.is::<TagElem>())
+        .filter(|&i| children[i].0.is::<DropcapElem>());
+
+    if !config.first_line_indent.is_zero() && dropcap_idx.is_none() {
         collector.push_item(Item::Absolute(config.first_line_indent, false));
         collector.spans.push(1, Span::detached());
     }
@@ -142,7 +155,7 @@ pub fn collect<'a>(
         collector.spans.push(1, Span::detached());
     }
 
-    for &(child, styles) in children {
+    for (i, &(child, styles)) in children.iter().enumerate() {
         let prev_len = collector.full.len();
 
         if child.is::<SpaceElem>() {
@@ -231,6 +244,21 @@ pub fn collect<'a>(
                 apply_shift(&engine.world, &mut frame, styles);
                 collector.push_item(Item::Frame(frame));
             }
+        } else if let Some(elem) = child.to_packed::<DropcapElem>() {
+            let loc = locator.next(&elem.span());
+            if dropcap_idx == Some(i) {
+                let mut laid_out = layout_dropcap(elem, engine, loc, shared, styles)?;
+                laid_out.frame.modify(&FrameModifiers::get_in(styles));
+                dropcap = Some(laid_out);
+            } else {
+                // Anywhere but at the start, the body is displayed as is.
+                let pod = Region::new(region, Axes::splat(false));
+                let mut frame = layout_and_modify(styles, |styles| {
+                    crate::layout_frame(engine, &elem.body, loc, styles, pod)
+                })?;
+                apply_shift(&engine.world, &mut frame, styles);
+                collector.push_item(Item::Frame(frame));
+            }
         } else if let Some(elem) = child.to_packed::<TagElem>() {
             collector.push_item(Item::Tag(&elem.tag));
         } else {
@@ -247,7 +275,7 @@ pub fn collect<'a>(
         collector.spans.push(len, child.span());
     }
 
-    Ok((collector.full, collector.segments, collector.spans))
+    Ok((collector.full, collector.segments, collector.spans, dropcap))
 }
 
 /// Collects segments.
@@ -0,0 +1,78 @@
+use typst_library::foundations::Resolve;
+use typst_library::layout::{Axes, Em, Frame, Region};
+use typst_library::text::{
+    BottomEdge, BottomEdgeMetric, DropcapElem, TextSize, TopEdge, TopEdgeMetric,
+    families, variant,
+};
+
+use super::*;
+
+/// A laid out drop cap at the start of an inline layout.
+#[derive(Debug)]
+pub struct Dropcap {
+    /// The frame of the enlarged letters. Its height spans from the cap height
+    /// to the baseline of the letters.
+    pub frame: Frame,
+    /// The number of lines the drop cap spans.
+    pub lines: usize,
+    /// The additional start indent of the spanned lines.
+    pub indent: Abs,
+    /// How far the top of the drop cap lies above the first line's baseline.
+    pub rise: Abs,
+    /// The spacing between the lines of the paragraph.
+    pub leading: Abs,
+}
+
+/// Lays out a drop cap.
+///
+/// The letters are scaled such that their cap height spans from the cap height
+/// of the surrounding text in the first line to the baseline of the last
+/// spanned line.
+pub fn layout_dropcap(
+    elem: &Packed<DropcapElem>,
+    engine: &mut Engine,
+    locator: Locator,
+    shared: StyleChain,
+    styles: StyleChain,
+) -> SourceResult<Dropcap> {
+    let lines = elem.lines.get(styles).get();
+    let gap = elem.gap.resolve(styles);
+    let leading = shared.resolve(ParElem::leading);
+    let rise = cap_height(engine, shared);
+
+    // The distance from the cap height of the first line to the baseline of
+    // the last one, assuming that lines are as high as the text's capitals.
+    let target = rise * lines as f64 + leading * (lines - 1) as f64;
+    let size = styles.resolve(TextElem::size);
+    let scale = cap_height(engine, styles) / size;
+    let size = if scale > 0.0 { target / scale } else { size };
+
+    let local = [
+        TextElem::size.set(TextSize(size.into())),
+        TextElem::top_edge.set(TopEdge::Metric(TopEdgeMetric::CapHeight)),
+        TextElem::bottom_edge.set(BottomEdge::Metric(BottomEdgeMetric::Baseline)),
+    ]
+    .map(|p| p.wrap());
+
+    let styles = styles.chain(&local);
+    let pod = Region::new(Size::splat(Abs::inf()), Axes::splat(false));
+    let frame = crate::layout_frame(engine, &elem.body, locator, styles, pod)?;
+    let indent = frame.width() + gap;
+
+    Ok(Dropcap { frame, lines, indent, rise, leading })
+}
+
+/// Determines the cap height of the first available font.
+fn cap_height(engine: &Engine, styles: StyleChain) -> Abs {
+    let size = styles.resolve(TextElem::size);
+    families(styles)
+        .find_map(|family| {
+            engine
+                .world
+                .book()
+                .select(family.as_str(), variant(styles))
+                .and_then(|id| engine.world.font(id))
+        })
+        .map_or(Em::new(0.7), |font| font.metrics().cap_height)
+        .at(size)
+}
@@ -1,4 +1,5 @@
 use typst_library::introspection::SplitLocator;
+use typst_library::layout::Frame;
 use typst_utils::Numeric;
 
 use super::*;
@@ -20,16 +21,39 @@ pub fn finalize(
     {
         region.x.min(
             p.config.hanging_indent
-                + lines.iter().map(|line| line.width).max().unwrap_or_default(),
+                + lines
+                    .iter()
+                    .enumerate()
+                    .map(|(i, line)| line.width + p.indent(i))
+                    .max()
+                    .unwrap_or_default(),
         )
     } else {
         region.x
     };
 
     // Stack the lines into one frame per region.
-    lines
+    let mut frames = lines
         .iter()
-        .map(|line| commit(engine, p, line, width, region.y, locator))
-        .collect::<SourceResult<_>>()
-        .map(Fragment::frames)
+        .enumerate()
+        .map(|(i, line)| commit(engine, p, line, i, width, region.y, locator))
+        .collect::<SourceResult<Vec<_>>>()?;
+
+    // If the paragraph ends before the drop cap does, e.g. because it has
+    // fewer lines than the drop cap spans, the last line is extended so that
+    // the drop cap doesn't overlap what comes after the paragraph.
+    if let Some(dropcap) = &p.dropcap
+        && let Some(first) = frames.first()
+    {
+        let bottom = first.baseline() - dropcap.rise + dropcap.frame.height();
+        let height = frames.iter().map(Frame::height).sum::<Abs>()
+            + dropcap.leading * (frames.len() - 1) as f64;
+        if let Some(last) = frames.last_mut()
+            && bottom > height
+        {
+            last.size_mut().y += bottom - height;
+        }
+    }
+
+    Ok(Fragment::frames(frames))
 }
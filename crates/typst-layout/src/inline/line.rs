@@ -484,18 +484,20 @@ pub fn commit(
     engine: &mut Engine,
     p: &Preparation,
     line: &Line,
+    index: usize,
     width: Abs,
     full: Abs,
     locator: &mut SplitLocator<'_>,
 ) -> SourceResult<Frame> {
-    let mut remaining = width - line.width - p.config.hanging_indent;
+    let indent = p.indent(index);
+    let mut remaining = width - line.width - p.config.hanging_indent - indent;
     let mut offset = Abs::zero();
 
     // We always build the line from left to right. In an LTR paragraph, we must
-    // thus add the hanging indent to the offset. In an RTL paragraph, the
-    // hanging indent arises naturally due to the line width.
+    // thus add the hanging indent and the drop cap's indent to the offset. In
+    // an RTL paragraph, these indents arise naturally due to the line width.
     if p.config.dir == Dir::LTR {
-        offset += p.config.hanging_indent;
+        offset += p.config.hanging_indent + indent;
     }
 
     // Handle hanging punctuation to the left.
@@ -616,6 +618,19 @@ pub fn commit(
         add_par_line_marker(&mut output, marker, engine, locator, top);
     }
 
+    // The drop cap hangs from the first line, with its top at the height of
+    // the text's capitals, at the start of the line.
+    if index == 0
+        && let Some(dropcap) = &p.dropcap
+    {
+        let x = match p.config.dir {
+            Dir::LTR => Abs::zero(),
+            _ => width - dropcap.frame.width(),
+        };
+        let y = top - dropcap.rise;
+        output.push_frame(Point::new(x, y), dropcap.frame.clone());
+    }
+
     // Ensure that the final frame's items are in logical order rather than in
     // visual order. This is important because it affects the order of elements
     // during introspection and thus things like counters.
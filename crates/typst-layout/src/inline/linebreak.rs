@@ -170,7 +170,7 @@ fn linebreak_simple<'a>(
         // If the line doesn't fit anymore, we push the last fitting attempt
         // into the stack and rebuild the line from the attempt's end. The
         // resulting line cannot be broken up further.
-        if !width.fits(attempt.width)
+        if !(width - p.indent(lines.len())).fits(attempt.width)
             && let Some((last_attempt, last_end)) = last.take()
         {
             lines.push(last_attempt);
@@ -181,7 +181,9 @@ fn linebreak_simple<'a>(
         // Finish the current line if there is a mandatory line break (i.e. due
         // to "\n") or if the line doesn't fit horizontally already since then
         // no shorter line will be possible.
-        if breakpoint == Breakpoint::Mandatory || !width.fits(attempt.width) {
+        if breakpoint == Breakpoint::Mandatory
+            || !(width - p.indent(lines.len())).fits(attempt.width)
+        {
             lines.push(attempt);
             start = end;
             last = None;
@@ -246,10 +248,17 @@ fn linebreak_optimized_bounded<'a>(
         total: Cost,
         line: Line<'a>,
         end: usize,
+        lines: usize,
     }
 
     // Dynamic programming table.
-    let mut table = vec![Entry { pred: 0, total: 0.0, line: Line::empty(), end: 0 }];
+    let mut table = vec![Entry {
+        pred: 0,
+        total: 0.0,
+        line: Line::empty(),
+        end: 0,
+        lines: 0,
+    }];
 
     let mut active = 0;
     let mut prev_end = 0;
@@ -280,7 +289,7 @@ fn linebreak_optimized_bounded<'a>(
             let (line_ratio, line_cost) = ratio_and_cost(
                 p,
                 metrics,
-                width,
+                width - p.indent(pred.lines),
                 &pred.line,
                 &attempt,
                 breakpoint,
@@ -307,12 +316,14 @@ fn linebreak_optimized_bounded<'a>(
             // If the line is already underfull (`line_ratio > 0`), any shorter
             // slice of the line will be even more underfull. So it'll only get
             // worse from here and further attempts would also have a cost
-            // exceeding `bound`. There is one exception: When the line has
-            // negative spacing, we can't know for sure, so we don't assign the
-            // lower bound in that case.
+            // exceeding `bound`. There are two exceptions: When the line has
+            // negative spacing or when the available width varies from line to
+            // line due to a drop cap, we can't know for sure, so we don't
+            // assign the lower bound in that case.
             if line_ratio > 0.0
                 && line_lower_bound.is_none()
                 && !attempt.has_negative_width_items()
+                && p.dropcap.is_none()
             {
                 line_lower_bound = Some(line_cost);
             }
@@ -325,7 +336,13 @@ fn linebreak_optimized_bounded<'a>(
 
             // If this attempt is better than what we had before, take it!
             if best.as_ref().is_none_or(|best| best.total >= total) {
-                best = Some(Entry { pred: pred_index, total, line: attempt, end });
+                best = Some(Entry {
+                    pred: pred_index,
+                    total,
+                    line: attempt,
+                    end,
+                    lines: pred.lines + 1,
+                });
             }
         }
 
@@ -387,6 +404,7 @@ fn linebreak_optimized_approximate(
         end: usize,
         unbreakable: bool,
         breakpoint: Breakpoint,
+        lines: usize,
     }
 
     // Dynamic programming table.
@@ -396,6 +414,7 @@ fn linebreak_optimized_approximate(
         end: 0,
         unbreakable: false,
         breakpoint: Breakpoint::Mandatory,
+        lines: 0,
     }];
 
     let mut active = 0;
@@ -424,7 +443,7 @@ fn linebreak_optimized_approximate(
             let trimmed_end = start + p.text[start..end].trim_end().len();
             let line_ratio = raw_ratio(
                 p,
-                width,
+                width - p.indent(pred.lines),
                 estimates.widths.estimate(start..trimmed_end)
                     + if breakpoint.is_hyphen() {
                         metrics.approx_hyphen_width
@@ -464,6 +483,7 @@ fn linebreak_optimized_approximate(
                     end,
                     unbreakable,
                     breakpoint,
+                    lines: pred.lines + 1,
                 });
             }
         }
@@ -494,12 +514,19 @@ fn linebreak_optimized_approximate(
     // got here is only likely to be good, not guaranteed to be the best. We now
     // computes its exact cost as that gives us a sound upper bound for the
     // proper optimization pass.
-    for idx in indices.into_iter().rev() {
+    for (i, idx) in indices.into_iter().rev().enumerate() {
         let Entry { end, breakpoint, unbreakable, .. } = table[idx];
 
         let attempt = line(engine, p, start..end, breakpoint, Some(&pred));
-        let (ratio, line_cost) =
-            ratio_and_cost(p, metrics, width, &pred, &attempt, breakpoint, unbreakable);
+        let (ratio, line_cost) = ratio_and_cost(
+            p,
+            metrics,
+            width - p.indent(i),
+            &pred,
+            &attempt,
+            breakpoint,
+            unbreakable,
+        );
 
         // If approximation produces a valid layout without too much shrinking,
         // exact layout is guaranteed to find the same layout. If, however, the
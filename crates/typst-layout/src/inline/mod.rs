@@ -2,6 +2,7 @@
 mod box_;
 mod collect;
 mod deco;
+mod dropcap;
 mod finalize;
 mod line;
 mod linebreak;
@@ -28,6 +29,7 @@ use typst_utils::{Numeric, Protected, SliceExt};
 
 use self::collect::{Item, Segment, SpanMapper, collect};
 use self::deco::decorate;
+use self::dropcap::{Dropcap, layout_dropcap};
 use self::finalize::finalize;
 use self::line::{Line, apply_shift, commit, line};
 use self::linebreak::{Breakpoint, linebreak};
@@ -164,11 +166,12 @@ fn layout_inline_impl<'a>(
     let config = configuration(base, children, shared, par);
 
     // Collect all text into one string for BiDi analysis.
-    let (text, segments, spans) = collect(children, engine, locator, &config, region)?;
+    let (text, segments, spans, dropcap) =
+        collect(children, engine, locator, shared, &config, region)?;
 
     // Perform BiDi analysis and performs some preparation steps before we
     // proceed to line breaking.
-    let p = prepare(engine, &config, &text, segments, spans)?;
+    let p = prepare(engine, &config, &text, segments, spans, dropcap)?;
 
     // Break the text into lines.
     let lines = linebreak(engine, &p, region.x - config.hanging_indent);
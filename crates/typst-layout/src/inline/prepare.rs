@@ -27,6 +27,8 @@ pub struct Preparation<'a> {
     pub indices: Vec<usize>,
     /// The span mapper.
     pub spans: SpanMapper,
+    /// The drop cap at the start, if any.
+    pub dropcap: Option<Dropcap>,
 }
 
 impl<'a> Preparation<'a> {
@@ -57,6 +59,15 @@ impl<'a> Preparation<'a> {
                 range.start < sliced.end || range.end <= sliced.end
             })
     }
+
+    /// The additional start indent of the line with the given index, which
+    /// makes room for a drop cap.
+    pub fn indent(&self, line: usize) -> Abs {
+        match &self.dropcap {
+            Some(dropcap) if line < dropcap.lines => dropcap.indent,
+            _ => Abs::zero(),
+        }
+    }
 }
 
 /// Performs BiDi analysis and then prepares further layout by building a
@@ -69,6 +80,7 @@ pub fn prepare<'a>(
     text: &'a str,
     segments: Vec<Segment<'a>>,
     spans: SpanMapper,
+    dropcap: Option<Dropcap>,
 ) -> SourceResult<Preparation<'a>> {
     let default_level = match config.dir {
         Dir::RTL => BidiLevel::rtl(),
@@ -110,6 +122,14 @@ pub fn prepare<'a>(
         add_cjk_latin_spacing(&mut items);
     }
 
+    for (_, item) in &items {
+        if let Item::Text(shaped) = item
+            && shaped.styles.get(TextElem::warn_fallback)
+        {
+            shaped.warn_fallback(engine, &spans);
+        }
+    }
+
     Ok(Preparation {
         config,
         text,
@@ -117,6 +137,7 @@ pub fn prepare<'a>(
         items,
         indices,
         spans,
+        dropcap,
     })
 }
 
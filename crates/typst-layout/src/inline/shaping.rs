@@ -9,14 +9,17 @@ use rustybuzz::{BufferFlags, Feature, ShapePlan, UnicodeBuffer};
 use ttf_parser::Tag;
 use ttf_parser::gsub::SubstitutionSubtable;
 use typst_library::World;
+use typst_library::diag::warning;
 use typst_library::engine::Engine;
 use typst_library::foundations::{Regex, Smart, StyleChain};
 use typst_library::layout::{Abs, Dir, Em, Frame, FrameItem, Point, Rel, Size};
 use typst_library::model::{JustificationLimits, ParElem};
 use typst_library::text::{
     Font, FontFamily, FontVariant, Glyph, Lang, Region, ShiftSettings, TextEdgeBounds,
-    TextElem, TextItem, families, features, is_default_ignorable, language, variant,
+    TextElem, TextItem, WritingScript, families, families_for, features,
+    is_default_ignorable, language, variant,
 };
+use typst_syntax::Span;
 use typst_utils::SliceExt;
 use unicode_bidi::{BidiInfo, Level as BidiLevel};
 use unicode_script::{Script, UnicodeScript};
@@ -538,6 +541,42 @@ impl<'a> ShapedText<'a> {
             .sum()
     }
 
+    /// Warns about the characters that were shaped with a font from outside
+    /// of the primary font list.
+    pub fn warn_fallback(&self, engine: &mut Engine, spans: &SpanMapper) {
+        let primary = self.styles.get_ref(TextElem::font);
+        let mut fallbacks: Vec<(Span, &Font, String)> = vec![];
+        for glyph in self.glyphs.all() {
+            // Tofus and spaces don't tell us anything about the font choice.
+            if glyph.glyph_id == 0 || glyph.c.is_whitespace() {
+                continue;
+            }
+
+            let family = glyph.font.info().family.to_lowercase();
+            if primary.into_iter().any(|f| f.as_str() == family) {
+                continue;
+            }
+
+            let (span, _) = spans.span_at(glyph.range.start);
+            match fallbacks
+                .iter_mut()
+                .find(|(s, font, _)| *s == span && **font == glyph.font)
+            {
+                Some((_, _, chars)) if chars.contains(glyph.c) => {}
+                Some((_, _, chars)) => chars.push(glyph.c),
+                None => fallbacks.push((span, &glyph.font, glyph.c.into())),
+            }
+        }
+
+        for (span, font, chars) in fallbacks {
+            engine.sink.warn(warning!(
+                span,
+                "used fallback font \"{}\" for \"{chars}\"",
+                font.info().family,
+            ));
+        }
+    }
+
     /// Reshape a range of the shaped text, reusing information from this
     /// shaping process if possible.
     ///
@@ -804,7 +843,7 @@ fn shape<'a>(
     };
 
     if !text.is_empty() {
-        shape_segment(&mut ctx, base, text, families(styles));
+        shape_segment(&mut ctx, base, text, families_for(styles, script(text, styles)));
     }
 
     track_and_space(&mut ctx);
@@ -827,6 +866,19 @@ fn shape<'a>(
     }
 }
 
+/// Determines the writing script of a piece of text for the selection of
+/// fallback fonts.
+fn script(text: &str, styles: StyleChain) -> Option<WritingScript> {
+    match styles.get(TextElem::script) {
+        Smart::Custom(script) => Some(script),
+        Smart::Auto => text
+            .chars()
+            .map(|c| c.script())
+            .find(|&script| !is_generic_script(script))
+            .and_then(|script| script.short_name().parse().ok()),
+    }
+}
+
 /// Holds shaping results and metadata common to all shaped segments.
 struct ShapingContext<'a> {
     world: Tracked<'a, dyn World + 'a>,
@@ -8,7 +8,7 @@ use std::num::NonZeroUsize;
 
 use comemo::{Tracked, TrackedMut};
 use typst_library::World;
-use typst_library::diag::SourceResult;
+use typst_library::diag::{At, SourceResult};
 use typst_library::engine::{Engine, Route, Sink, Traced};
 use typst_library::foundations::{Content, StyleChain};
 use typst_library::introspection::{
@@ -18,6 +18,7 @@ use typst_library::introspection::{
 use typst_library::layout::{FrameItem, Page, PagedDocument, Point, Position, Transform};
 use typst_library::model::DocumentInfo;
 use typst_library::routines::{Arenas, Pair, RealizationKind, Routines};
+use typst_syntax::Span;
 use typst_utils::Protected;
 
 use self::collect::{Item, collect};
@@ -120,17 +121,32 @@ fn layout_pages<'a>(
     let mut pages = vec![];
     let mut tags = vec![];
     let mut counter = ManualPageCounter::new();
+    let budget = engine.traced.budget();
 
     // Collect and finalize the runs, handling things like page parity and tags
     // between pages.
     for item in &items {
         match item {
-            Item::Run(..) => {
+            Item::Run(children, ..) => {
                 let layouted = runs.next().unwrap()?;
                 for layouted in layouted {
                     let page = finalize(engine, &mut counter, &mut tags, layouted)?;
                     pages.push(page);
                 }
+
+                // Stop between runs if the document has too many pages or the
+                // compilation was cancelled.
+                let checked = budget
+                    .check_pages(pages.len())
+                    .and_then(|()| budget.check(engine.traced));
+                if let Err(err) = checked {
+                    let span = children
+                        .iter()
+                        .map(|(child, _)| child.span())
+                        .find(|span| !span.is_detached())
+                        .unwrap_or_else(Span::detached);
+                    return Err(err).at(span);
+                }
             }
             Item::Parity(parity, initial, locator) => {
                 if !parity.matches(pages.len()) {
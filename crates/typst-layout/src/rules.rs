@@ -2,6 +2,7 @@ use comemo::Track;
 use ecow::{EcoVec, eco_format};
 use smallvec::smallvec;
 use typst_library::diag::{At, SourceResult, bail};
+use typst_library::engine::Engine;
 use typst_library::foundations::{
     Content, Context, NativeElement, NativeRuleMap, Packed, Resolve, ShowFn, Smart,
     StyleChain, Synthesize, Target, dict,
@@ -17,10 +18,11 @@ use typst_library::layout::{
 use typst_library::math::EquationElem;
 use typst_library::model::{
     Attribution, BibliographyElem, CiteElem, CiteGroup, CslIndentElem, CslLightElem,
-    Destination, DirectLinkElem, EmphElem, EnumElem, FigureCaption, FigureElem,
-    FootnoteElem, FootnoteEntry, HeadingElem, LinkElem, LinkMarker, ListElem,
-    OutlineElem, OutlineEntry, ParElem, ParbreakElem, QuoteElem, RefElem, StrongElem,
-    TableCell, TableElem, TermsElem, TitleElem, Works,
+    Destination, DirectLinkElem, EmphElem, EndnoteElem, EndnoteEntry, EndnotesElem,
+    EnumElem, FigureCaption, FigureElem, FootnoteElem, FootnoteEntry, GlossaryElem,
+    HeadingElem, IndexElem, IndexNode, LinkElem, LinkMarker, ListElem, OutlineElem,
+    OutlineEntry, ParElem, ParbreakElem, QuoteElem, RefElem, StrongElem, TableCell,
+    TableElem, TermsElem, TitleElem, Works,
 };
 use typst_library::pdf::{ArtifactElem, ArtifactKind, AttachElem, PdfMarkerTag};
 use typst_library::text::{
@@ -33,6 +35,7 @@ use typst_library::visualize::{
     CircleElem, CurveElem, EllipseElem, ImageElem, LineElem, PolygonElem, RectElem,
     SquareElem, Stroke,
 };
+use typst_syntax::Span;
 use typst_utils::{Get, Numeric};
 
 /// Register show rules for the [paged target](Target::Paged).
@@ -55,8 +58,13 @@ pub fn register(rules: &mut NativeRuleMap) {
     rules.register(Paged, QUOTE_RULE);
     rules.register(Paged, FOOTNOTE_RULE);
     rules.register(Paged, FOOTNOTE_ENTRY_RULE);
+    rules.register(Paged, ENDNOTE_RULE);
+    rules.register(Paged, ENDNOTE_ENTRY_RULE);
+    rules.register(Paged, ENDNOTES_RULE);
     rules.register(Paged, OUTLINE_RULE);
     rules.register(Paged, OUTLINE_ENTRY_RULE);
+    rules.register(Paged, INDEX_RULE);
+    rules.register(Paged, GLOSSARY_RULE);
     rules.register(Paged, REF_RULE);
     rules.register(Paged, CITE_GROUP_RULE);
     rules.register(Paged, BIBLIOGRAPHY_RULE);
@@ -412,6 +420,48 @@ const FOOTNOTE_ENTRY_RULE: ShowFn<FootnoteEntry> = |elem, engine, styles| {
     ]))
 };
 
+const ENDNOTE_RULE: ShowFn<EndnoteElem> = |elem, engine, styles| {
+    let span = elem.span();
+    let (dest, num) = elem.realize(engine, styles)?;
+    let alt = EndnoteElem::alt_text(styles, &num.plain_text());
+    let mut sup = SuperElem::new(num).pack().spanned(span);
+    if let Some(dest) = dest {
+        sup = sup.linked(dest, Some(alt));
+    }
+    Ok(HElem::hole().clone() + PdfMarkerTag::Label(sup))
+};
+
+const ENDNOTE_ENTRY_RULE: ShowFn<EndnoteEntry> = |elem, engine, styles| {
+    let number_gap = Em::new(0.05);
+    let (prefix, body) = elem.realize(engine, styles)?;
+    Ok(Content::sequence([
+        PdfMarkerTag::Label(prefix),
+        HElem::new(number_gap.into()).with_weak(true).pack(),
+        body,
+    ]))
+};
+
+const ENDNOTES_RULE: ShowFn<EndnotesElem> = |elem, engine, styles| {
+    let span = elem.span();
+    let mut seq = vec![];
+    seq.extend(elem.realize_title(styles));
+
+    for note in elem.notes(engine) {
+        let loc = note.location().unwrap();
+        let entry = EndnoteEntry::new(note).pack().spanned(span);
+        seq.push(
+            BlockElem::new()
+                .with_body(Some(BlockBody::Content(entry)))
+                .pack()
+                .located(loc.variant(1))
+                .spanned(span),
+        );
+    }
+
+    seq.extend(elem.realize_reset(styles));
+    Ok(Content::sequence(seq))
+};
+
 const OUTLINE_RULE: ShowFn<OutlineElem> = |elem, engine, styles| {
     let title = elem.realize_title(styles);
     let entries = elem.realize_flat(engine, styles)?;
@@ -459,6 +509,114 @@ const OUTLINE_ENTRY_RULE: ShowFn<OutlineEntry> = |elem, engine, styles| {
     Ok(block.linked(Destination::Location(loc), Some(alt)))
 };
 
+const INDEX_RULE: ShowFn<IndexElem> = |elem, engine, styles| {
+    let span = elem.span();
+    let compress = elem.compress.get(styles);
+
+    let mut seq = vec![];
+    seq.extend(elem.realize_title(styles));
+
+    for group in elem.realize_groups(engine, styles)? {
+        if let Some(letter) = group.letter {
+            let body = TextElem::packed(letter).strong();
+            seq.push(
+                BlockElem::new()
+                    .with_body(Some(BlockBody::Content(body)))
+                    .with_sticky(true)
+                    .pack()
+                    .spanned(span),
+            );
+        }
+
+        for node in &group.nodes {
+            show_index_node(&mut seq, engine, styles, node, 0, compress, span)?;
+        }
+    }
+
+    Ok(Content::sequence(seq))
+};
+
+/// Produces a block with hanging indent for an index term, followed by blocks
+/// for its sub-entries.
+fn show_index_node(
+    seq: &mut Vec<Content>,
+    engine: &mut Engine,
+    styles: StyleChain,
+    node: &IndexNode,
+    depth: usize,
+    compress: bool,
+    span: Span,
+) -> SourceResult<()> {
+    const INDENT: Em = Em::new(1.5);
+
+    let pages = node.realize_pages(engine, styles, compress, span)?;
+    let mut body = node.term.clone();
+    for page in &pages {
+        body += TextElem::packed(", ");
+        body += page.clone();
+    }
+
+    if let Some(cross_refs) = node.realize_cross_refs(styles) {
+        body += TextElem::packed(if pages.is_empty() { ", " } else { "; " });
+        body += cross_refs;
+    }
+
+    let inset = Sides::default().with(
+        styles.resolve(TextElem::dir).start(),
+        Some((INDENT * (depth + 1) as f64).into()),
+    );
+    let body = HElem::new((-INDENT).into()).pack() + body;
+    seq.push(
+        BlockElem::new()
+            .with_body(Some(BlockBody::Content(body)))
+            .with_inset(inset)
+            .pack()
+            .spanned(span),
+    );
+
+    for child in &node.children {
+        show_index_node(seq, engine, styles, child, depth + 1, compress, span)?;
+    }
+
+    Ok(())
+}
+
+const GLOSSARY_RULE: ShowFn<GlossaryElem> = |elem, engine, styles| {
+    const INDENT: Em = Em::new(1.5);
+
+    let span = elem.span();
+    let mut seq = vec![];
+    seq.extend(elem.realize_title(styles));
+
+    for item in elem.realize_items(engine, styles)? {
+        let mut body = item.entry.short.clone().strong();
+        let description = item.realize_description();
+        if !description.is_empty() {
+            body += HElem::new(Em::new(0.5).into()).pack();
+            body += description;
+        }
+
+        for page in item.realize_pages(engine, styles, span)? {
+            body += TextElem::packed(", ");
+            body += page;
+        }
+
+        let inset = Sides::default()
+            .with(styles.resolve(TextElem::dir).start(), Some(INDENT.into()));
+        let body = HElem::new((-INDENT).into()).pack() + body;
+        seq.push(
+            BlockElem::new()
+                .with_body(Some(BlockBody::Content(body)))
+                .with_inset(inset)
+                .pack()
+                .located(item.target)
+                .spanned(span),
+        );
+    }
+
+    Ok(Content::sequence(seq))
+};
+
 const REF_RULE: ShowFn<RefElem> = |elem, engine, styles| elem.realize(engine, styles);
 
 const CITE_GROUP_RULE: ShowFn<CiteGroup> = |elem, engine, _| elem.realize(engine);
@@ -306,26 +306,56 @@ impl DeprecationSink for (&mut Engine<'_>, Span) {
 /// A part of a diagnostic's [trace](SourceDiagnostic::trace).
 #[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
 pub enum Tracepoint {
-    /// A function call.
-    Call(Option<EcoString>),
+    /// A function call, with the name of the function (if any) and a short
+    /// summary of the arguments it was called with (if known).
+    Call(Option<EcoString>, Option<EcoString>),
     /// A show rule application.
     Show(EcoString),
+    /// The show rule whose transformation was being applied.
+    ShowRule,
     /// A module import.
     Import,
 }
 
+impl Tracepoint {
+    /// A summary of the arguments of a traced function call.
+    ///
+    /// This is not part of the tracepoint's display message and only shown
+    /// when verbose traces are requested.
+    pub fn args(&self) -> Option<&str> {
+        match self {
+            Self::Call(_, args) => args.as_deref(),
+            _ => None,
+        }
+    }
+
+    /// Whether two tracepoints at the same span describe the same frame.
+    ///
+    /// Calls at the same span are the same frame even if they were recorded
+    /// with a different name (e.g. for a function stored in a dictionary).
+    fn same_frame(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::Call(..), Self::Call(..)) => true,
+            _ => self == other,
+        }
+    }
+}
+
 impl Display for Tracepoint {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
         match self {
-            Tracepoint::Call(Some(name)) => {
+            Tracepoint::Call(Some(name), _) => {
                 write!(f, "error occurred in this call of function `{name}`")
             }
-            Tracepoint::Call(None) => {
+            Tracepoint::Call(None, _) => {
                 write!(f, "error occurred in this function call")
             }
             Tracepoint::Show(name) => {
                 write!(f, "error occurred while applying show rule to this {name}")
             }
+            Tracepoint::ShowRule => {
+                write!(f, "error occurred in this show rule")
+            }
             Tracepoint::Import => {
                 write!(f, "error occurred while importing this module")
             }
@@ -336,6 +366,10 @@ impl Display for Tracepoint {
 /// Enrich a [`SourceResult`] with a tracepoint.
 pub trait Trace<T> {
     /// Add the tracepoint to all errors that lie outside the `span`.
+    ///
+    /// Once an error has a trace, further tracepoints are always added so that
+    /// the trace forms a complete call stack. Directly repeated frames (as in
+    /// recursion) are only recorded once.
     fn trace<F>(self, world: Tracked<dyn World + '_>, make_point: F, span: Span) -> Self
     where
         F: Fn() -> Tracepoint;
@@ -350,7 +384,8 @@ impl<T> Trace<T> for SourceResult<T> {
             let Some(trace_range) = world.range(span) else { return errors };
             for error in errors.make_mut().iter_mut() {
                 // Skip traces that surround the error.
-                if let Some(error_range) = world.range(error.span)
+                if error.trace.is_empty()
+                    && let Some(error_range) = world.range(error.span)
                     && error.span.id() == span.id()
                     && trace_range.start <= error_range.start
                     && trace_range.end >= error_range.end
@@ -358,7 +393,17 @@ impl<T> Trace<T> for SourceResult<T> {
                     continue;
                 }
 
-                error.trace.push(Spanned::new(make_point(), span));
+                // Skip frames that repeat the previous one.
+                let point = make_point();
+                if error
+                    .trace
+                    .last()
+                    .is_some_and(|last| last.span == span && last.v.same_frame(&point))
+                {
+                    continue;
+                }
+
+                error.trace.push(Spanned::new(point, span));
             }
             errors
         })
@@ -1,17 +1,21 @@
 //! Definition of the central compilation context.
 
-use std::sync::atomic::{AtomicUsize, Ordering};
+use std::hash::{Hash, Hasher};
+use std::ops::Deref;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, LazyLock};
+use std::time::{Duration, Instant};
 
 use comemo::{Track, Tracked, TrackedMut};
-use ecow::EcoVec;
+use ecow::{EcoString, EcoVec};
 use rayon::iter::{IndexedParallelIterator, IntoParallelIterator, ParallelIterator};
 use rustc_hash::FxHashSet;
-use typst_syntax::{FileId, Span};
+use typst_syntax::{FileId, Span, SyntaxMode};
 use typst_utils::Protected;
 
 use crate::World;
 use crate::diag::{HintedStrResult, SourceDiagnostic, SourceResult, StrResult, bail};
-use crate::foundations::{Styles, Value};
+use crate::foundations::{Binding, Context, Scope, Scopes, Styles, Value};
 use crate::introspection::{Introspect, Introspection, Introspector};
 use crate::routines::Routines;
 
@@ -115,16 +119,37 @@ impl Engine<'_> {
     }
 }
 
-/// May hold a span that is currently under inspection.
+/// May hold a span that is currently under inspection, a debugger that is
+/// attached to the compilation, and the compilation's resource budget.
 #[derive(Default)]
-pub struct Traced(Option<Span>);
+pub struct Traced {
+    /// The span under inspection.
+    span: Option<Span>,
+    /// The attached debugger.
+    debugger: Option<DebuggerHandle>,
+    /// The budget of the compilation.
+    budget: Budget,
+}
 
 impl Traced {
     /// Wraps a to-be-traced `Span`.
     ///
     /// Call `Traced::default()` to trace nothing.
     pub fn new(traced: Span) -> Self {
-        Self(Some(traced))
+        Self { span: Some(traced), ..Self::default() }
+    }
+
+    /// Attaches a debugger that is notified as code is evaluated.
+    pub fn debugged(debugger: Arc<dyn Debugger>) -> Self {
+        Self {
+            debugger: Some(DebuggerHandle(debugger)),
+            ..Self::default()
+        }
+    }
+
+    /// Bounds the resources of the compilation by the given budget.
+    pub fn budgeted(budget: Budget) -> Self {
+        Self { budget, ..Self::default() }
     }
 }
 
@@ -136,7 +161,394 @@ impl Traced {
     /// We hide the span if it isn't in the given file so that only results for
     /// the file with the traced span are invalidated.
     pub fn get(&self, id: FileId) -> Option<Span> {
-        if self.0.and_then(Span::id) == Some(id) { self.0 } else { None }
+        if self.span.and_then(Span::id) == Some(id) { self.span } else { None }
+    }
+
+    /// Returns the attached debugger and the budget if evaluation has to
+    /// observe either of them, or `None` otherwise.
+    ///
+    /// The evaluator reads both with this single call whenever it creates a
+    /// virtual machine. Without a debugger or an active budget, the call is
+    /// cheap to record and to validate.
+    pub fn hooks(&self) -> Option<(Option<DebuggerHandle>, Budget)> {
+        (self.debugger.is_some() || self.budget.is_active())
+            .then(|| (self.debugger.clone(), self.budget.clone()))
+    }
+
+    /// Returns the budget of the compilation.
+    pub fn budget(&self) -> Budget {
+        self.budget.clone()
+    }
+
+    /// Whether the compilation was interrupted because it exhausted its
+    /// budget or was cancelled.
+    ///
+    /// This is called when a budget check fails so that the resulting error is
+    /// only reused by compilations that were interrupted as well.
+    pub fn interrupted(&self) -> bool {
+        self.budget.0.interrupted.load(Ordering::Relaxed)
+    }
+}
+
+/// Limits for the resources a compilation may use.
+///
+/// All limits are unset by default. When compiling untrusted documents, they
+/// can be configured to bound the total work a document can cause.
+///
+/// Work that is reused from the memoization cache is not counted against
+/// the limits.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct Limits {
+    /// The maximum number of evaluation steps. Roughly one step is taken per
+    /// evaluated expression and loop iteration.
+    pub eval_steps: Option<u64>,
+    /// The maximum wall-clock duration of the compilation.
+    pub timeout: Option<Duration>,
+    /// The maximum number of pages in a paged document.
+    pub pages: Option<usize>,
+    /// The maximum length in bytes of a string produced by evaluated code.
+    ///
+    /// Repetition with `*` is checked before the string is built. Other
+    /// operations are checked once their result exists, so they may briefly
+    /// allocate more. Strings nested in arrays, dictionaries, or content are
+    /// not checked separately.
+    pub str_len: Option<usize>,
+    /// The maximum number of items in an array produced by evaluated code.
+    ///
+    /// Repetition with `*` and `range` are checked before the array is built.
+    /// As with strings, other operations are checked once their result exists.
+    /// Dictionaries, bytes, and content are not limited.
+    pub array_len: Option<usize>,
+    /// The maximum amount of fuel a single plugin call may consume. Roughly
+    /// one unit of fuel is consumed per executed WebAssembly instruction.
+    pub plugin_fuel: Option<u64>,
+}
+
+/// Keeps track of the resources a compilation used with respect to its
+/// [`Limits`].
+///
+/// Budgets are hashed by their limits, so that compilations with the same
+/// limits share their caches. As the step and time limits and cancellation
+/// depend on the compilation as a whole, their checks take the tracked
+/// [`Traced`] to record a failure.
+#[derive(Clone)]
+pub struct Budget(Arc<BudgetRepr>);
+
+/// The shared internals of a [`Budget`].
+#[derive(Default)]
+struct BudgetRepr {
+    /// The configured limits.
+    limits: Limits,
+    /// The point in time at which the compilation must have finished.
+    deadline: Option<Instant>,
+    /// The number of evaluation steps taken so far.
+    steps: AtomicU64,
+    /// The number of times the budget was polled so far.
+    polls: AtomicU64,
+    /// Cancels the compilation when triggered.
+    cancellation: Option<CancellationToken>,
+    /// Whether the step or time limit was exceeded or the compilation was
+    /// cancelled.
+    interrupted: AtomicBool,
+    /// Whether any limit is set or the compilation can be cancelled.
+    active: bool,
+}
+
+impl Budget {
+    /// How many evaluation steps to take before the clock is checked again.
+    const CLOCK_INTERVAL: u64 = 256;
+
+    /// Creates a new budget, starting the clock if there is a timeout.
+    ///
+    /// If a cancellation token is given, the compilation is interrupted once
+    /// the token is cancelled.
+    pub fn new(limits: Limits, cancellation: Option<CancellationToken>) -> Self {
+        // Only query the clock if necessary as it is not available on all
+        // platforms.
+        let deadline = limits.timeout.map(|timeout| Instant::now() + timeout);
+        let active = limits != Limits::default() || cancellation.is_some();
+        Self(Arc::new(BudgetRepr {
+            limits,
+            deadline,
+            cancellation,
+            active,
+            ..BudgetRepr::default()
+        }))
+    }
+
+    /// Whether any limit is set or the compilation can be cancelled. An
+    /// inactive budget never fails a check.
+    pub fn is_active(&self) -> bool {
+        self.0.active
+    }
+
+    /// The configured limits.
+    pub fn limits(&self) -> &Limits {
+        &self.0.limits
+    }
+
+    /// Takes an evaluation step, ensuring that the step and time limits are
+    /// not exceeded and that the compilation was not cancelled.
+    pub fn step(&self, traced: Tracked<Traced>) -> HintedStrResult<()> {
+        if !self.0.active {
+            return Ok(());
+        }
+
+        // We only need atomicity, so `Relaxed` is fine.
+        let steps = self.0.steps.fetch_add(1, Ordering::Relaxed) + 1;
+        if self.0.limits.eval_steps.is_some_and(|max| steps > max) {
+            self.interrupt(traced);
+            bail!(
+                "maximum number of evaluation steps exceeded";
+                hint: "the document performs too much computation";
+            );
+        }
+
+        self.check_cancelled(traced)?;
+        if steps.is_multiple_of(Self::CLOCK_INTERVAL) {
+            self.check_time(traced)?;
+        }
+
+        Ok(())
+    }
+
+    /// Ensures that the time limit is not exceeded and that the compilation
+    /// was not cancelled.
+    pub fn check(&self, traced: Tracked<Traced>) -> HintedStrResult<()> {
+        self.check_cancelled(traced)?;
+        self.check_time(traced)
+    }
+
+    /// Ensures that the compilation was not cancelled and, on every
+    /// [`CLOCK_INTERVAL`](Self::CLOCK_INTERVAL)th call, that the time limit is
+    /// not exceeded.
+    ///
+    /// This is cheaper than [`check`](Self::check) and meant for hot paths
+    /// outside of evaluation.
+    pub fn poll(&self, traced: Tracked<Traced>) -> HintedStrResult<()> {
+        if !self.0.active {
+            return Ok(());
+        }
+
+        self.check_cancelled(traced)?;
+        let polls = self.0.polls.fetch_add(1, Ordering::Relaxed) + 1;
+        if polls.is_multiple_of(Self::CLOCK_INTERVAL) {
+            self.check_time(traced)?;
+        }
+
+        Ok(())
+    }
+
+    /// Ensures that the compilation was not cancelled.
+    fn check_cancelled(&self, traced: Tracked<Traced>) -> HintedStrResult<()> {
+        if self
+            .0
+            .cancellation
+            .as_ref()
+            .is_some_and(CancellationToken::is_cancelled)
+        {
+            self.interrupt(traced);
+            bail!("{}", CancellationToken::MESSAGE);
+        }
+        Ok(())
+    }
+
+    /// Ensures that the time limit is not exceeded.
+    fn check_time(&self, traced: Tracked<Traced>) -> HintedStrResult<()> {
+        if self.0.deadline.is_some_and(|deadline| Instant::now() > deadline) {
+            self.interrupt(traced);
+            bail!(
+                "maximum compilation time exceeded";
+                hint: "the document takes too long to compile";
+            );
+        }
+        Ok(())
+    }
+
+    /// Marks the compilation as interrupted and records this through the
+    /// tracked `traced`, so that memoized results which observed the
+    /// interruption are not reused by other compilations.
+    fn interrupt(&self, traced: Tracked<Traced>) {
+        self.0.interrupted.store(true, Ordering::Relaxed);
+        traced.interrupted();
+    }
+
+    /// Ensures that a document with the given number of pages is within the
+    /// page limit.
+    pub fn check_pages(&self, count: usize) -> HintedStrResult<()> {
+        if let Some(max) = self.0.limits.pages
+            && count > max
+        {
+            bail!(
+                "maximum number of pages exceeded";
+                hint: "the document may not have more than {max} pages";
+            );
+        }
+        Ok(())
+    }
+
+    /// Ensures that a string with the given length in bytes is within the
+    /// string length limit.
+    pub fn check_str_len(&self, len: usize) -> StrResult<()> {
+        if self.0.limits.str_len.is_some_and(|max| len > max) {
+            bail!("maximum string length exceeded");
+        }
+        Ok(())
+    }
+
+    /// Ensures that an array with the given number of items is within the
+    /// array length limit.
+    pub fn check_array_len(&self, len: usize) -> StrResult<()> {
+        if self.0.limits.array_len.is_some_and(|max| len > max) {
+            bail!("maximum array length exceeded");
+        }
+        Ok(())
+    }
+
+    /// Ensures that a value is within the string and array length limits if it
+    /// is a string or array.
+    pub fn check_value(&self, value: &Value) -> StrResult<()> {
+        match value {
+            Value::Str(string) => self.check_str_len(string.len()),
+            Value::Array(array) => self.check_array_len(array.len()),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl Default for Budget {
+    fn default() -> Self {
+        // All inactive budgets are equivalent, so they can share one
+        // allocation.
+        static INACTIVE: LazyLock<Budget> =
+            LazyLock::new(|| Budget(Arc::new(BudgetRepr::default())));
+        INACTIVE.clone()
+    }
+}
+
+impl Hash for Budget {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.limits.hash(state);
+    }
+}
+
+/// Allows cancelling an in-flight compilation from another thread.
+///
+/// A cancelled compilation returns promptly with an error. Results that were
+/// memoized before the cancellation remain valid for later compilations.
+#[derive(Debug, Default, Clone)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    /// The message of the error a cancelled compilation fails with.
+    pub const MESSAGE: &str = "compilation was cancelled";
+
+    /// Creates a new token that is not yet cancelled.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Cancels the compilations this token was passed to.
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    /// Whether the token was cancelled.
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// Receives notifications about the evaluation of Typst code.
+///
+/// A debugger is attached to a compilation through [`Traced::debugged`]. The
+/// evaluator notifies it whenever it enters or exits a module or function body
+/// and before it evaluates a code expression. The debugger may block in
+/// [`step`](Self::step) to pause the compilation.
+///
+/// Note that evaluations which are served from the memoization cache are not
+/// observed by the debugger.
+pub trait Debugger: Send + Sync {
+    /// Called when the evaluation of a module or function body starts.
+    ///
+    /// The name is the module's file name or the function's name, if any.
+    fn enter(&self, name: Option<&str>, span: Span);
+
+    /// Called when the evaluation of the most recently entered module or
+    /// function body ends.
+    fn exit(&self);
+
+    /// Called before an expression is evaluated.
+    fn step(&self, frame: &DebugFrame);
+}
+
+/// A shared handle to a [`Debugger`].
+///
+/// Handles are compared and hashed by identity.
+#[derive(Clone)]
+pub struct DebuggerHandle(Arc<dyn Debugger>);
+
+impl Deref for DebuggerHandle {
+    type Target = dyn Debugger;
+
+    fn deref(&self) -> &Self::Target {
+        &*self.0
+    }
+}
+
+impl Hash for DebuggerHandle {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        Arc::as_ptr(&self.0).cast::<()>().hash(state);
+    }
+}
+
+/// The state of evaluation at an expression that is about to be evaluated.
+pub struct DebugFrame<'a, 'b> {
+    /// The span of the expression.
+    pub span: Span,
+    /// The variables in scope.
+    pub scopes: &'b Scopes<'a>,
+    /// The engine of the evaluation.
+    pub engine: &'b Engine<'a>,
+    /// The context of the evaluation.
+    pub context: Tracked<'a, Context<'a>>,
+}
+
+impl DebugFrame<'_, '_> {
+    /// The local variables in scope, innermost first.
+    ///
+    /// Variables that are shadowed by an inner binding are omitted.
+    pub fn locals(&self) -> Vec<(EcoString, Value)> {
+        let mut seen = FxHashSet::default();
+        std::iter::once(&self.scopes.top)
+            .chain(self.scopes.scopes.iter().rev())
+            .flat_map(|scope| scope.iter())
+            .filter(|(name, _)| seen.insert(*name))
+            .map(|(name, binding)| (name.clone(), binding.read().clone()))
+            .collect()
+    }
+
+    /// Evaluates a code expression with access to the local variables.
+    ///
+    /// Warnings emitted during the evaluation are discarded.
+    pub fn eval(&self, expr: &str) -> SourceResult<Value> {
+        let mut scope = Scope::new();
+        for (name, value) in self.locals().into_iter().rev() {
+            scope.bind(name, Binding::detached(value));
+        }
+
+        let mut sink = Sink::new();
+        (self.engine.routines.eval_string)(
+            self.engine.routines,
+            self.engine.world,
+            sink.track_mut(),
+            *self.engine.introspector.access("is okay for debugger evaluations"),
+            self.context,
+            expr,
+            Span::detached(),
+            SyntaxMode::Code,
+            scope,
+        )
     }
 }
 
@@ -280,6 +280,27 @@ impl Args {
         self.items.is_empty()
     }
 
+    /// A short, single-line summary of the arguments for use in diagnostics.
+    ///
+    /// Long or multi-line argument values are cut off.
+    pub fn summary(&self) -> EcoString {
+        const LIMIT: usize = 40;
+        let pieces = self
+            .items
+            .iter()
+            .map(|arg| {
+                let repr = arg.repr();
+                let line = repr.lines().next().unwrap_or_default();
+                match line.char_indices().nth(LIMIT) {
+                    Some((i, _)) => eco_format!("{}..", &line[..i]),
+                    None if line.len() < repr.len() => eco_format!("{line}.."),
+                    None => repr,
+                }
+            })
+            .collect::<Vec<_>>();
+        eco_format!("({})", pieces.join(", "))
+    }
+
     fn get(&self, key: &ArgumentKey) -> Option<&Value> {
         let item = match key {
             &ArgumentKey::Index(index) => {
@@ -563,3 +584,41 @@ fn missing_key_no_default(key: ArgumentKey) -> EcoString {
         }
     )
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_args_summary() {
+        let span = Span::detached();
+        assert_eq!(Args::new::<Value>(span, []).summary(), "()");
+
+        let mut args = Args::new(
+            span,
+            [
+                Value::Int(1),
+                Value::Str("a".repeat(50).into()),
+                Value::Str("ä".repeat(50).into()),
+            ],
+        );
+        args.items.push(Arg {
+            span,
+            name: Some("list".into()),
+            value: Spanned::new(Value::Array((1..=20).map(Value::Int).collect()), span),
+        });
+        args.items.push(Arg {
+            span,
+            name: Some("short".into()),
+            value: Spanned::new(Value::Str("b".repeat(30).into()), span),
+        });
+
+        let a = "a".repeat(39);
+        let umlauts = "ä".repeat(39);
+        let b = "b".repeat(30);
+        assert_eq!(
+            args.summary(),
+            format!("(1, \"{a}.., \"{umlauts}.., list: (.., short: \"{b}\")"),
+        );
+    }
+}
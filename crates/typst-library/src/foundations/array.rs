@@ -373,6 +373,7 @@ impl Array {
     /// ```
     #[func]
     pub fn range(
+        engine: &mut Engine,
         args: &mut Args,
         /// The start of the range (inclusive).
         #[external]
@@ -394,6 +395,20 @@ impl Array {
 
         let step = step.get();
 
+        // Check the length before allocating as a range can be arbitrarily
+        // long.
+        let (distance, by) = (i128::from(end) - i128::from(start), i128::from(step));
+        let len = if distance.signum() == by.signum() {
+            (distance + by - by.signum()) / by
+        } else {
+            0
+        };
+        engine
+            .traced
+            .budget()
+            .check_array_len(usize::try_from(len).unwrap_or(usize::MAX))
+            .at(args.span)?;
+
         let mut x = start;
         let mut array = Self::new();
 
@@ -14,8 +14,10 @@ use unicode_math_class::MathClass;
 
 use crate::diag::{At, HintedStrResult, HintedString, SourceResult, StrResult};
 use crate::foundations::{
-    Fold, NativeElement, Packed, Repr, Str, Type, Value, array, repr,
+    Content, Fold, NativeElement, Packed, Repr, Str, SymbolElem, Type, Value, array, repr,
 };
+use crate::layout::Rel;
+use crate::text::TextElem;
 
 /// Determine details of a type.
 ///
@@ -304,6 +306,52 @@ pub enum CastInfo {
 }
 
 impl CastInfo {
+    /// Whether the value is accepted, possibly after a built-in coercion.
+    pub fn matches(&self, value: &Value) -> bool {
+        self.coerce(value.clone()).is_ok()
+    }
+
+    /// Converts the value into one that is accepted without any conversion.
+    ///
+    /// This applies the same coercions as the arguments of native functions,
+    /// e.g. from an integer to a float or from a string to content. Returns
+    /// the value unchanged if it isn't accepted.
+    pub fn coerce(&self, value: Value) -> Result<Value, Value> {
+        match self {
+            Self::Any => Ok(value),
+            Self::Value(expected, _) if *expected == value => Ok(value),
+            Self::Value(..) => Err(value),
+            Self::Type(ty) => coerce(*ty, value),
+            Self::Union(infos) => {
+                // A value of one of the types must not be converted into
+                // another one, e.g. an integer into a float for `(int, float)`.
+                if infos.iter().any(|info| info.is_exact(&value)) {
+                    return Ok(value);
+                }
+
+                let mut value = value;
+                for info in infos {
+                    match info.coerce(value) {
+                        Ok(coerced) => return Ok(coerced),
+                        Err(original) => value = original,
+                    }
+                }
+
+                Err(value)
+            }
+        }
+    }
+
+    /// Whether the value is accepted without any conversion.
+    fn is_exact(&self, value: &Value) -> bool {
+        match self {
+            Self::Any => true,
+            Self::Value(expected, _) => expected == value,
+            Self::Type(ty) => value.ty() == *ty,
+            Self::Union(infos) => infos.iter().any(|info| info.is_exact(value)),
+        }
+    }
+
     /// Produce an error message describing what was expected and what was
     /// found.
     pub fn error(&self, found: &Value) -> HintedString {
@@ -386,6 +434,29 @@ impl CastInfo {
     }
 }
 
+/// Converts a value into the given type like the corresponding native argument
+/// would, as long as the conversion can't fail.
+fn coerce(ty: Type, value: Value) -> Result<Value, Value> {
+    if value.ty() == ty {
+        return Ok(value);
+    }
+
+    Ok(match value {
+        Value::Int(v) if ty == Type::of::<f64>() => Value::Float(v as f64),
+        Value::Length(v) if ty == Type::of::<Rel>() => Value::Relative(v.into()),
+        Value::Ratio(v) if ty == Type::of::<Rel>() => Value::Relative(v.into()),
+        Value::Symbol(v) if ty == Type::of::<Str>() => Value::Str(v.get().into()),
+        Value::None if ty == Type::of::<Content>() => Value::Content(Content::empty()),
+        Value::Str(v) if ty == Type::of::<Content>() => {
+            Value::Content(TextElem::packed(v))
+        }
+        Value::Symbol(v) if ty == Type::of::<Content>() => {
+            Value::Content(SymbolElem::packed(v.get()))
+        }
+        value => return Err(value),
+    })
+}
+
 impl Add for CastInfo {
     type Output = Self;
 
@@ -154,7 +154,8 @@ pub fn plugin(
     source: Spanned<DataSource>,
 ) -> SourceResult<Module> {
     let loaded = source.load(engine.world)?;
-    Plugin::module(loaded.data).at(source.span)
+    let metered = engine.traced.budget().limits().plugin_fuel.is_some();
+    Plugin::module(loaded.data, metered).at(source.span)
 }
 
 #[scope]
@@ -193,13 +194,14 @@ impl plugin {
     /// ```
     #[func]
     pub fn transition(
+        engine: &mut Engine,
         /// The plugin function to call.
         func: PluginFunc,
         /// The byte buffers to call the function with.
         #[variadic]
         arguments: Vec<Bytes>,
     ) -> StrResult<Module> {
-        func.transition(arguments)
+        func.transition(arguments, engine.traced.budget().limits().plugin_fuel)
     }
 }
 
@@ -219,17 +221,22 @@ impl PluginFunc {
     }
 
     /// Call the WebAssembly function with the given arguments.
+    ///
+    /// If the plugin was loaded with fuel metering, the call may consume at
+    /// most the given amount of fuel.
     #[comemo::memoize]
     #[typst_macros::time(name = "call plugin")]
-    pub fn call(&self, args: Vec<Bytes>) -> StrResult<Bytes> {
-        self.plugin.call(&self.name, args)
+    pub fn call(&self, args: Vec<Bytes>, fuel: Option<u64>) -> StrResult<Bytes> {
+        self.plugin.call(&self.name, args, fuel)
     }
 
     /// Transition a plugin and turn the result into a module.
     #[comemo::memoize]
     #[typst_macros::time(name = "transition plugin")]
-    pub fn transition(&self, args: Vec<Bytes>) -> StrResult<Module> {
-        self.plugin.transition(&self.name, args).map(Plugin::into_module)
+    pub fn transition(&self, args: Vec<Bytes>, fuel: Option<u64>) -> StrResult<Module> {
+        self.plugin
+            .transition(&self.name, args, fuel)
+            .map(Plugin::into_module)
     }
 }
 
@@ -262,16 +269,18 @@ impl Plugin {
     /// Create a plugin and turn it into a module.
     #[comemo::memoize]
     #[typst_macros::time(name = "load plugin")]
-    fn module(bytes: Bytes) -> StrResult<Module> {
-        Self::new(bytes).map(Self::into_module)
+    fn module(bytes: Bytes, metered: bool) -> StrResult<Module> {
+        Self::new(bytes, metered).map(Self::into_module)
     }
 
-    /// Create a new plugin from raw WebAssembly bytes.
-    fn new(bytes: Bytes) -> StrResult<Self> {
+    /// Create a new plugin from raw WebAssembly bytes, optionally metering
+    /// the fuel consumed by calls.
+    fn new(bytes: Bytes, metered: bool) -> StrResult<Self> {
         let mut config = wasmi::Config::default();
 
         // Disable relaxed SIMD as it can introduce non-determinism.
         config.wasm_relaxed_simd(false);
+        config.consume_fuel(metered);
 
         let engine = wasmi::Engine::new(&config);
         let module = wasmi::Module::new(&engine, bytes.as_slice())
@@ -310,14 +319,14 @@ impl Plugin {
     }
 
     /// Execute a function with access to an instance.
-    fn call(&self, func: &str, args: Vec<Bytes>) -> StrResult<Bytes> {
+    fn call(&self, func: &str, args: Vec<Bytes>, fuel: Option<u64>) -> StrResult<Bytes> {
         // Acquire an instance from the pool (potentially creating a new one).
         let mut instance = self.acquire()?;
 
         // Execute the call on an instance from the pool. If the call fails, we
         // return early and _don't_ return the instance to the pool as it might
         // be irrecoverably damaged.
-        let output = instance.call(func, args)?;
+        let output = instance.call(func, args, fuel)?;
 
         // Return the instance to the pool.
         self.pool.lock().unwrap().push(instance);
@@ -327,7 +336,12 @@ impl Plugin {
 
     /// Call a mutable plugin function, producing a new mutable whose functions
     /// are guaranteed to be able to observe the mutation.
-    fn transition(&self, func: &str, args: Vec<Bytes>) -> StrResult<Plugin> {
+    fn transition(
+        &self,
+        func: &str,
+        args: Vec<Bytes>,
+        fuel: Option<u64>,
+    ) -> StrResult<Plugin> {
         // Derive a new transition hash from the old one and the function and arguments.
         let fingerprint = typst_utils::hash128(&(self.fingerprint, func, &args));
 
@@ -336,7 +350,7 @@ impl Plugin {
 
         // Call the function. If the call fails, we return early and _don't_
         // return the instance to the pool as it might be irrecoverably damaged.
-        instance.call(func, args)?;
+        instance.call(func, args, fuel)?;
 
         // Snapshot the instance after the mutable call.
         let snapshot = instance.snapshot();
@@ -446,7 +460,12 @@ impl PluginInstance {
     }
 
     /// Call a plugin function with byte arguments.
-    fn call(&mut self, func: &str, args: Vec<Bytes>) -> StrResult<Bytes> {
+    fn call(
+        &mut self,
+        func: &str,
+        args: Vec<Bytes>,
+        fuel: Option<u64>,
+    ) -> StrResult<Bytes> {
         let handle = self
             .instance
             .get_export(&self.store, func)
@@ -487,11 +506,21 @@ impl PluginInstance {
         // Store the input data.
         self.store.data_mut().args = args;
 
+        // Refuel the instance. This fails if fuel is not metered, in which case
+        // there is nothing to do.
+        self.store.set_fuel(fuel.unwrap_or(u64::MAX)).ok();
+
         // Call the function.
         let mut code = wasmi::Val::I32(-1);
         handle
             .call(&mut self.store, &lengths, std::slice::from_mut(&mut code))
-            .map_err(|err| eco_format!("plugin panicked: {err}"))?;
+            .map_err(|err| {
+                if matches!(self.store.get_fuel(), Ok(0)) {
+                    eco_format!("plugin function `{func}` ran out of fuel")
+                } else {
+                    eco_format!("plugin panicked: {err}")
+                }
+            })?;
 
         if let Some(MemoryError { offset, length, write }) =
             self.store.data_mut().memory_error.take()
@@ -503,7 +503,11 @@ impl Recipe {
         let mut content = match &self.transform {
             Transformation::Content(content) => content.clone(),
             Transformation::Func(func) => {
-                let mut result = func.call(engine, context, [content.clone()]);
+                let mut result = func.call(engine, context, [content.clone()]).trace(
+                    engine.world,
+                    || Tracepoint::ShowRule,
+                    self.span,
+                );
                 if self.selector.is_some() {
                     let point = || Tracepoint::Show(content.func().name().into());
                     result = result.trace(engine.world, point, content.span());
@@ -1018,6 +1022,8 @@ impl NativeRuleMap {
         rules.register_empty::<crate::introspection::StateUpdateElem>();
         rules.register_empty::<crate::introspection::MetadataElem>();
         rules.register_empty::<crate::model::PrefixInfo>();
+        rules.register_empty::<crate::model::IndexEntry>();
+        rules.register_empty::<crate::model::GlossaryEntry>();
 
         rules
     }
@@ -22,7 +22,9 @@ use crate::introspection::{
 };
 use crate::layout::{Frame, FrameItem, PageElem};
 use crate::math::EquationElem;
-use crate::model::{FigureElem, FootnoteElem, HeadingElem, Numbering, NumberingPattern};
+use crate::model::{
+    EndnoteElem, FigureElem, FootnoteElem, HeadingElem, Numbering, NumberingPattern,
+};
 use crate::routines::Routines;
 
 /// Counts through pages, elements, and more.
@@ -292,6 +294,10 @@ impl Counter {
                         content
                             .to_packed::<FootnoteElem>()
                             .and_then(|elem| elem.numbering.as_option().clone())
+                    } else if func == EndnoteElem::ELEM {
+                        content
+                            .to_packed::<EndnoteElem>()
+                            .and_then(|elem| elem.numbering.as_option().clone())
                     } else {
                         None
                     }
@@ -305,6 +311,8 @@ impl Counter {
                         styles.get_cloned(EquationElem::numbering)
                     } else if func == FootnoteElem::ELEM {
                         Some(styles.get_cloned(FootnoteElem::numbering))
+                    } else if func == EndnoteElem::ELEM {
+                        Some(styles.get_cloned(EndnoteElem::numbering))
                     } else {
                         None
                     }
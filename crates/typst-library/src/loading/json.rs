@@ -4,7 +4,7 @@ use typst_syntax::Spanned;
 use crate::diag::{At, LineCol, LoadError, LoadedWithin, SourceResult, bail};
 use crate::engine::Engine;
 use crate::foundations::{Str, Value, func, scope};
-use crate::loading::{DataSource, Load, Readable};
+use crate::loading::{DataSource, Load, Loaded, Readable};
 
 /// Reads structured data from a JSON file.
 ///
@@ -81,6 +81,11 @@ pub fn json(
     source: Spanned<DataSource>,
 ) -> SourceResult<Value> {
     let loaded = source.load(engine.world)?;
+    decode_json(&loaded)
+}
+
+/// Parses loaded JSON data into a value.
+pub fn decode_json(loaded: &Loaded) -> SourceResult<Value> {
     let raw = loaded.data.as_slice();
     // If the file starts with a UTF-8 Byte Order Mark (BOM), return a
     // friendly error message.
@@ -91,7 +96,7 @@ pub fn json(
                 "failed to parse JSON",
                 "unexpected Byte Order Mark",
             )
-            .within(&loaded)
+            .within(loaded)
             .with_hint("JSON requires UTF-8 without a BOM")
         );
     }
@@ -101,7 +106,7 @@ pub fn json(
             let pos = LineCol::one_based(err.line(), err.column());
             LoadError::new(pos, "failed to parse JSON", err)
         })
-        .within(&loaded)
+        .within(loaded)
 }
 
 #[scope]
@@ -4,7 +4,7 @@ use typst_syntax::Spanned;
 use crate::diag::{At, LoadError, LoadedWithin, ReportPos, SourceResult};
 use crate::engine::Engine;
 use crate::foundations::{Dict, Str, func, scope};
-use crate::loading::{DataSource, Load, Readable};
+use crate::loading::{DataSource, Load, Loaded, Readable};
 
 /// Reads structured data from a TOML file.
 ///
@@ -68,8 +68,13 @@ pub fn toml(
     source: Spanned<DataSource>,
 ) -> SourceResult<Dict> {
     let loaded = source.load(engine.world)?;
-    let raw = loaded.data.as_str().within(&loaded)?;
-    ::toml::from_str(raw).map_err(format_toml_error).within(&loaded)
+    decode_toml(&loaded)
+}
+
+/// Parses loaded TOML data into a dictionary.
+pub fn decode_toml(loaded: &Loaded) -> SourceResult<Dict> {
+    let raw = loaded.data.as_str().within(loaded)?;
+    ::toml::from_str(raw).map_err(format_toml_error).within(loaded)
 }
 
 #[scope]
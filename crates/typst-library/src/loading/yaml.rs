@@ -4,7 +4,7 @@ use typst_syntax::Spanned;
 use crate::diag::{At, LineCol, LoadError, LoadedWithin, ReportPos, SourceResult};
 use crate::engine::Engine;
 use crate::foundations::{Str, Value, func, scope};
-use crate::loading::{DataSource, Load, Readable};
+use crate::loading::{DataSource, Load, Loaded, Readable};
 
 /// Reads structured data from a YAML file.
 ///
@@ -75,9 +75,14 @@ pub fn yaml(
     source: Spanned<DataSource>,
 ) -> SourceResult<Value> {
     let loaded = source.load(engine.world)?;
+    decode_yaml(&loaded)
+}
+
+/// Parses loaded YAML data into a value.
+pub fn decode_yaml(loaded: &Loaded) -> SourceResult<Value> {
     serde_yaml::from_slice(loaded.data.as_slice())
         .map_err(format_yaml_error)
-        .within(&loaded)
+        .within(loaded)
 }
 
 #[scope]
@@ -8,12 +8,13 @@ use comemo::{Track, Tracked};
 use ecow::{EcoString, EcoVec, eco_format};
 use hayagriva::archive::ArchivedStyle;
 use hayagriva::io::BibLaTeXError;
+use hayagriva::types::EntryType;
 use hayagriva::{
     BibliographyDriver, BibliographyRequest, CitationItem, CitationRequest, Library,
     SpecificLocator, TransparentLocator, citationberg,
 };
 use indexmap::IndexMap;
-use rustc_hash::{FxBuildHasher, FxHashMap};
+use rustc_hash::{FxBuildHasher, FxHashMap, FxHashSet};
 use smallvec::SmallVec;
 use typst_syntax::{Span, Spanned, SyntaxMode};
 use typst_utils::{ManuallyHash, NonZeroExt, PicoStr};
@@ -26,8 +27,8 @@ use crate::diag::{
 use crate::engine::{Engine, Sink};
 use crate::foundations::{
     Bytes, CastInfo, Content, Context, Derived, FromValue, IntoValue, Label,
-    NativeElement, OneOrMultiple, Packed, Reflect, Scope, ShowSet, Smart, StyleChain,
-    Styles, Synthesize, Value, elem,
+    LocatableSelector, NativeElement, OneOrMultiple, Packed, Reflect, Scope, ShowSet,
+    Smart, StyleChain, Styles, Synthesize, Value, cast, elem,
 };
 use crate::introspection::{
     History, Introspect, Introspector, Locatable, Location, QueryIntrospection,
@@ -35,8 +36,8 @@ use crate::introspection::{
 use crate::layout::{BlockBody, BlockElem, Em, HElem, PadElem};
 use crate::loading::{DataSource, Load, LoadSource, Loaded, format_yaml_error};
 use crate::model::{
-    CitationForm, CiteGroup, Destination, DirectLinkElem, FootnoteElem, HeadingElem,
-    LinkElem, Url,
+    CitationForm, CiteElem, CiteGroup, Destination, DirectLinkElem, FootnoteElem,
+    HeadingElem, LinkElem, Url,
 };
 use crate::routines::Routines;
 use crate::text::{Lang, LocalName, Region, SmallcapsElem, SubElem, SuperElem, TextElem};
@@ -83,6 +84,16 @@ use crate::text::{Lang, LocalName, Region, SmallcapsElem, SubElem, SuperElem, Te
 ///
 /// #bibliography("works.bib")
 /// ```
+///
+/// # Multiple bibliographies
+/// A document can contain several bibliographies, for instance one at the end
+/// of each chapter or separate lists for primary and secondary sources. Each
+/// bibliography can be restricted to a part of the document with its
+/// [`target`]($bibliography.target) and to some of its works with
+/// [`key-prefix`]($bibliography.key-prefix) and
+/// [`types`]($bibliography.types). A citation belongs to the first
+/// bibliography that covers it and lists its work. Citations are numbered
+/// independently for each bibliography.
 #[elem(Locatable, Synthesize, ShowSet, LocalName)]
 pub struct BibliographyElem {
     /// One or multiple paths to or raw bytes for Hayagriva `.yaml` and/or
@@ -119,6 +130,61 @@ pub struct BibliographyElem {
     #[default(false)]
     pub full: bool,
 
+    /// The citations for which this bibliography lists works.
+    ///
+    /// By default, a bibliography covers all citations in the document. This
+    /// can be narrowed down with a [selector], for instance to give each
+    /// chapter its own bibliography.
+    ///
+    /// ```example
+    /// = Introduction <intro>
+    /// As noted by pirates. @arrgh
+    ///
+    /// #bibliography(
+    ///   "works.bib",
+    ///   title: none,
+    ///   target: selector(cite).before(<methods>),
+    /// )
+    ///
+    /// = Methods <methods>
+    /// Both @netwok and @arrgh.
+    ///
+    /// #bibliography(
+    ///   "works.bib",
+    ///   title: none,
+    ///   target: selector(cite).after(<methods>),
+    /// )
+    /// ```
+    #[default(LocatableSelector(CiteElem::ELEM.select()))]
+    pub target: LocatableSelector,
+
+    /// If given, the bibliography only lists works whose key starts with this
+    /// prefix.
+    ///
+    /// ```example
+    /// Primary: @netwok \
+    /// Secondary: @arrgh
+    ///
+    /// #bibliography(
+    ///   "works.bib",
+    ///   title: [Primary sources],
+    ///   key-prefix: "net",
+    /// )
+    /// #bibliography(
+    ///   "works.bib",
+    ///   title: [Secondary sources],
+    /// )
+    /// ```
+    pub key_prefix: Option<EcoString>,
+
+    /// If given, the bibliography only lists works of these types, for instance
+    /// `{"book"}` or `{"article"}`.
+    ///
+    /// The types are those of
+    /// [Hayagriva's file format](https://github.com/typst/hayagriva/blob/main/docs/file-format.md#entry-type).
+    /// Entries from BibLaTeX files are converted to these types.
+    pub types: Option<OneOrMultiple<WorkType>>,
+
     /// The bibliography style.
     ///
     /// This can be:
@@ -149,20 +215,17 @@ pub struct BibliographyElem {
 }
 
 impl BibliographyElem {
-    /// Find the document's bibliography.
-    pub fn find(engine: &mut Engine, span: Span) -> StrResult<Packed<Self>> {
+    /// Find the document's bibliographies.
+    pub fn find_all(engine: &mut Engine, span: Span) -> StrResult<Vec<Packed<Self>>> {
         let elems = engine.introspect(QueryIntrospection(Self::ELEM.select(), span));
-
-        let mut iter = elems.iter();
-        let Some(elem) = iter.next() else {
+        if elems.is_empty() {
             bail!("the document does not contain a bibliography");
-        };
-
-        if iter.next().is_some() {
-            bail!("multiple bibliographies are not yet supported");
         }
 
-        Ok(elem.to_packed::<Self>().unwrap().clone())
+        Ok(elems
+            .iter()
+            .map(|elem| elem.to_packed::<Self>().unwrap().clone())
+            .collect())
     }
 
     /// Whether the bibliography contains the given key.
@@ -185,6 +248,89 @@ impl BibliographyElem {
         }
         vec
     }
+
+    /// Looks up the entry with the given key if it passes the bibliography's
+    /// `key-prefix` and `types` filters.
+    fn entry(&self, key: Label) -> Option<&hayagriva::Entry> {
+        let entry = self.sources.derived.get(key)?;
+        let default = StyleChain::default();
+
+        if let Some(prefix) = self.key_prefix.get_ref(default)
+            && !key.resolve().as_str().starts_with(prefix.as_str())
+        {
+            return None;
+        }
+
+        if let Some(types) = self.types.get_ref(default)
+            && !types.0.iter().any(|ty| ty.kind == *entry.entry_type())
+        {
+            return None;
+        }
+
+        Some(entry)
+    }
+
+    /// The locations of the citations covered by the bibliography's target, or
+    /// `None` if it covers all citations.
+    pub fn coverage(
+        &self,
+        engine: &mut Engine,
+        span: Span,
+    ) -> Option<Arc<FxHashSet<Location>>> {
+        let target = self.target.get_cloned(StyleChain::default()).0;
+        (target != CiteElem::ELEM.select()).then(|| {
+            covered_locations(engine.introspect(QueryIntrospection(target, span)))
+        })
+    }
+
+    /// Determines the bibliography the given citation belongs to.
+    ///
+    /// Each citation belongs to the first bibliography that covers it with its
+    /// target and lists its work. Citations whose work is not listed by any
+    /// covering bibliography are assigned to the first covering one, so that
+    /// its generator reports the missing key. Returns `None` if no
+    /// bibliography covers the citation.
+    pub fn assign(
+        bibliographies: &[Packed<Self>],
+        coverage: &[Option<Arc<FxHashSet<Location>>>],
+        cite: &Packed<CiteElem>,
+    ) -> Option<usize> {
+        let covers = |i: &usize| match (&coverage[*i], cite.location()) {
+            (Some(covered), Some(loc)) => covered.contains(&loc),
+            _ => true,
+        };
+        (0..bibliographies.len())
+            .filter(covers)
+            .find(|&i| bibliographies[i].entry(cite.key).is_some())
+            .or_else(|| (0..bibliographies.len()).find(covers))
+    }
+}
+
+/// Collects the locations of the elements matched by a bibliography's target.
+///
+/// This is memoized, so that the set is only built once per bibliography
+/// instead of for every citation that is checked against it.
+#[comemo::memoize]
+fn covered_locations(elems: EcoVec<Content>) -> Arc<FxHashSet<Location>> {
+    Arc::new(elems.iter().flat_map(Content::location).collect())
+}
+
+/// A type of work by which a bibliography can filter its entries.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct WorkType {
+    /// The type as it was written.
+    name: EcoString,
+    /// The corresponding Hayagriva entry type.
+    kind: EntryType,
+}
+
+cast! {
+    WorkType,
+    self => self.name.into_value(),
+    v: EcoString => {
+        let kind = v.parse().map_err(|_| eco_format!("unknown work type `{v}`"))?;
+        Self { name: v, kind }
+    },
 }
 
 impl Packed<BibliographyElem> {
@@ -548,25 +694,41 @@ pub struct Works {
 }
 
 impl Works {
-    /// Generate all citations and the whole bibliography.
-    pub fn generate(engine: &mut Engine, span: Span) -> SourceResult<Arc<Works>> {
-        let bibliography = BibliographyElem::find(engine, span).at(span)?;
-        let groups = engine.introspect(CiteGroupIntrospection(span));
-        Self::generate_impl(engine.routines, engine.world, bibliography, groups).at(span)
-    }
-
-    /// Generate all citations and the whole bibliography, given an existing
-    /// bibliography (no need to query it).
+    /// Generate the citations and references of a single bibliography, given
+    /// an existing bibliography (no need to query it).
     pub fn with_bibliography(
         engine: &mut Engine,
         bibliography: Packed<BibliographyElem>,
     ) -> SourceResult<Arc<Works>> {
         let span = bibliography.span();
         let groups = engine.introspect(CiteGroupIntrospection(span));
+        let bibliographies =
+            engine.introspect(QueryIntrospection(BibliographyElem::ELEM.select(), span));
+
+        let coverage: Vec<_> = bibliographies
+            .iter()
+            .map(|elem| {
+                let elem = elem.to_packed::<BibliographyElem>().unwrap();
+                let target = elem.target.get_cloned(StyleChain::default()).0;
+                (target != CiteElem::ELEM.select())
+                    .then(|| engine.introspect(QueryIntrospection(target, span)))
+            })
+            .collect();
+
+        // With multiple bibliographies or a restricted target, the citations
+        // are distributed among the bibliographies, so that each one is
+        // numbered independently.
+        let groups = if bibliographies.len() > 1 || coverage.iter().any(Option::is_some) {
+            let location = bibliography.location().unwrap();
+            distribute_groups(bibliographies, groups, coverage, location)
+        } else {
+            groups
+        };
+
         Self::generate_impl(engine.routines, engine.world, bibliography, groups).at(span)
     }
 
-    /// The internal implementation of [`Works::generate`].
+    /// The internal implementation of [`Works::with_bibliography`].
     #[comemo::memoize]
     fn generate_impl(
         routines: &Routines,
@@ -602,6 +764,62 @@ impl Works {
     }
 }
 
+/// Determines the citation groups that belong to the bibliography at the
+/// given location.
+///
+/// Each citation is assigned as described in [`BibliographyElem::assign`].
+/// Citations that no bibliography covers are left out, they are reported when
+/// the citation group is realized. Groups with citations for different
+/// bibliographies are split, keeping the location of the original group.
+#[comemo::memoize]
+fn distribute_groups(
+    bibliographies: EcoVec<Content>,
+    groups: EcoVec<Content>,
+    coverage: Vec<Option<EcoVec<Content>>>,
+    location: Location,
+) -> EcoVec<Content> {
+    let bibliographies: Vec<_> = bibliographies
+        .iter()
+        .map(|elem| elem.to_packed::<BibliographyElem>().unwrap().clone())
+        .collect();
+    let coverage: Vec<_> = coverage
+        .into_iter()
+        .map(|elems| elems.map(covered_locations))
+        .collect();
+    let Some(index) = bibliographies
+        .iter()
+        .position(|elem| elem.location() == Some(location))
+    else {
+        return EcoVec::new();
+    };
+
+    let mut output = EcoVec::new();
+    for elem in &groups {
+        let group = elem.to_packed::<CiteGroup>().unwrap();
+        let children: Vec<_> = group
+            .children
+            .iter()
+            .filter(|child| {
+                BibliographyElem::assign(&bibliographies, &coverage, child) == Some(index)
+            })
+            .cloned()
+            .collect();
+
+        if children.len() == group.children.len() {
+            output.push(elem.clone());
+        } else if !children.is_empty() {
+            output.push(
+                CiteGroup::new(children)
+                    .pack()
+                    .spanned(elem.span())
+                    .located(elem.location().unwrap()),
+            );
+        }
+    }
+
+    output
+}
+
 /// Retrieves all citation groups in the document.
 ///
 /// This is separate from `QueryIntrospection` so that we can customize the
@@ -636,9 +854,9 @@ struct Generator<'a> {
     routines: &'a Routines,
     /// The world that is used to evaluate mathematical material in citations.
     world: Tracked<'a, dyn World + 'a>,
-    /// The document's bibliography.
+    /// The bibliography to generate.
     bibliography: Packed<BibliographyElem>,
-    /// The document's citation groups.
+    /// The citation groups that belong to the bibliography.
     groups: EcoVec<Content>,
     /// Details about each group that are accumulated while driving hayagriva's
     /// bibliography driver and needed when processing hayagriva's output.
@@ -695,7 +913,7 @@ impl<'a> Generator<'a> {
         static LOCALES: LazyLock<Vec<citationberg::Locale>> =
             LazyLock::new(hayagriva::archive::locales);
 
-        let database = &self.bibliography.sources.derived;
+        let bibliography = &self.bibliography;
         let bibliography_style =
             &self.bibliography.style.get_ref(StyleChain::default()).derived;
 
@@ -716,7 +934,7 @@ impl<'a> Generator<'a> {
 
             // Create infos and items for each child in the group.
             for child in children {
-                let Some(entry) = database.get(child.key) else {
+                let Some(entry) = bibliography.entry(child.key) else {
                     errors.push(error!(
                         child.span(),
                         "key `{}` does not exist in the bibliography",
@@ -788,7 +1006,8 @@ impl<'a> Generator<'a> {
         // Add hidden items for everything if we should print the whole
         // bibliography.
         if self.bibliography.full.get(StyleChain::default()) {
-            for (_, entry) in database.iter() {
+            let database = &bibliography.sources.derived;
+            for entry in database.iter().filter_map(|(key, _)| bibliography.entry(key)) {
                 driver.citation(CitationRequest::new(
                     vec![CitationItem::new(entry, None, None, true, None)],
                     bibliography_style.get(),
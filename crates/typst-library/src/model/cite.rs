@@ -1,3 +1,4 @@
+use ecow::EcoVec;
 use typst_syntax::Spanned;
 
 use crate::diag::{At, HintedString, SourceResult, error};
@@ -6,9 +7,9 @@ use crate::foundations::{
     Cast, Content, Derived, Label, Packed, Smart, StyleChain, Synthesize, cast, elem,
 };
 use crate::introspection::Locatable;
-use crate::model::bibliography::Works;
+use crate::model::bibliography::{BibliographyElem, Works};
 use crate::model::{CslSource, CslStyle};
-use crate::text::{Lang, Region, TextElem};
+use crate::text::{Lang, Region, SpaceElem, TextElem};
 
 /// Cite a work from the bibliography.
 ///
@@ -162,12 +163,51 @@ impl Packed<CiteGroup> {
     pub fn realize(&self, engine: &mut Engine) -> SourceResult<Content> {
         let location = self.location().unwrap();
         let span = self.span();
-        Works::generate(engine, span)?
-            .citations
-            .get(&location)
-            .cloned()
-            .ok_or_else(failed_to_format_citation)
-            .at(span)?
+
+        // Only the bibliographies that the group's citations belong to need
+        // to be generated. A citation outside of the target of every
+        // bibliography would otherwise silently vanish from the group.
+        let bibliographies = BibliographyElem::find_all(engine, span).at(span)?;
+        let coverage: Vec<_> = bibliographies
+            .iter()
+            .map(|elem| elem.coverage(engine, span))
+            .collect();
+        let mut indices = vec![];
+        let mut errors = EcoVec::new();
+        for child in &self.children {
+            match BibliographyElem::assign(&bibliographies, &coverage, child) {
+                Some(index) => indices.push(index),
+                None => errors.push(error!(
+                    child.span(),
+                    "citation is not covered by any bibliography";
+                    hint: "the `target` of each bibliography excludes this citation";
+                )),
+            }
+        }
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+
+        // If the group's citations belong to different bibliographies, each
+        // one formats its part of the group.
+        indices.sort_unstable();
+        indices.dedup();
+        let mut parts = vec![];
+        for index in indices {
+            let works = Works::with_bibliography(engine, bibliographies[index].clone())?;
+            if let Some(citation) = works.citations.get(&location) {
+                if !parts.is_empty() {
+                    parts.push(SpaceElem::shared().clone());
+                }
+                parts.push(citation.clone()?);
+            }
+        }
+
+        if parts.is_empty() {
+            return Err(failed_to_format_citation()).at(span);
+        }
+
+        Ok(Content::sequence(parts))
     }
 }
 
@@ -0,0 +1,346 @@
+use std::num::NonZeroUsize;
+use std::str::FromStr;
+
+use ecow::{EcoString, eco_format, eco_vec};
+use typst_syntax::Span;
+use typst_utils::NonZeroExt;
+
+use crate::diag::{At, SourceResult, StrResult, bail, warning};
+use crate::engine::Engine;
+use crate::foundations::{
+    Content, Label, NativeElement, Packed, Selector, ShowSet, Smart, StyleChain, Styles,
+    cast, elem, scope,
+};
+use crate::introspection::{
+    Count, Counter, CounterState, CounterUpdate, Locatable, Location, QueryIntrospection,
+    QueryLabelIntrospection, Tagged,
+};
+use crate::model::{
+    Destination, DirectLinkElem, FootnoteBody, HeadingElem, Numbering, NumberingPattern,
+};
+use crate::text::{LocalName, SuperElem, TextElem};
+
+/// An endnote.
+///
+/// Like a [footnote], an endnote inserts a superscript number into the text.
+/// The note itself, however, is not placed at the bottom of the page. Instead,
+/// notes are collected and displayed by the next [list of endnotes]($endnotes)
+/// in the document. This way, notes can be grouped at the end of each chapter
+/// or at the end of the document.
+///
+/// The number of an endnote links to its entry in the list and vice versa.
+///
+/// # Example
+/// ```example
+/// = Chapter 1
+/// Pirates were feared.#endnote[By
+/// most sailors, at least.] They
+/// sailed the seas.#endnote[Mostly
+/// the Caribbean.]
+///
+/// #endnotes(title: [Notes to Chapter 1])
+/// ```
+///
+/// By giving a label to an endnote, you can have multiple references to it.
+///
+/// ```example
+/// Ships were fast.#endnote[Very
+/// fast.] <fast> And @fast
+/// so were boats.#endnote(<fast>)
+///
+/// #endnotes()
+/// ```
+#[elem(scope, Locatable, Tagged, Count)]
+pub struct EndnoteElem {
+    /// How to number endnotes. Accepts a
+    /// [numbering pattern or function]($numbering) taking a single number.
+    ///
+    /// ```example
+    /// #set endnote(numbering: "i")
+    ///
+    /// Endnotes#endnote[One] in
+    /// Roman numerals.#endnote[Two]
+    ///
+    /// #endnotes(title: none)
+    /// ```
+    #[default(Numbering::Pattern(NumberingPattern::from_str("1").unwrap()))]
+    pub numbering: Numbering,
+
+    /// The content to put into the endnote. Can also be the label of another
+    /// endnote this one should point to.
+    #[required]
+    pub body: FootnoteBody,
+}
+
+#[scope]
+impl EndnoteElem {
+    #[elem]
+    type EndnoteEntry;
+}
+
+impl LocalName for Packed<EndnoteElem> {
+    const KEY: &'static str = "endnote";
+}
+
+impl EndnoteElem {
+    /// The alternative text of an endnote's number.
+    pub fn alt_text(styles: StyleChain, num: &str) -> EcoString {
+        let local_name = Packed::<EndnoteElem>::local_name_in(styles);
+        eco_format!("{local_name} {num}")
+    }
+
+    /// Creates a new endnote referencing the endnote with the specified label,
+    /// with the other fields from the current endnote cloned.
+    pub fn into_ref(&self, label: Label) -> Self {
+        Self {
+            body: FootnoteBody::Reference(label),
+            ..self.clone()
+        }
+    }
+
+    /// Tests if this endnote is a reference to another endnote.
+    pub fn is_ref(&self) -> bool {
+        matches!(self.body, FootnoteBody::Reference(_))
+    }
+
+    /// Returns the content of the body of this endnote if it is not a ref.
+    pub fn body_content(&self) -> Option<&Content> {
+        match &self.body {
+            FootnoteBody::Content(content) => Some(content),
+            _ => None,
+        }
+    }
+}
+
+impl Packed<EndnoteElem> {
+    /// Returns the linking destination and the resolved numbers.
+    ///
+    /// The destination is `None` if no list of endnotes displays the note. In
+    /// that case, a warning is emitted.
+    pub fn realize(
+        &self,
+        engine: &mut Engine,
+        styles: StyleChain,
+    ) -> SourceResult<(Option<Destination>, Content)> {
+        let span = self.span();
+        let loc = self.declaration_location(engine).at(span)?;
+        let numbering = self.numbering.get_ref(styles);
+        let counter = Counter::of(EndnoteElem::ELEM);
+        let num = counter.display_at(engine, loc, styles, numbering, span)?;
+
+        if find_list(engine, loc, span).is_none() {
+            engine.sink.warn(warning!(
+                span, "endnote is not displayed";
+                hint: "add `#endnotes()` after the endnote to display it";
+            ));
+            return Ok((None, num));
+        }
+
+        Ok((Some(Destination::Location(loc.variant(1))), num))
+    }
+
+    /// Returns the location of the definition of this endnote.
+    pub fn declaration_location(&self, engine: &mut Engine) -> StrResult<Location> {
+        match self.body {
+            FootnoteBody::Reference(label) => {
+                let element =
+                    engine.introspect(QueryLabelIntrospection(label, self.span()))?;
+                let endnote = element
+                    .to_packed::<EndnoteElem>()
+                    .ok_or("referenced element should be an endnote")?;
+                if self.location() == endnote.location() {
+                    bail!("endnote cannot reference itself");
+                }
+                endnote.declaration_location(engine)
+            }
+            _ => Ok(self.location().unwrap()),
+        }
+    }
+}
+
+impl Count for Packed<EndnoteElem> {
+    fn update(&self) -> Option<CounterUpdate> {
+        (!self.is_ref()).then(|| CounterUpdate::Step(NonZeroUsize::ONE))
+    }
+}
+
+cast! {
+    EndnoteElem,
+    v: Content => v.unpack::<Self>().unwrap_or_else(|v| Self::new(FootnoteBody::Content(v)))
+}
+
+/// An entry in a list of endnotes.
+///
+/// This function is not intended to be called directly. Instead, it is used in
+/// set and show rules to customize endnote listings.
+///
+/// ```example
+/// #show endnote.entry: set text(blue)
+///
+/// Blue endnotes.#endnote[Like this.]
+///
+/// #endnotes(title: none)
+/// ```
+#[elem(name = "entry", title = "Endnote Entry", Locatable, Tagged)]
+pub struct EndnoteEntry {
+    /// The endnote for this entry. Its location can be used to determine
+    /// the endnote counter state.
+    ///
+    /// ```example
+    /// #show endnote.entry: it => {
+    ///   let loc = it.note.location()
+    ///   counter(endnote).display(at: loc, "1: ")
+    ///   it.note.body
+    /// }
+    ///
+    /// Customized#endnote[Hello]
+    /// listing.#endnote[World!]
+    ///
+    /// #endnotes(title: none)
+    /// ```
+    #[required]
+    pub note: Packed<EndnoteElem>,
+}
+
+impl Packed<EndnoteEntry> {
+    /// Returns the number of the entry, which links back to the endnote, and
+    /// the body content.
+    pub fn realize(
+        &self,
+        engine: &mut Engine,
+        styles: StyleChain,
+    ) -> SourceResult<(Content, Content)> {
+        let span = self.span();
+        let default = StyleChain::default();
+        let numbering = self.note.numbering.get_ref(default);
+        let counter = Counter::of(EndnoteElem::ELEM);
+        let Some(loc) = self.note.location() else {
+            bail!(
+                span, "endnote entry must have a location";
+                hint: "try using a query or a show rule to customize the endnote instead";
+            );
+        };
+
+        let num = counter.display_at(engine, loc, styles, numbering, span)?;
+        let alt = num.plain_text();
+        let sup = SuperElem::new(num).pack().spanned(span);
+        let prefix = DirectLinkElem::new(loc, sup, Some(alt)).pack().spanned(span);
+        let body = self.note.body_content().unwrap().clone();
+
+        Ok((prefix, body))
+    }
+}
+
+/// A list of endnotes.
+///
+/// Displays all [endnotes]($endnote) since the previous list of endnotes, or
+/// since the start of the document if there is none. By placing a list at the
+/// end of each chapter, notes are grouped by chapter.
+///
+/// # Example
+/// ```example
+/// = Introduction
+/// Hello.#endnote[A greeting.]
+/// #endnotes(title: [Notes to the Introduction])
+///
+/// = Conclusion
+/// Goodbye.#endnote[A farewell.]
+/// #endnotes(title: [Notes to the Conclusion])
+/// ```
+#[elem(title = "Endnote List", Locatable, Tagged, ShowSet, LocalName)]
+pub struct EndnotesElem {
+    /// The title of the list of endnotes.
+    ///
+    /// - When set to `{auto}`, an appropriate title for the
+    ///   [text language]($text.lang) will be used.
+    /// - When set to `{none}`, the list will not have a title.
+    /// - A custom title can be set by passing content.
+    pub title: Smart<Option<Content>>,
+
+    /// Whether to restart the numbering of endnotes after this list.
+    ///
+    /// ```example
+    /// A#endnote[First]
+    /// #endnotes(title: none, reset: false)
+    /// B#endnote[Second]
+    /// #endnotes(title: none)
+    /// ```
+    #[default(true)]
+    pub reset: bool,
+}
+
+impl Packed<EndnotesElem> {
+    /// Produces the heading for the list, if any.
+    pub fn realize_title(&self, styles: StyleChain) -> Option<Content> {
+        let span = self.span();
+        self.title
+            .get_cloned(styles)
+            .unwrap_or_else(|| {
+                Some(
+                    TextElem::packed(Packed::<EndnotesElem>::local_name_in(styles))
+                        .spanned(span),
+                )
+            })
+            .map(|title| {
+                HeadingElem::new(title)
+                    .with_depth(NonZeroUsize::ONE)
+                    .pack()
+                    .spanned(span)
+            })
+    }
+
+    /// Collects the endnotes that are displayed by this list, that is all
+    /// endnotes after the previous list.
+    pub fn notes(&self, engine: &mut Engine) -> Vec<Packed<EndnoteElem>> {
+        let loc = self.location().unwrap();
+        let mut notes = vec![];
+        for elem in engine.introspect(QueryIntrospection(select_notes(), self.span())) {
+            if elem.location() == Some(loc) {
+                break;
+            } else if elem.is::<EndnotesElem>() {
+                notes.clear();
+            } else if let Ok(note) = elem.into_packed::<EndnoteElem>()
+                && !note.is_ref()
+            {
+                notes.push(note);
+            }
+        }
+        notes
+    }
+
+    /// Produces the counter update that restarts the numbering after the list,
+    /// if enabled.
+    pub fn realize_reset(&self, styles: StyleChain) -> Option<Content> {
+        self.reset.get(styles).then(|| {
+            Counter::of(EndnoteElem::ELEM)
+                .update(self.span(), CounterUpdate::Set(CounterState::init(false)))
+        })
+    }
+}
+
+impl ShowSet for Packed<EndnotesElem> {
+    fn show_set(&self, _: StyleChain) -> Styles {
+        let mut out = Styles::new();
+        out.set(HeadingElem::numbering, None);
+        out
+    }
+}
+
+impl LocalName for Packed<EndnotesElem> {
+    const KEY: &'static str = "endnotes";
+}
+
+/// Selects all endnotes and lists of endnotes.
+fn select_notes() -> Selector {
+    Selector::Or(eco_vec![EndnoteElem::ELEM.select(), EndnotesElem::ELEM.select()])
+}
+
+/// Finds the list of endnotes that displays the endnote at the given location.
+fn find_list(engine: &mut Engine, loc: Location, span: Span) -> Option<Location> {
+    engine
+        .introspect(QueryIntrospection(select_notes(), span))
+        .iter()
+        .skip_while(|elem| elem.location() != Some(loc))
+        .find(|elem| elem.is::<EndnotesElem>())
+        .and_then(|elem| elem.location())
+}
@@ -0,0 +1,334 @@
+use std::num::NonZeroUsize;
+
+use comemo::Track;
+use ecow::{EcoString, eco_format};
+use typst_syntax::Span;
+use typst_utils::NonZeroExt;
+
+use crate::diag::{At, SourceResult, StrResult};
+use crate::engine::Engine;
+use crate::foundations::{
+    Content, Context, Label, NativeElement, Packed, Selector, ShowSet, Smart, StyleChain,
+    Styles, elem, scope, select_where,
+};
+use crate::introspection::{
+    Counter, CounterKey, Locatable, Location, PageIntrospection, QueryIntrospection,
+};
+use crate::layout::PageElem;
+use crate::model::{
+    DirectLinkElem, HeadingElem, ParElem, RefElem, SortKey, Supplement, format_page,
+};
+use crate::text::{LocalName, TextElem};
+
+/// A glossary of the terms and acronyms used in the document.
+///
+/// Terms are defined with [glossary entries]($glossary.entry) and used by
+/// [referencing]($ref) the entry's label. The first use of a term with a
+/// [long form]($glossary.entry.long) shows both forms, for instance "Portable
+/// Document Format (PDF)", while later uses only show the short form. Each use
+/// links to the term's definition in the glossary.
+///
+/// The glossary lists only the terms that are used in the document, sorted
+/// according to the [text language]($text.lang). Each term is followed by the
+/// pages on which it is used.
+///
+/// # Example
+/// ```example
+/// #set page(height: 150pt)
+/// #glossary.entry(
+///   "PDF",
+///   long: [Portable Document Format],
+/// ) <pdf>
+///
+/// Typst exports @pdf files.
+/// Many @pdf viewers exist.
+///
+/// #glossary()
+/// ```
+///
+/// # First use
+/// Whether a use is the first one is determined by a [counter] of the
+/// references to the entry's label. By resetting that counter, the long form
+/// can be shown again, for instance at the start of each chapter.
+///
+/// ```example
+/// #glossary.entry(
+///   "HTML",
+///   long: [HyperText Markup Language],
+/// ) <html>
+///
+/// @html, @html
+///
+/// #counter(ref.where(target: <html>)).update(0)
+/// @html
+/// ```
+#[elem(scope, keywords = ["acronym", "abbreviation"], ShowSet, LocalName, Locatable)]
+pub struct GlossaryElem {
+    /// The title of the glossary.
+    ///
+    /// - When set to `{auto}`, an appropriate title for the
+    ///   [text language]($text.lang) will be used.
+    /// - When set to `{none}`, the glossary will not have a title.
+    /// - A custom title can be set by passing content.
+    pub title: Smart<Option<Content>>,
+}
+
+#[scope]
+impl GlossaryElem {
+    #[elem]
+    type GlossaryEntry;
+}
+
+impl Packed<GlossaryElem> {
+    /// Produces the heading for the glossary, if any.
+    pub fn realize_title(&self, styles: StyleChain) -> Option<Content> {
+        let span = self.span();
+        self.title
+            .get_cloned(styles)
+            .unwrap_or_else(|| {
+                Some(
+                    TextElem::packed(Packed::<GlossaryElem>::local_name_in(styles))
+                        .spanned(span),
+                )
+            })
+            .map(|title| {
+                HeadingElem::new(title)
+                    .with_depth(NonZeroUsize::ONE)
+                    .pack()
+                    .spanned(span)
+            })
+    }
+
+    /// Collects all glossary entries that are used in the document, in sorted
+    /// order.
+    pub fn realize_items(
+        &self,
+        engine: &mut Engine,
+        styles: StyleChain,
+    ) -> SourceResult<Vec<GlossaryItem>> {
+        let span = self.span();
+        let lang = styles.get(TextElem::lang);
+        let glossary = engine
+            .introspect(QueryIntrospection(GlossaryElem::ELEM.select(), span))
+            .iter()
+            .position(|elem| elem.location() == self.location())
+            .unwrap_or_default();
+        let entries =
+            engine.introspect(QueryIntrospection(GlossaryEntry::ELEM.select(), span));
+
+        let mut items = vec![];
+        for entry in entries {
+            let entry = entry.to_packed::<GlossaryEntry>().unwrap();
+            let Some(label) = entry.label() else { continue };
+
+            let uses: Vec<Location> = engine
+                .introspect(QueryIntrospection(GlossaryEntry::uses(label), span))
+                .iter()
+                .map(|elem| elem.location().unwrap())
+                .collect();
+            if uses.is_empty() {
+                continue;
+            }
+
+            let key = SortKey::new(&entry.sort_text(StyleChain::default()), lang);
+            let target = entry.target(glossary).at(span)?;
+            items.push((key, GlossaryItem { entry: entry.clone(), target, uses }));
+        }
+
+        items.sort_by(|a, b| a.0.cmp(&b.0));
+        Ok(items.into_iter().map(|(_, item)| item).collect())
+    }
+}
+
+impl ShowSet for Packed<GlossaryElem> {
+    fn show_set(&self, _: StyleChain) -> Styles {
+        let mut out = Styles::new();
+        out.set(HeadingElem::outlined, false);
+        out.set(HeadingElem::numbering, None);
+        out.set(ParElem::justify, false);
+        out
+    }
+}
+
+impl LocalName for Packed<GlossaryElem> {
+    const KEY: &'static str = "glossary";
+}
+
+/// Defines a term or acronym for the [glossary].
+///
+/// The entry itself is invisible. To use the term, attach a label to the entry
+/// and reference it. References to glossary entries show the term instead of a
+/// number.
+///
+/// ```example
+/// #glossary.entry(
+///   "API",
+///   long: [application programming interface],
+///   description: [A set of functions exposed by a program.],
+/// ) <api>
+///
+/// The @api is stable.
+/// Our @api has few functions.
+///
+/// #glossary(title: none)
+/// ```
+#[elem(name = "entry", title = "Glossary Entry", Locatable)]
+pub struct GlossaryEntry {
+    /// The short form of the term, for instance an acronym.
+    #[required]
+    pub short: Content,
+
+    /// The long form of the term. If given, it is shown along with the short
+    /// form on the first use of the term.
+    pub long: Option<Content>,
+
+    /// A description of the term that is shown in the glossary.
+    pub description: Option<Content>,
+
+    /// The text by which the term is sorted. Defaults to the short form's plain
+    /// text.
+    pub key: Option<EcoString>,
+}
+
+impl GlossaryEntry {
+    /// Selects all uses of the entry with the given label.
+    ///
+    /// This is also the key of the counter that tracks the first use.
+    pub fn uses(label: Label) -> Selector {
+        select_where!(RefElem, target => label)
+    }
+
+    /// The text by which the term is sorted.
+    fn sort_text(&self, styles: StyleChain) -> EcoString {
+        self.key.get_cloned(styles).unwrap_or_else(|| self.short.plain_text())
+    }
+}
+
+impl Packed<GlossaryEntry> {
+    /// The location of the term's definition in the document's glossary with
+    /// the given index.
+    ///
+    /// Each glossary defines the term at a distinct variant of the entry's
+    /// location, so that a document with multiple glossaries has a distinct
+    /// target per listing.
+    pub fn target(&self, glossary: usize) -> StrResult<Location> {
+        Ok(self.entry_location()?.variant(glossary + 1))
+    }
+
+    /// The location of the entry itself.
+    fn entry_location(&self) -> StrResult<Location> {
+        self.location().ok_or_else(|| "glossary entry has no location".into())
+    }
+
+    /// Realizes a reference to this entry as a use of the term.
+    pub fn realize_ref(
+        &self,
+        reference: &Packed<RefElem>,
+        engine: &mut Engine,
+        styles: StyleChain,
+    ) -> SourceResult<Content> {
+        let span = reference.span();
+        let default = StyleChain::default();
+        let short = self.short.clone();
+        let long = self.long.get_cloned(default);
+
+        // The counter includes the reference itself, so the first use of the
+        // term sees a count of one.
+        let loc = reference.location().unwrap();
+        let counter =
+            Counter::new(CounterKey::Selector(GlossaryEntry::uses(reference.target)));
+        let context = Context::new(Some(loc), Some(styles));
+        let first = counter.get(engine, context.track(), span)?.first() == 1;
+
+        let body = match reference.supplement.get_ref(styles) {
+            Smart::Custom(Some(Supplement::Content(content))) => content.clone(),
+            Smart::Custom(Some(supplement @ Supplement::Func(_))) => {
+                supplement.resolve(engine, styles, [self.clone().pack()])?
+            }
+            _ => match long {
+                Some(long) if first => {
+                    long + TextElem::packed(" (") + short + TextElem::packed(")")
+                }
+                _ => short,
+            },
+        };
+
+        // Link to the definition in the first glossary if there is one and to
+        // the invisible entry otherwise.
+        let glossaries =
+            engine.introspect(QueryIntrospection(GlossaryElem::ELEM.select(), span));
+        let dest = if glossaries.is_empty() {
+            self.entry_location().at(span)?
+        } else {
+            self.target(0).at(span)?
+        };
+        let alt = body.plain_text();
+
+        Ok(DirectLinkElem::new(dest, body.spanned(span), Some(alt))
+            .pack()
+            .spanned(span))
+    }
+}
+
+/// A used term in the glossary.
+#[derive(Debug, Clone)]
+pub struct GlossaryItem {
+    /// The entry that defines the term.
+    pub entry: Packed<GlossaryEntry>,
+    /// The location of the term's definition in the glossary.
+    pub target: Location,
+    /// The locations of the references to the term, in document order.
+    pub uses: Vec<Location>,
+}
+
+impl GlossaryItem {
+    /// Produces the description of the term: its long form followed by its
+    /// description, if any.
+    pub fn realize_description(&self) -> Content {
+        let default = StyleChain::default();
+        let parts: Vec<Content> = [
+            self.entry.long.get_cloned(default),
+            self.entry.description.get_cloned(default),
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+
+        let mut seq = vec![];
+        for (i, part) in parts.into_iter().enumerate() {
+            if i > 0 {
+                seq.push(TextElem::packed(". "));
+            }
+            seq.push(part);
+        }
+
+        Content::sequence(seq)
+    }
+
+    /// Produces the linked numbers of the pages on which the term is used.
+    /// Each page is listed only once and links to the first use on it.
+    pub fn realize_pages(
+        &self,
+        engine: &mut Engine,
+        styles: StyleChain,
+        span: Span,
+    ) -> SourceResult<Vec<Content>> {
+        let mut pages: Vec<(Location, NonZeroUsize)> = vec![];
+        for &loc in &self.uses {
+            let page = engine.introspect(PageIntrospection(loc, span));
+            if !pages.iter().any(|&(_, p)| p == page) {
+                pages.push((loc, page));
+            }
+        }
+
+        let page_str = PageElem::local_name_in(styles);
+        pages
+            .into_iter()
+            .map(|(loc, _)| {
+                let body = format_page(engine, loc, styles, span)?;
+                let alt = eco_format!("{page_str} {}", body.plain_text());
+                Ok(DirectLinkElem::new(loc, body, Some(alt)).pack().spanned(span))
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,629 @@
+use std::num::NonZeroUsize;
+use std::str::FromStr;
+
+use ecow::{EcoString, eco_format};
+use typst_syntax::Span;
+use typst_utils::NonZeroExt;
+use unicode_normalization::UnicodeNormalization;
+use unicode_normalization::char::is_combining_mark;
+
+use crate::diag::SourceResult;
+use crate::engine::Engine;
+use crate::foundations::{
+    Content, NativeElement, Packed, ShowSet, Smart, StyleChain, Styles, elem, scope,
+};
+use crate::introspection::{
+    Counter, CounterKey, Locatable, Location, PageIntrospection,
+    PageNumberingIntrospection, QueryIntrospection,
+};
+use crate::layout::PageElem;
+use crate::model::{DirectLinkElem, HeadingElem, NumberingPattern, ParElem};
+use crate::text::{Lang, LocalName, TextElem, localized_str};
+
+/// An alphabetical index of terms, as found at the back of a book.
+///
+/// The index collects all [index entries]($index.entry) in the document,
+/// sorts them according to the [text language]($text.lang) and lists each
+/// term with the pages on which it occurs. Pages are linked to the places where
+/// the term was marked, and runs of consecutive pages are compressed into
+/// ranges.
+///
+/// # Example
+/// ```example
+/// #set page(height: 120pt)
+///
+/// Typst#index.entry[Typst] is a
+/// markup-based#index.entry[markup] typesetting
+/// system#index.entry("typesetting", sub: [system]).
+///
+/// #pagebreak()
+/// #index()
+/// ```
+///
+/// # Sub-entries and cross references
+/// An entry can be placed below a parent term with the
+/// [`sub`]($index.entry.sub) argument. Entries sharing the same term are merged
+/// and their sub-entries are listed below them. Cross references to other terms
+/// can be added with [`see`]($index.entry.see) and
+/// [`see-also`]($index.entry.see-also).
+///
+/// ```example
+/// #index.entry("fonts", sub: "fallback")
+/// #index.entry("fonts", see-also: [typography])
+/// #index.entry("typefaces", see: [fonts])
+///
+/// #index(title: none)
+/// ```
+#[elem(scope, keywords = ["back-of-book index"], ShowSet, LocalName)]
+pub struct IndexElem {
+    /// The title of the index.
+    ///
+    /// - When set to `{auto}`, an appropriate title for the
+    ///   [text language]($text.lang) will be used.
+    /// - When set to `{none}`, the index will not have a title.
+    /// - A custom title can be set by passing content.
+    pub title: Smart<Option<Content>>,
+
+    /// Whether to group the entries by their initial letter and to display
+    /// each letter above its group.
+    ///
+    /// ```example
+    /// #index.entry[apple]
+    /// #index.entry[avocado]
+    /// #index.entry[banana]
+    ///
+    /// #index(title: none, grouped: false)
+    /// ```
+    #[default(true)]
+    pub grouped: bool,
+
+    /// Whether to compress references on consecutive pages into a page range,
+    /// for instance `3–5` instead of `3, 4, 5`.
+    #[default(true)]
+    pub compress: bool,
+}
+
+#[scope]
+impl IndexElem {
+    #[elem]
+    type IndexEntry;
+}
+
+impl Packed<IndexElem> {
+    /// Produces the heading for the index, if any.
+    pub fn realize_title(&self, styles: StyleChain) -> Option<Content> {
+        let span = self.span();
+        self.title
+            .get_cloned(styles)
+            .unwrap_or_else(|| {
+                Some(
+                    TextElem::packed(Packed::<IndexElem>::local_name_in(styles))
+                        .spanned(span),
+                )
+            })
+            .map(|title| {
+                HeadingElem::new(title)
+                    .with_depth(NonZeroUsize::ONE)
+                    .pack()
+                    .spanned(span)
+            })
+    }
+
+    /// Collects all index entries of the document into sorted letter groups.
+    ///
+    /// If the index is not [grouped]($index.grouped), a single group without
+    /// a letter is returned.
+    pub fn realize_groups(
+        &self,
+        engine: &mut Engine,
+        styles: StyleChain,
+    ) -> SourceResult<Vec<IndexGroup>> {
+        let span = self.span();
+        let lang = styles.get(TextElem::lang);
+        let entries =
+            engine.introspect(QueryIntrospection(IndexEntry::ELEM.select(), span));
+
+        let mut roots: Vec<IndexNode> = vec![];
+        for entry in entries {
+            let entry = entry.to_packed::<IndexEntry>().unwrap();
+            let default = StyleChain::default();
+            let loc = entry.location().unwrap();
+
+            let term = entry.term.clone();
+            let key = SortKey::new(&entry.sort_text(default), lang);
+            let mut node = IndexNode::find_or_insert(&mut roots, term, key);
+
+            if let Some(sub) = entry.sub.get_cloned(default) {
+                let key = SortKey::new(&sub.plain_text(), lang);
+                node = IndexNode::find_or_insert(&mut node.children, sub, key);
+            }
+
+            let see = entry.see.get_cloned(default);
+            let see_also = entry.see_also.get_cloned(default);
+            let is_ref = see.is_none() && see_also.is_none();
+            node.add_cross_ref(see, CrossRefKind::See);
+            node.add_cross_ref(see_also, CrossRefKind::SeeAlso);
+
+            if is_ref {
+                node.refs.push(IndexRef { loc, main: entry.main.get(default) });
+            }
+        }
+
+        IndexNode::sort(&mut roots);
+
+        if !self.grouped.get(styles) {
+            return Ok(vec![IndexGroup { letter: None, nodes: roots }]);
+        }
+
+        let mut groups: Vec<IndexGroup> = vec![];
+        for node in roots {
+            let letter = node.key.letter.clone();
+            match groups.last_mut() {
+                Some(group) if group.letter == letter => group.nodes.push(node),
+                _ => groups.push(IndexGroup { letter, nodes: vec![node] }),
+            }
+        }
+
+        Ok(groups)
+    }
+}
+
+impl ShowSet for Packed<IndexElem> {
+    fn show_set(&self, _: StyleChain) -> Styles {
+        let mut out = Styles::new();
+        out.set(HeadingElem::outlined, false);
+        out.set(HeadingElem::numbering, None);
+        out.set(ParElem::justify, false);
+        out
+    }
+}
+
+impl LocalName for Packed<IndexElem> {
+    const KEY: &'static str = "index";
+}
+
+/// Marks a term that should appear in the [index]($index).
+///
+/// The entry itself is invisible. It records the location at which it was
+/// placed, so that the index can list and link the page on which the term
+/// occurs.
+///
+/// ```example
+/// Kerning#index.entry[kerning] adjusts
+/// the space between letters.
+///
+/// #index(title: none)
+/// ```
+#[elem(name = "entry", title = "Index Entry", Locatable)]
+pub struct IndexEntry {
+    /// The term under which the entry is listed.
+    #[required]
+    pub term: Content,
+
+    /// A sub-entry to list below the term.
+    ///
+    /// ```example
+    /// #index.entry("page", sub: "margins")
+    /// #index.entry("page", sub: "size")
+    ///
+    /// #index(title: none)
+    /// ```
+    pub sub: Option<Content>,
+
+    /// The text by which the term is sorted. Defaults to the term's plain text.
+    ///
+    /// This is useful for terms that should be sorted differently than they
+    /// are written, for instance names with a particle.
+    ///
+    /// ```example
+    /// #index.entry(key: "Gogh", [van Gogh])
+    /// #index.entry[Goethe]
+    ///
+    /// #index(title: none)
+    /// ```
+    pub key: Option<EcoString>,
+
+    /// Another term to refer to instead of listing a page for this entry.
+    pub see: Option<Content>,
+
+    /// A related term to refer to in addition to the pages of this term.
+    pub see_also: Option<Content>,
+
+    /// Whether this is the main reference for the term. The page numbers of
+    /// main references are emphasized with bold text in the index.
+    #[default(false)]
+    pub main: bool,
+}
+
+impl IndexEntry {
+    /// The text by which the top-level term is sorted and merged.
+    fn sort_text(&self, styles: StyleChain) -> EcoString {
+        self.key.get_cloned(styles).unwrap_or_else(|| self.term.plain_text())
+    }
+}
+
+/// A group of index terms that start with the same letter.
+#[derive(Debug, Clone)]
+pub struct IndexGroup {
+    /// The upper-case letter shared by all terms in the group. Is `None` for
+    /// terms not starting with a letter and for ungrouped indices.
+    pub letter: Option<EcoString>,
+    /// The terms in the group, in sorted order.
+    pub nodes: Vec<IndexNode>,
+}
+
+/// A term in the index along with its references and sub-entries.
+#[derive(Debug, Clone)]
+pub struct IndexNode {
+    /// The term as it is displayed.
+    pub term: Content,
+    /// The places where the term was marked, in document order.
+    pub refs: Vec<IndexRef>,
+    /// Cross references to other terms.
+    pub cross_refs: Vec<(CrossRefKind, Content)>,
+    /// The sub-entries of the term, in sorted order.
+    pub children: Vec<IndexNode>,
+    /// The key by which the term is sorted.
+    key: SortKey,
+}
+
+impl IndexNode {
+    /// Finds the node with the given key among the nodes or creates it.
+    fn find_or_insert(nodes: &mut Vec<Self>, term: Content, key: SortKey) -> &mut Self {
+        let i = match nodes.iter().position(|node| node.key == key) {
+            Some(i) => i,
+            None => {
+                nodes.push(Self {
+                    term,
+                    refs: vec![],
+                    cross_refs: vec![],
+                    children: vec![],
+                    key,
+                });
+                nodes.len() - 1
+            }
+        };
+        &mut nodes[i]
+    }
+
+    /// Adds a cross reference unless an equal one exists already.
+    fn add_cross_ref(&mut self, target: Option<Content>, kind: CrossRefKind) {
+        let Some(target) = target else { return };
+        let text = target.plain_text();
+        if !self
+            .cross_refs
+            .iter()
+            .any(|(k, existing)| *k == kind && existing.plain_text() == text)
+        {
+            self.cross_refs.push((kind, target));
+        }
+    }
+
+    /// Sorts the nodes and their descendants by their keys.
+    fn sort(nodes: &mut [Self]) {
+        nodes.sort_by(|a, b| a.key.cmp(&b.key));
+        for node in nodes {
+            Self::sort(&mut node.children);
+        }
+    }
+
+    /// Produces the linked page numbers of the term.
+    ///
+    /// References on the same page are merged. If `compress` is true,
+    /// references on consecutive pages are merged into a range, but a range
+    /// never mixes main and other references.
+    pub fn realize_pages(
+        &self,
+        engine: &mut Engine,
+        styles: StyleChain,
+        compress: bool,
+        span: Span,
+    ) -> SourceResult<Vec<Content>> {
+        let pages = self
+            .refs
+            .iter()
+            .map(|r| (r.loc, engine.introspect(PageIntrospection(r.loc, span)), r.main));
+        let ranges = PageRange::merge(pages, compress);
+
+        let page_str = PageElem::local_name_in(styles);
+        ranges
+            .into_iter()
+            .map(|range| {
+                let (start, end) = (range.start.0, range.end.0);
+                let mut body = format_page(engine, start, styles, span)?;
+                if range.start.1 != range.end.1 {
+                    body += TextElem::packed("–");
+                    body += format_page(engine, end, styles, span)?;
+                }
+                let alt = eco_format!("{page_str} {}", body.plain_text());
+                if range.main {
+                    body = body.strong();
+                }
+                Ok(DirectLinkElem::new(start, body, Some(alt)).pack().spanned(span))
+            })
+            .collect()
+    }
+
+    /// Produces the cross references of the term, for instance
+    /// "_see_ Fonts; _see also_ Typography".
+    pub fn realize_cross_refs(&self, styles: StyleChain) -> Option<Content> {
+        if self.cross_refs.is_empty() {
+            return None;
+        }
+
+        let lang = styles.get(TextElem::lang);
+        let region = styles.get(TextElem::region);
+        let mut seq = vec![];
+        for (i, (kind, target)) in self.cross_refs.iter().enumerate() {
+            if i > 0 {
+                seq.push(TextElem::packed("; "));
+            }
+            let key = match kind {
+                CrossRefKind::See => "index-see",
+                CrossRefKind::SeeAlso => "index-see-also",
+            };
+            seq.push(TextElem::packed(localized_str(lang, region, key)).emph());
+            seq.push(TextElem::packed(" "));
+            seq.push(target.clone());
+        }
+
+        Some(Content::sequence(seq))
+    }
+}
+
+/// A place where a term was marked.
+#[derive(Debug, Copy, Clone)]
+pub struct IndexRef {
+    /// The location of the index entry.
+    pub loc: Location,
+    /// Whether this is a main reference.
+    pub main: bool,
+}
+
+/// The kind of a cross reference between index terms.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum CrossRefKind {
+    /// Refers to another term instead of listing pages.
+    See,
+    /// Refers to a related term.
+    SeeAlso,
+}
+
+/// A range of consecutive pages with references to a term.
+struct PageRange {
+    start: (Location, NonZeroUsize),
+    end: (Location, NonZeroUsize),
+    main: bool,
+}
+
+impl PageRange {
+    /// Merges references, given in document order, into page ranges.
+    ///
+    /// References on the same page are listed once, as a main reference if
+    /// any of them is one. If `compress` is true, consecutive pages are merged
+    /// into a range as long as they agree in whether they are main references.
+    fn merge(
+        refs: impl IntoIterator<Item = (Location, NonZeroUsize, bool)>,
+        compress: bool,
+    ) -> Vec<Self> {
+        let mut pages: Vec<Self> = vec![];
+        for (loc, page, main) in refs {
+            match pages.last_mut() {
+                Some(last) if last.end.1 == page => last.main |= main,
+                _ => pages.push(Self { start: (loc, page), end: (loc, page), main }),
+            }
+        }
+
+        let mut ranges: Vec<Self> = vec![];
+        for page in pages {
+            match ranges.last_mut() {
+                Some(range)
+                    if compress
+                        && range.main == page.main
+                        && range.end.1.get() + 1 == page.start.1.get() =>
+                {
+                    range.end = page.end;
+                }
+                _ => ranges.push(page),
+            }
+        }
+        ranges
+    }
+}
+
+/// Formats the number of the page the location is on with that page's
+/// numbering.
+pub(crate) fn format_page(
+    engine: &mut Engine,
+    loc: Location,
+    styles: StyleChain,
+    span: Span,
+) -> SourceResult<Content> {
+    let numbering = engine
+        .introspect(PageNumberingIntrospection(loc, span))
+        .unwrap_or_else(|| NumberingPattern::from_str("1").unwrap().into());
+    Counter::new(CounterKey::Page).display_at(engine, loc, styles, &numbering, span)
+}
+
+/// A key by which index terms are sorted.
+///
+/// This approximates language-aware collation: Terms are first compared
+/// case-insensitively on their base letters (ignoring diacritics), then
+/// including diacritics, and finally by their exact text. Languages that sort
+/// some letters with diacritics separately (for instance, Swedish `å`, `ä`,
+/// `ö` after `z`) are tailored accordingly.
+#[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd)]
+pub(crate) struct SortKey {
+    primary: Vec<char>,
+    secondary: EcoString,
+    tertiary: EcoString,
+    letter: Option<EcoString>,
+}
+
+impl SortKey {
+    /// Computes the sort key for a text in the given language.
+    pub(crate) fn new(text: &str, lang: Lang) -> Self {
+        let text = text.trim();
+        let lower: EcoString = text.to_lowercase().into();
+        let tailoring = tailoring(lang);
+
+        let mut primary = vec![];
+        let mut letter = None;
+        for c in lower.chars() {
+            let base = if let Some(i) = tailoring.after_z.iter().position(|&t| t == c) {
+                // Sorts after `z`, in the order given by the tailoring.
+                let rank = char::from_u32(0xE000 + i as u32).unwrap();
+                primary.extend(['z', char::MAX, rank]);
+                Some(c)
+            } else if let Some(&(_, base)) = tailoring.after.iter().find(|p| p.0 == c) {
+                // Sorts directly after its base letter.
+                primary.extend([base, char::MAX]);
+                Some(c)
+            } else {
+                let start = primary.len();
+                primary.extend(c.to_string().nfd().filter(|&c| !is_combining_mark(c)));
+                primary.get(start).copied()
+            };
+
+            // The first letter determines the group. Tailored letters form
+            // their own group while others are grouped by their base letter.
+            if letter.is_none()
+                && let Some(base) = base
+            {
+                letter =
+                    Some(base.is_alphabetic().then(|| base.to_uppercase().collect()));
+            }
+        }
+
+        Self {
+            primary,
+            secondary: lower,
+            tertiary: text.into(),
+            letter: letter.flatten(),
+        }
+    }
+}
+
+/// Language-specific deviations from the default sort order.
+struct Tailoring {
+    /// Letters that sort after `z`, in order.
+    after_z: &'static [char],
+    /// Letters that sort directly after a base letter.
+    after: &'static [(char, char)],
+}
+
+/// Returns the tailoring for a language.
+fn tailoring(lang: Lang) -> Tailoring {
+    let (after_z, after): (&[char], &[(char, char)]) = match lang {
+        Lang::SWEDISH => (&['å', 'ä', 'ö'], &[]),
+        Lang::FINNISH => (&['å', 'ä', 'ö'], &[]),
+        Lang::DANISH
+        | Lang::NORWEGIAN
+        | Lang::NORWEGIAN_BOKMAL
+        | Lang::NORWEGIAN_NYNORSK => (&['æ', 'ø', 'å'], &[]),
+        Lang::SPANISH => (&[], &[('ñ', 'n')]),
+        Lang::POLISH => (
+            &[],
+            &[
+                ('ą', 'a'),
+                ('ć', 'c'),
+                ('ę', 'e'),
+                ('ł', 'l'),
+                ('ń', 'n'),
+                ('ó', 'o'),
+                ('ś', 's'),
+                ('ź', 'z'),
+                ('ż', 'z'),
+            ],
+        ),
+        _ => (&[], &[]),
+    };
+    Tailoring { after_z, after }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sorted(lang: Lang, words: &[&str]) -> Vec<String> {
+        let mut keys: Vec<_> =
+            words.iter().map(|w| (SortKey::new(w, lang), *w)).collect();
+        keys.sort_by(|a, b| a.0.cmp(&b.0));
+        keys.into_iter().map(|(_, w)| w.to_string()).collect()
+    }
+
+    #[test]
+    fn test_index_sort_default() {
+        assert_eq!(
+            sorted(
+                Lang::ENGLISH,
+                &["zebra", "Äpfel", "apple", "Apple", "éclair", "eagle"]
+            ),
+            ["Äpfel", "Apple", "apple", "eagle", "éclair", "zebra"],
+        );
+    }
+
+    #[test]
+    fn test_index_sort_tailored() {
+        assert_eq!(
+            sorted(Lang::SWEDISH, &["öl", "zon", "ål", "äng", "ost"]),
+            ["ost", "zon", "ål", "äng", "öl"],
+        );
+        assert_eq!(sorted(Lang::SPANISH, &["ñu", "nz", "oso"]), ["nz", "ñu", "oso"]);
+    }
+
+    #[test]
+    fn test_index_sort_letter() {
+        let letter = |w, lang| SortKey::new(w, lang).letter;
+        assert_eq!(letter("Äpfel", Lang::GERMAN).as_deref(), Some("A"));
+        assert_eq!(letter("äng", Lang::SWEDISH).as_deref(), Some("Ä"));
+        assert_eq!(letter("1984", Lang::ENGLISH), None);
+    }
+
+    fn merged(
+        pages: &[(usize, bool)],
+        compress: bool,
+    ) -> Vec<(u128, usize, usize, bool)> {
+        let refs = pages.iter().enumerate().map(|(i, &(page, main))| {
+            (Location::new(i as u128), NonZeroUsize::new(page).unwrap(), main)
+        });
+        PageRange::merge(refs, compress)
+            .into_iter()
+            .map(|range| {
+                let start = range.start.0.hash();
+                (start, range.start.1.get(), range.end.1.get(), range.main)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_index_pages_compress() {
+        let pages = [(1, false), (2, false), (2, true), (3, false), (5, false)];
+        assert_eq!(
+            merged(&pages, true),
+            [(0, 1, 1, false), (1, 2, 2, true), (3, 3, 3, false), (4, 5, 5, false)],
+        );
+        assert_eq!(
+            merged(&pages, false),
+            [(0, 1, 1, false), (1, 2, 2, true), (3, 3, 3, false), (4, 5, 5, false)],
+        );
+    }
+
+    #[test]
+    fn test_index_pages_same_page() {
+        assert_eq!(merged(&[(4, false), (4, false)], true), [(0, 4, 4, false)]);
+        assert_eq!(merged(&[(4, true), (4, false)], false), [(0, 4, 4, true)]);
+        assert!(merged(&[], true).is_empty());
+    }
+
+    #[test]
+    fn test_index_pages_main() {
+        let pages = [(1, false), (2, false), (3, true), (4, true), (5, false)];
+        assert_eq!(
+            merged(&pages, true),
+            [(0, 1, 2, false), (2, 3, 4, true), (4, 5, 5, false)],
+        );
+        let pages = [(1, false), (2, false), (2, true), (3, true)];
+        assert_eq!(merged(&pages, true), [(0, 1, 1, false), (1, 2, 3, true)]);
+    }
+}
@@ -4,11 +4,14 @@ mod bibliography;
 mod cite;
 mod document;
 mod emph;
+mod endnote;
 #[path = "enum.rs"]
 mod enum_;
 mod figure;
 mod footnote;
+mod glossary;
 mod heading;
+mod index;
 mod link;
 mod list;
 #[path = "numbering.rs"]
@@ -26,10 +29,13 @@ pub use self::bibliography::*;
 pub use self::cite::*;
 pub use self::document::*;
 pub use self::emph::*;
+pub use self::endnote::*;
 pub use self::enum_::*;
 pub use self::figure::*;
 pub use self::footnote::*;
+pub use self::glossary::*;
 pub use self::heading::*;
+pub use self::index::*;
 pub use self::link::*;
 pub use self::list::*;
 pub use self::numbering_::*;
@@ -61,7 +67,11 @@ pub fn define(global: &mut Scope) {
     global.define_elem::<FigureElem>();
     global.define_elem::<QuoteElem>();
     global.define_elem::<FootnoteElem>();
+    global.define_elem::<EndnoteElem>();
+    global.define_elem::<EndnotesElem>();
     global.define_elem::<OutlineElem>();
+    global.define_elem::<IndexElem>();
+    global.define_elem::<GlossaryElem>();
     global.define_elem::<RefElem>();
     global.define_elem::<CiteElem>();
     global.define_elem::<BibliographyElem>();
@@ -13,7 +13,8 @@ use crate::introspection::{
 };
 use crate::math::EquationElem;
 use crate::model::{
-    BibliographyElem, CiteElem, DirectLinkElem, Figurable, FootnoteElem, Numbering,
+    BibliographyElem, CiteElem, DirectLinkElem, EndnoteElem, Figurable, FootnoteElem,
+    GlossaryEntry, Numbering,
 };
 use crate::text::TextElem;
 
@@ -280,6 +281,14 @@ impl Packed<RefElem> {
             return Ok(footnote.into_ref(self.target).pack().spanned(span));
         }
 
+        if let Some(endnote) = elem.to_packed::<EndnoteElem>() {
+            return Ok(endnote.into_ref(self.target).pack().spanned(span));
+        }
+
+        if let Some(entry) = elem.to_packed::<GlossaryEntry>() {
+            return entry.realize_ref(self, engine, styles);
+        }
+
         let elem = elem.clone();
         let refable = elem
             .with::<dyn Refable>()
@@ -0,0 +1,67 @@
+use std::num::NonZeroUsize;
+
+use crate::foundations::{Content, elem};
+use crate::layout::{Em, Length};
+
+/// A drop cap: an enlarged initial letter at the start of a paragraph.
+///
+/// The drop cap is scaled such that it spans the given number of
+/// [`lines`]($dropcap.lines). Its size is measured from the cap height of the
+/// first line to the baseline of the last spanned line, so the top of the
+/// letter is flush with the capitals of the first line. The spanned lines are
+/// indented by the width of the drop cap plus the [`gap`]($dropcap.gap).
+///
+/// A drop cap only has an effect at the very start of a paragraph. Anywhere
+/// else, its body is displayed inline at the normal size.
+///
+/// # Example
+/// ```example
+/// #set par(justify: true)
+/// #dropcap[T]his is a paragraph that
+/// starts with a drop cap. The first
+/// letter spans three lines of text,
+/// which are indented to make room.
+/// #lorem(10)
+/// ```
+///
+/// # Styling
+/// Drop caps are often set in a different font or color. Since the drop cap is
+/// scaled based on the cap height of its own font, a show-set rule can be used
+/// to change its appearance without affecting its alignment.
+///
+/// ```example
+/// #show dropcap: set text(fill: maroon)
+///
+/// #dropcap(lines: 2)[O]nce upon a time,
+/// there was a paragraph with a colored
+/// initial.
+/// ```
+#[elem(title = "Drop Cap", keywords = ["initial"])]
+pub struct DropcapElem {
+    /// The number of lines the drop cap spans.
+    ///
+    /// If the paragraph has fewer lines than this, it is extended below its
+    /// last line to make room for the drop cap.
+    ///
+    /// ```example
+    /// #dropcap(lines: 2)[A] drop cap
+    /// spanning two lines of text in
+    /// this paragraph.
+    /// ```
+    #[default(NonZeroUsize::new(3).unwrap())]
+    pub lines: NonZeroUsize,
+
+    /// The horizontal gap between the drop cap and the text next to it.
+    ///
+    /// ```example
+    /// #dropcap(lines: 2, gap: 1em)[W]ith
+    /// a larger gap between the initial
+    /// and the text.
+    /// ```
+    #[default(Em::new(0.2).into())]
+    pub gap: Length,
+
+    /// The letter or letters to enlarge.
+    #[required]
+    pub body: Content,
+}
@@ -1,3 +1,4 @@
+use std::borrow::Cow;
 use std::cmp::Reverse;
 use std::collections::BTreeMap;
 use std::fmt::{self, Debug, Formatter};
@@ -7,6 +8,7 @@ use ttf_parser::{PlatformId, Tag, name_id};
 use unicode_segmentation::UnicodeSegmentation;
 
 use super::exceptions::find_exception;
+use super::woff;
 use crate::text::{
     Font, FontStretch, FontStyle, FontVariant, FontWeight, is_default_ignorable,
 };
@@ -209,14 +211,24 @@ bitflags::bitflags! {
 impl FontInfo {
     /// Compute metadata for font at the `index` of the given data.
     pub fn new(data: &[u8], index: u32) -> Option<Self> {
+        if woff::is_woff(data) {
+            return Self::new(&woff::decode(data)?, index);
+        }
+
         let ttf = ttf_parser::Face::parse(data, index).ok()?;
         Self::from_ttf(&ttf)
     }
 
     /// Compute metadata for all fonts in the given data.
     pub fn iter(data: &[u8]) -> impl Iterator<Item = FontInfo> + '_ {
-        let count = ttf_parser::fonts_in_collection(data).unwrap_or(1);
-        (0..count).filter_map(move |index| Self::new(data, index))
+        let data = if woff::is_woff(data) {
+            Cow::Owned(woff::decode(data).unwrap_or_default())
+        } else {
+            Cow::Borrowed(data)
+        };
+
+        let count = ttf_parser::fonts_in_collection(&data).unwrap_or(1);
+        (0..count).filter_map(move |index| Self::new(&data, index))
     }
 
     /// Compute metadata for a single ttf-parser face.
@@ -1,6 +1,7 @@
 //! Font handling.
 
 pub mod color;
+pub mod woff;
 
 mod book;
 mod exceptions;
@@ -53,7 +54,13 @@ struct FontInner {
 
 impl Font {
     /// Parse a font from data and collection index.
+    ///
+    /// WOFF and WOFF2 fonts are decoded into OpenType data first.
     pub fn new(data: Bytes, index: u32) -> Option<Self> {
+        if woff::is_woff(&data) {
+            return Self::new(Bytes::new(woff::decode(&data)?), index);
+        }
+
         // Safety:
         // - The slices's location is stable in memory:
         //   - We don't move the underlying vector
@@ -73,6 +80,13 @@ impl Font {
 
     /// Parse all fonts in the given data.
     pub fn iter(data: Bytes) -> impl Iterator<Item = Self> {
+        // Decode web fonts only once instead of once per font.
+        let data = if woff::is_woff(&data) {
+            Bytes::new(woff::decode(&data).unwrap_or_default())
+        } else {
+            data
+        };
+
         let count = ttf_parser::fonts_in_collection(&data).unwrap_or(1);
         (0..count).filter_map(move |index| Self::new(data.clone(), index))
     }
@@ -0,0 +1,886 @@
+//! Decoding of WOFF and WOFF2 fonts.
+//!
+//! Web fonts are converted into plain OpenType data once when they are loaded.
+//! Everything else (metadata extraction, shaping, and export) then works with
+//! the decoded data as if it had been an OpenType font from the start.
+
+use std::io::Read;
+
+/// The signature of a WOFF font.
+const WOFF: &[u8] = b"wOFF";
+
+/// The signature of a WOFF2 font.
+const WOFF2: &[u8] = b"wOF2";
+
+/// The flavor of a font collection.
+const TTCF: u32 = u32::from_be_bytes(*b"ttcf");
+
+/// Tags of tables that WOFF2 encodes with a five bit index.
+const KNOWN_TAGS: [Tag; 63] = [
+    *b"cmap", *b"head", *b"hhea", *b"hmtx", *b"maxp", *b"name", *b"OS/2", *b"post",
+    *b"cvt ", *b"fpgm", *b"glyf", *b"loca", *b"prep", *b"CFF ", *b"VORG", *b"EBDT",
+    *b"EBLC", *b"gasp", *b"hdmx", *b"kern", *b"LTSH", *b"PCLT", *b"VDMX", *b"vhea",
+    *b"vmtx", *b"BASE", *b"GDEF", *b"GPOS", *b"GSUB", *b"EBSC", *b"JSTF", *b"MATH",
+    *b"CBDT", *b"CBLC", *b"COLR", *b"CPAL", *b"SVG ", *b"sbix", *b"acnt", *b"avar",
+    *b"bdat", *b"bloc", *b"bsln", *b"cvar", *b"fdsc", *b"feat", *b"fmtx", *b"fvar",
+    *b"gvar", *b"hsty", *b"just", *b"lcar", *b"mort", *b"morx", *b"opbd", *b"prop",
+    *b"trak", *b"Zapf", *b"Silf", *b"Glat", *b"Gloc", *b"Feat", *b"Sill",
+];
+
+/// The maximum size of a decoded font.
+///
+/// The sizes in a font's header are checked against this before anything is
+/// decompressed, so that small malicious files cannot exhaust the memory.
+const MAX_SIZE: usize = 256 * 1024 * 1024;
+
+/// A four-byte table tag.
+type Tag = [u8; 4];
+
+/// Whether the data is a WOFF or WOFF2 font.
+pub fn is_woff(data: &[u8]) -> bool {
+    data.starts_with(WOFF) || data.starts_with(WOFF2)
+}
+
+/// Decodes a WOFF or WOFF2 font into OpenType data.
+///
+/// WOFF2 font collections are decoded into OpenType font collections. Returns
+/// `None` if the data is not a well-formed WOFF or WOFF2 font, including when
+/// the sizes declared in its header do not match its data or are too large.
+pub fn decode(data: &[u8]) -> Option<Vec<u8>> {
+    if data.starts_with(WOFF) {
+        decode_woff(data)
+    } else if data.starts_with(WOFF2) {
+        decode_woff2(data)
+    } else {
+        None
+    }
+}
+
+/// Decodes a WOFF font, whose tables are compressed individually.
+fn decode_woff(data: &[u8]) -> Option<Vec<u8>> {
+    let mut r = Reader::new(data);
+    r.skip(4)?; // signature
+    let flavor = r.u32()?;
+    let file_len = r.u32()? as usize;
+    let num_tables = r.u16()?;
+    r.skip(2)?; // reserved
+    let sfnt_size = r.u32()? as usize;
+    r.skip(2 + 2 + 5 * 4)?; // version, metadata, private
+
+    let mut directory = Vec::with_capacity(num_tables.into());
+    for _ in 0..num_tables {
+        let tag = r.tag()?;
+        let offset = r.u32()? as usize;
+        let compressed_length = r.u32()? as usize;
+        let length = r.u32()? as usize;
+        r.skip(4)?; // checksum
+        directory.push((tag, offset, compressed_length, length));
+    }
+
+    // The declared size of the decoded font must match its header, directory,
+    // and padded tables.
+    let expected = directory
+        .iter()
+        .try_fold(12 + 16 * usize::from(num_tables), |sum, &(.., length)| {
+            sum.checked_add(length.checked_next_multiple_of(4)?)
+        })?;
+    if file_len != data.len() || sfnt_size > MAX_SIZE || sfnt_size != expected {
+        return None;
+    }
+
+    let mut tables = Vec::with_capacity(directory.len());
+    for (tag, offset, compressed_length, length) in directory {
+        let compressed = data.get(offset..offset.checked_add(compressed_length)?)?;
+        let table = if compressed_length < length {
+            let mut table = Vec::new();
+            flate2::read::ZlibDecoder::new(compressed)
+                .take(length as u64)
+                .read_to_end(&mut table)
+                .ok()?;
+            table
+        } else if compressed_length == length {
+            compressed.to_vec()
+        } else {
+            return None;
+        };
+
+        if table.len() != length {
+            return None;
+        }
+
+        tables.push(Table { tag, data: table });
+    }
+
+    let fonts = [(flavor, (0..tables.len()).collect())];
+    Some(assemble(None, &fonts, tables))
+}
+
+/// Decodes a WOFF2 font, whose tables are compressed together and may be
+/// transformed.
+fn decode_woff2(data: &[u8]) -> Option<Vec<u8>> {
+    let mut r = Reader::new(data);
+    r.skip(4)?; // signature
+    let flavor = r.u32()?;
+    let file_len = r.u32()? as usize;
+    let num_tables = r.u16()?;
+    r.skip(2)?; // reserved
+    let sfnt_size = r.u32()? as usize;
+    let compressed_length = r.u32()? as usize;
+    r.skip(2 + 2 + 5 * 4)?; // version, metadata, private
+
+    let mut entries = Vec::with_capacity(num_tables.into());
+    for _ in 0..num_tables {
+        let flags = r.u8()?;
+        let tag = match flags & 0x3f {
+            63 => r.tag()?,
+            index => KNOWN_TAGS[usize::from(index)],
+        };
+
+        // The null transform of glyf and loca is version 3 while it is version
+        // 0 for all other tables.
+        let version = flags >> 6;
+        let transformed =
+            if matches!(&tag, b"glyf" | b"loca") { version != 3 } else { version != 0 };
+
+        let length = r.base128()? as usize;
+        let stored = if transformed { r.base128()? as usize } else { length };
+        entries.push(Entry { tag, transformed, stored });
+    }
+
+    // The fonts in the file along with their flavor and the indices of their
+    // tables.
+    let mut fonts = vec![];
+    let collection = if flavor == TTCF {
+        let version = r.u32()?;
+        for _ in 0..r.u255()? {
+            let count = r.u255()?;
+            let flavor = r.u32()?;
+            let indices = (0..count)
+                .map(|_| r.u255().map(usize::from).filter(|&i| i < entries.len()))
+                .collect::<Option<Vec<_>>>()?;
+            fonts.push((flavor, indices));
+        }
+        Some(version)
+    } else {
+        fonts.push((flavor, (0..entries.len()).collect()));
+        None
+    };
+
+    // All tables are compressed together into a single Brotli stream. The
+    // transforms make the stream smaller than the decoded font, but the size of
+    // the reconstructed tables is not known upfront.
+    let compressed = r.take(compressed_length)?;
+    let total = entries
+        .iter()
+        .try_fold(0usize, |sum, entry| sum.checked_add(entry.stored))?;
+    if file_len != data.len() || sfnt_size > MAX_SIZE || total > sfnt_size {
+        return None;
+    }
+
+    let mut stream = Vec::new();
+    brotli_decompressor::Decompressor::new(compressed, 4096)
+        .take(total as u64)
+        .read_to_end(&mut stream)
+        .ok()?;
+    if stream.len() != total {
+        return None;
+    }
+
+    let mut stored = Vec::with_capacity(entries.len());
+    let mut rest = stream.as_slice();
+    for entry in &entries {
+        let (data, tail) = rest.split_at(entry.stored);
+        stored.push(data);
+        rest = tail;
+    }
+
+    // Reconstruct the transformed tables. These are always interpreted in
+    // the context of a single font in the collection.
+    let mut tables: Vec<Option<Vec<u8>>> = entries
+        .iter()
+        .zip(&stored)
+        .map(|(entry, data)| (!entry.transformed).then(|| data.to_vec()))
+        .collect();
+
+    for (_, indices) in &fonts {
+        let find = |tag: &Tag| indices.iter().copied().find(|&i| entries[i].tag == *tag);
+
+        let mut x_mins = None;
+        if let Some(glyf) = find(b"glyf")
+            && entries[glyf].transformed
+        {
+            let loca = find(b"loca")?;
+            let (glyf_data, loca_data, mins) = reconstruct_glyf(stored[glyf])?;
+            tables[glyf] = Some(glyf_data);
+            tables[loca] = Some(loca_data);
+            x_mins = Some(mins);
+        }
+
+        if let Some(hmtx) = find(b"hmtx")
+            && entries[hmtx].transformed
+        {
+            let hhea = tables[find(b"hhea")?].as_deref()?;
+            let num_metrics = Reader::new(hhea.get(34..)?).u16()?;
+            let data = reconstruct_hmtx(stored[hmtx], num_metrics, x_mins.as_deref()?)?;
+            tables[hmtx] = Some(data);
+        }
+    }
+
+    // Tables with unknown transforms remain unset.
+    let tables = entries
+        .iter()
+        .zip(tables)
+        .map(|(entry, data)| Some(Table { tag: entry.tag, data: data? }))
+        .collect::<Option<Vec<_>>>()?;
+
+    Some(assemble(collection, &fonts, tables))
+}
+
+/// An entry in the table directory of a WOFF2 font.
+struct Entry {
+    /// The table's tag.
+    tag: Tag,
+    /// Whether the table is stored in transformed form.
+    transformed: bool,
+    /// The length of the table in the decompressed stream.
+    stored: usize,
+}
+
+/// A decoded font table.
+struct Table {
+    tag: Tag,
+    data: Vec<u8>,
+}
+
+/// Flags of simple glyph points.
+const ON_CURVE_POINT: u8 = 0x01;
+const X_SHORT_VECTOR: u8 = 0x02;
+const Y_SHORT_VECTOR: u8 = 0x04;
+const REPEAT_FLAG: u8 = 0x08;
+const X_IS_SAME_OR_POSITIVE: u8 = 0x10;
+const Y_IS_SAME_OR_POSITIVE: u8 = 0x20;
+const OVERLAP_SIMPLE: u8 = 0x40;
+
+/// Flags of composite glyph components.
+const ARG_1_AND_2_ARE_WORDS: u16 = 0x0001;
+const WE_HAVE_A_SCALE: u16 = 0x0008;
+const MORE_COMPONENTS: u16 = 0x0020;
+const WE_HAVE_AN_X_AND_Y_SCALE: u16 = 0x0040;
+const WE_HAVE_A_TWO_BY_TWO: u16 = 0x0080;
+const WE_HAVE_INSTRUCTIONS: u16 = 0x0100;
+
+/// Reconstructs the glyf and loca tables from a transformed glyf table.
+///
+/// Also returns the minimum x coordinate of each glyph, which is needed to
+/// reconstruct a transformed hmtx table.
+fn reconstruct_glyf(data: &[u8]) -> Option<(Vec<u8>, Vec<u8>, Vec<i16>)> {
+    let mut r = Reader::new(data);
+    r.skip(2)?; // reserved
+    let options = r.u16()?;
+    let num_glyphs = usize::from(r.u16()?);
+    let index_format = r.u16()?;
+
+    let mut sizes = [0; 7];
+    for size in &mut sizes {
+        *size = r.u32()? as usize;
+    }
+
+    let mut contours = Reader::new(r.take(sizes[0])?);
+    let mut points = Reader::new(r.take(sizes[1])?);
+    let mut flags = Reader::new(r.take(sizes[2])?);
+    let mut glyphs = Reader::new(r.take(sizes[3])?);
+    let mut composites = Reader::new(r.take(sizes[4])?);
+    let bbox_stream = r.take(sizes[5])?;
+    let mut instructions = Reader::new(r.take(sizes[6])?);
+    let overlaps = match options & 1 {
+        0 => None,
+        _ => Some(r.take(num_glyphs.div_ceil(8))?),
+    };
+
+    let (bbox_bitmap, bboxes) =
+        bbox_stream.split_at_checked(4 * num_glyphs.div_ceil(32))?;
+    let mut bboxes = Reader::new(bboxes);
+
+    let mut glyf = Vec::new();
+    let mut offsets = Vec::with_capacity(num_glyphs + 1);
+    let mut x_mins = vec![0; num_glyphs];
+    let mut outline = Vec::new();
+
+    for (i, x_min) in x_mins.iter_mut().enumerate() {
+        offsets.push(glyf.len());
+
+        let explicit_bbox = bit(bbox_bitmap, i);
+        let num_contours = contours.i16()?;
+        if num_contours == 0 {
+            // Empty glyphs have no data at all.
+            if explicit_bbox {
+                return None;
+            }
+            continue;
+        }
+
+        let mut read_bbox =
+            || Some([bboxes.i16()?, bboxes.i16()?, bboxes.i16()?, bboxes.i16()?]);
+        let bbox;
+        if num_contours > 0 {
+            // A simple glyph, whose points are encoded as triplets.
+            let mut end_points = Vec::with_capacity(num_contours as usize);
+            let mut count = 0u16;
+            for _ in 0..num_contours {
+                count = count.checked_add(points.u255()?)?;
+                end_points.push(count.checked_sub(1)?);
+            }
+
+            outline.clear();
+            let (mut x, mut y) = (0i32, 0i32);
+            for _ in 0..count {
+                let flag = flags.u8()?;
+                let (dx, dy) = triplet(flag & 0x7f, &mut glyphs)?;
+                x += dx;
+                y += dy;
+                outline.push((
+                    i16::try_from(x).ok()?,
+                    i16::try_from(y).ok()?,
+                    flag >> 7 == 0,
+                ));
+            }
+
+            let code = instructions.take(usize::from(glyphs.u255()?))?;
+            bbox = match explicit_bbox {
+                true => read_bbox()?,
+                false => compute_bbox(&outline),
+            };
+
+            write_glyph_header(&mut glyf, num_contours, bbox);
+            for end in end_points {
+                glyf.extend(end.to_be_bytes());
+            }
+            glyf.extend((code.len() as u16).to_be_bytes());
+            glyf.extend(code);
+            write_outline(&mut glyf, &outline, overlaps.is_some_and(|o| bit(o, i)));
+        } else {
+            // A composite glyph, whose components are stored verbatim.
+            if !explicit_bbox {
+                return None;
+            }
+
+            let start = composites.offset;
+            let mut has_instructions = false;
+            loop {
+                let flags = composites.u16()?;
+                let mut size = 2 + if flags & ARG_1_AND_2_ARE_WORDS != 0 { 4 } else { 2 };
+                if flags & WE_HAVE_A_SCALE != 0 {
+                    size += 2;
+                } else if flags & WE_HAVE_AN_X_AND_Y_SCALE != 0 {
+                    size += 4;
+                } else if flags & WE_HAVE_A_TWO_BY_TWO != 0 {
+                    size += 8;
+                }
+                composites.skip(size)?;
+                has_instructions |= flags & WE_HAVE_INSTRUCTIONS != 0;
+                if flags & MORE_COMPONENTS == 0 {
+                    break;
+                }
+            }
+
+            bbox = read_bbox()?;
+            write_glyph_header(&mut glyf, num_contours, bbox);
+            glyf.extend(&composites.data[start..composites.offset]);
+            if has_instructions {
+                let code = instructions.take(usize::from(glyphs.u255()?))?;
+                glyf.extend((code.len() as u16).to_be_bytes());
+                glyf.extend(code);
+            }
+        }
+
+        *x_min = bbox[0];
+        glyf.resize(glyf.len().next_multiple_of(4), 0);
+        if glyf.len() > MAX_SIZE {
+            return None;
+        }
+    }
+
+    offsets.push(glyf.len());
+
+    let mut loca = Vec::new();
+    for offset in offsets {
+        if index_format == 0 {
+            loca.extend(u16::try_from(offset / 2).ok()?.to_be_bytes());
+        } else {
+            loca.extend(u32::try_from(offset).ok()?.to_be_bytes());
+        }
+    }
+
+    Some((glyf, loca, x_mins))
+}
+
+/// Decodes the delta to the next point of a simple glyph.
+fn triplet(flag: u8, r: &mut Reader) -> Option<(i32, i32)> {
+    let sign = |flag: u8, value: i32| if flag & 1 != 0 { value } else { -value };
+    let f = i32::from(flag);
+    Some(match flag {
+        0..10 => {
+            let b0 = i32::from(r.u8()?);
+            (0, sign(flag, ((f & 14) << 7) + b0))
+        }
+        10..20 => {
+            let b0 = i32::from(r.u8()?);
+            (sign(flag, (((f - 10) & 14) << 7) + b0), 0)
+        }
+        20..84 => {
+            let b0 = f - 20;
+            let b1 = i32::from(r.u8()?);
+            (
+                sign(flag, 1 + (b0 & 0x30) + (b1 >> 4)),
+                sign(flag >> 1, 1 + ((b0 & 0x0c) << 2) + (b1 & 0x0f)),
+            )
+        }
+        84..120 => {
+            let b0 = f - 84;
+            let [b1, b2] = r.array()?.map(i32::from);
+            (
+                sign(flag, 1 + ((b0 / 12) << 8) + b1),
+                sign(flag >> 1, 1 + (((b0 % 12) >> 2) << 8) + b2),
+            )
+        }
+        120..124 => {
+            let [b0, b1, b2] = r.array()?.map(i32::from);
+            (sign(flag, (b0 << 4) + (b1 >> 4)), sign(flag >> 1, ((b1 & 0x0f) << 8) + b2))
+        }
+        _ => {
+            let [b0, b1, b2, b3] = r.array()?.map(i32::from);
+            (sign(flag, (b0 << 8) + b1), sign(flag >> 1, (b2 << 8) + b3))
+        }
+    })
+}
+
+/// Computes the bounding box of a glyph's points.
+fn compute_bbox(outline: &[(i16, i16, bool)]) -> [i16; 4] {
+    let Some(&(x, y, _)) = outline.first() else { return [0; 4] };
+    outline
+        .iter()
+        .fold([x, y, x, y], |[x_min, y_min, x_max, y_max], &(x, y, _)| {
+            [x_min.min(x), y_min.min(y), x_max.max(x), y_max.max(y)]
+        })
+}
+
+/// Writes the header of a glyph.
+fn write_glyph_header(glyf: &mut Vec<u8>, num_contours: i16, bbox: [i16; 4]) {
+    glyf.extend(num_contours.to_be_bytes());
+    for value in bbox {
+        glyf.extend(value.to_be_bytes());
+    }
+}
+
+/// Writes the flags and coordinates of a simple glyph.
+fn write_outline(glyf: &mut Vec<u8>, outline: &[(i16, i16, bool)], overlap: bool) {
+    let mut flags = Vec::with_capacity(outline.len());
+    let mut xs = Vec::new();
+    let mut ys = Vec::new();
+    let (mut prev_x, mut prev_y) = (0, 0);
+    for (i, &(x, y, on_curve)) in outline.iter().enumerate() {
+        let mut flag = if on_curve { ON_CURVE_POINT } else { 0 };
+        if overlap && i == 0 {
+            flag |= OVERLAP_SIMPLE;
+        }
+        flag |= write_delta(
+            &mut xs,
+            x.wrapping_sub(prev_x),
+            X_SHORT_VECTOR,
+            X_IS_SAME_OR_POSITIVE,
+        );
+        flag |= write_delta(
+            &mut ys,
+            y.wrapping_sub(prev_y),
+            Y_SHORT_VECTOR,
+            Y_IS_SAME_OR_POSITIVE,
+        );
+        flags.push(flag);
+        (prev_x, prev_y) = (x, y);
+    }
+
+    // Runs of equal flags are written once with a repeat count.
+    let mut i = 0;
+    while i < flags.len() {
+        let flag = flags[i];
+        let run = flags[i + 1..].iter().take(255).take_while(|&&f| f == flag).count();
+        if run > 0 {
+            glyf.extend([flag | REPEAT_FLAG, run as u8]);
+        } else {
+            glyf.push(flag);
+        }
+        i += 1 + run;
+    }
+
+    glyf.extend(xs);
+    glyf.extend(ys);
+}
+
+/// Writes a coordinate delta in its most compact form and returns the flags
+/// describing that form.
+fn write_delta(out: &mut Vec<u8>, delta: i16, short: u8, same_or_positive: u8) -> u8 {
+    if delta == 0 {
+        same_or_positive
+    } else if let Ok(magnitude) = u8::try_from(delta.unsigned_abs()) {
+        out.push(magnitude);
+        if delta > 0 { short | same_or_positive } else { short }
+    } else {
+        out.extend(delta.to_be_bytes());
+        0
+    }
+}
+
+/// Reconstructs the hmtx table from a transformed hmtx table.
+///
+/// The transform may omit left side bearings that are equal to the minimum x
+/// coordinate of a glyph.
+fn reconstruct_hmtx(data: &[u8], num_metrics: u16, x_mins: &[i16]) -> Option<Vec<u8>> {
+    let num_metrics = usize::from(num_metrics);
+    if num_metrics == 0 || num_metrics > x_mins.len() {
+        return None;
+    }
+
+    let mut r = Reader::new(data);
+    let flags = r.u8()?;
+    let advances = (0..num_metrics).map(|_| r.u16()).collect::<Option<Vec<_>>>()?;
+
+    let mut bearings = Vec::with_capacity(x_mins.len());
+    for (i, &x_min) in x_mins.iter().enumerate() {
+        let derived = if i < num_metrics { flags & 1 != 0 } else { flags & 2 != 0 };
+        bearings.push(if derived { x_min } else { r.i16()? });
+    }
+
+    let mut hmtx = Vec::with_capacity(2 * (num_metrics + x_mins.len()));
+    for (i, bearing) in bearings.into_iter().enumerate() {
+        if let Some(advance) = advances.get(i) {
+            hmtx.extend(advance.to_be_bytes());
+        }
+        hmtx.extend(bearing.to_be_bytes());
+    }
+
+    Some(hmtx)
+}
+
+/// Assembles an OpenType font or, if a collection version is given, a font
+/// collection.
+///
+/// Each font is given by its flavor and the indices of its tables.
+fn assemble(
+    collection: Option<u32>,
+    fonts: &[(u32, Vec<usize>)],
+    mut tables: Vec<Table>,
+) -> Vec<u8> {
+    // The checksum adjustment in the head table must be zero while computing
+    // checksums.
+    for table in &mut tables {
+        if &table.tag == b"head"
+            && let Some(adjustment) = table.data.get_mut(8..12)
+        {
+            adjustment.fill(0);
+        }
+    }
+
+    let mut header_len = match collection {
+        Some(version) => {
+            12 + 4 * fonts.len() + if version >= 0x0002_0000 { 12 } else { 0 }
+        }
+        None => 0,
+    };
+
+    let mut directories = Vec::with_capacity(fonts.len());
+    for (_, indices) in fonts {
+        directories.push(header_len);
+        header_len += 12 + 16 * indices.len();
+    }
+
+    let mut offsets = Vec::with_capacity(tables.len());
+    let mut len = header_len;
+    for table in &tables {
+        offsets.push(len);
+        len += table.data.len().next_multiple_of(4);
+    }
+
+    let mut out = Vec::with_capacity(len);
+    if let Some(version) = collection {
+        out.extend(TTCF.to_be_bytes());
+        out.extend(version.to_be_bytes());
+        out.extend((fonts.len() as u32).to_be_bytes());
+        for &offset in &directories {
+            out.extend((offset as u32).to_be_bytes());
+        }
+        if version >= 0x0002_0000 {
+            // No digital signature.
+            out.extend([0; 12]);
+        }
+    }
+
+    for (flavor, indices) in fonts {
+        let mut indices = indices.clone();
+        indices.sort_by_key(|&i| tables[i].tag);
+
+        let num_tables = indices.len() as u16;
+        let selector = num_tables.checked_ilog2().unwrap_or(0) as u16;
+        let range = 16u16 << selector;
+        out.extend(flavor.to_be_bytes());
+        out.extend(num_tables.to_be_bytes());
+        out.extend(range.to_be_bytes());
+        out.extend(selector.to_be_bytes());
+        out.extend(num_tables.wrapping_mul(16).wrapping_sub(range).to_be_bytes());
+
+        for i in indices {
+            let table = &tables[i];
+            out.extend(table.tag);
+            out.extend(checksum(&table.data).to_be_bytes());
+            out.extend((offsets[i] as u32).to_be_bytes());
+            out.extend((table.data.len() as u32).to_be_bytes());
+        }
+    }
+
+    for table in &tables {
+        out.extend(&table.data);
+        out.resize(out.len().next_multiple_of(4), 0);
+    }
+
+    // The checksum adjustment of a single font accounts for the whole file.
+    if collection.is_none()
+        && let Some(i) = tables.iter().position(|table| &table.tag == b"head")
+        && tables[i].data.len() >= 12
+    {
+        let adjustment = 0xB1B0AFBA_u32.wrapping_sub(checksum(&out));
+        out[offsets[i] + 8..offsets[i] + 12].copy_from_slice(&adjustment.to_be_bytes());
+    }
+
+    out
+}
+
+/// Computes the checksum of a table.
+fn checksum(data: &[u8]) -> u32 {
+    data.chunks(4).fold(0, |sum, chunk| {
+        let mut word = [0; 4];
+        word[..chunk.len()].copy_from_slice(chunk);
+        sum.wrapping_add(u32::from_be_bytes(word))
+    })
+}
+
+/// Whether the bit for the given index is set in a bitmap.
+fn bit(bitmap: &[u8], i: usize) -> bool {
+    bitmap.get(i / 8).is_some_and(|byte| byte & (0x80 >> (i % 8)) != 0)
+}
+
+/// Reads big-endian values from binary data.
+struct Reader<'a> {
+    data: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Self { data, offset: 0 }
+    }
+
+    fn take(&mut self, len: usize) -> Option<&'a [u8]> {
+        let slice = self.data.get(self.offset..self.offset.checked_add(len)?)?;
+        self.offset += len;
+        Some(slice)
+    }
+
+    fn skip(&mut self, len: usize) -> Option<()> {
+        self.take(len).map(drop)
+    }
+
+    fn array<const N: usize>(&mut self) -> Option<[u8; N]> {
+        self.take(N)?.try_into().ok()
+    }
+
+    fn tag(&mut self) -> Option<Tag> {
+        self.array()
+    }
+
+    fn u8(&mut self) -> Option<u8> {
+        self.array().map(u8::from_be_bytes)
+    }
+
+    fn u16(&mut self) -> Option<u16> {
+        self.array().map(u16::from_be_bytes)
+    }
+
+    fn i16(&mut self) -> Option<i16> {
+        self.array().map(i16::from_be_bytes)
+    }
+
+    fn u32(&mut self) -> Option<u32> {
+        self.array().map(u32::from_be_bytes)
+    }
+
+    /// Reads a variable-length `255UInt16`.
+    fn u255(&mut self) -> Option<u16> {
+        Some(match self.u8()? {
+            253 => self.u16()?,
+            254 => 506 + u16::from(self.u8()?),
+            255 => 253 + u16::from(self.u8()?),
+            code => code.into(),
+        })
+    }
+
+    /// Reads a variable-length `UIntBase128`.
+    fn base128(&mut self) -> Option<u32> {
+        let mut value = 0u32;
+        for i in 0..5 {
+            let byte = self.u8()?;
+            // Leading zeros and overflows are invalid.
+            if (i == 0 && byte == 0x80) || value >> 25 != 0 {
+                return None;
+            }
+            value = (value << 7) | u32::from(byte & 0x7f);
+            if byte & 0x80 == 0 {
+                return Some(value);
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Source Code Pro Regular as a WOFF font.
+    const WOFF_FONT: &[u8] =
+        include_bytes!("../../../../../tests/fonts/SourceCodePro-Regular.woff");
+
+    /// Source Code Pro Italic as a WOFF2 font with transformed glyf and loca
+    /// tables.
+    const WOFF2_FONT: &[u8] =
+        include_bytes!("../../../../../tests/fonts/SourceCodePro-It.woff2");
+
+    #[test]
+    fn test_woff_decode() {
+        let decoded = decode(WOFF_FONT).unwrap();
+        assert_eq!(decoded.len(), sfnt_size(WOFF_FONT));
+        assert_eq!(checksum(&decoded), 0xB1B0AFBA);
+
+        // The decoded tables match the checksums of the original tables.
+        let tables = tables(&decoded);
+        let mut r = Reader::new(WOFF_FONT);
+        r.skip(12).unwrap();
+        let num_tables = r.u16().unwrap();
+        r.skip(30).unwrap();
+        assert_eq!(tables.len(), usize::from(num_tables));
+        for &(tag, data) in &tables {
+            assert_eq!(r.tag(), Some(tag));
+            r.skip(12).unwrap();
+            assert_eq!(r.u32(), Some(table_checksum(tag, data)));
+        }
+    }
+
+    #[test]
+    fn test_woff2_decode() {
+        let decoded = decode(WOFF2_FONT).unwrap();
+        assert_eq!(decoded.len(), sfnt_size(WOFF2_FONT));
+        assert_eq!(checksum(&decoded), 0xB1B0AFBA);
+
+        // The reconstructed loca table points into the reconstructed glyf
+        // table.
+        let tables = tables(&decoded);
+        let table = |tag: &Tag| tables.iter().find(|(t, _)| t == tag).unwrap().1;
+        let num_glyphs = usize::from(Reader::new(&table(b"maxp")[4..]).u16().unwrap());
+        let long = Reader::new(&table(b"head")[50..]).u16() == Some(1);
+        let mut loca = Reader::new(table(b"loca"));
+        let offsets: Vec<_> = std::iter::from_fn(|| match long {
+            true => loca.u32().map(|offset| offset as usize),
+            false => loca.u16().map(|offset| 2 * usize::from(offset)),
+        })
+        .collect();
+        assert_eq!(offsets.len(), num_glyphs + 1);
+        assert!(offsets.is_sorted());
+        assert_eq!(offsets.last(), Some(&table(b"glyf").len()));
+    }
+
+    #[test]
+    fn test_woff_size_mismatch() {
+        let with_size = |data: &[u8], size: usize| {
+            let mut changed = data.to_vec();
+            changed[16..20].copy_from_slice(&(size as u32).to_be_bytes());
+            changed
+        };
+
+        for data in [WOFF_FONT, WOFF2_FONT] {
+            // The file is longer than declared.
+            let mut longer = data.to_vec();
+            longer.push(0);
+            assert!(decode(&longer).is_none());
+
+            // The tables are larger than declared or than allowed.
+            assert!(decode(&with_size(data, 1)).is_none());
+            assert!(decode(&with_size(data, MAX_SIZE + 4)).is_none());
+        }
+
+        // The size of a WOFF font is known exactly.
+        assert!(decode(&with_size(WOFF_FONT, sfnt_size(WOFF_FONT) - 4)).is_none());
+        assert!(decode(&with_size(WOFF_FONT, sfnt_size(WOFF_FONT) + 4)).is_none());
+    }
+
+    #[test]
+    fn test_woff_truncated() {
+        for data in [WOFF_FONT, WOFF2_FONT] {
+            for len in 0..data.len() {
+                assert!(decode(&data[..len]).is_none(), "truncated to {len} bytes");
+            }
+        }
+    }
+
+    #[test]
+    fn test_woff_malformed() {
+        assert_eq!(decode(b""), None);
+        assert_eq!(decode(b"wOFF"), None);
+        assert_eq!(decode(b"wOF2"), None);
+        assert_eq!(decode(b"OTTO"), None);
+
+        // Corrupting a single byte may or may not result in a valid font, but
+        // must not panic. The header fields are also set to extreme values.
+        // The bytes at the start, which hold the table directories, are all
+        // corrupted, those in the table data only sporadically.
+        for data in [WOFF_FONT, WOFF2_FONT] {
+            let extreme = (0..48).flat_map(|i| [(i, 0x00), (i, 0xff)]);
+            let flipped =
+                (0..128).chain((128..data.len()).step_by(4999)).map(|i| (i, !data[i]));
+            for (i, value) in extreme.chain(flipped) {
+                let mut corrupted = data.to_vec();
+                corrupted[i] = value;
+                decode(&corrupted);
+            }
+        }
+    }
+
+    /// The size of the decoded font declared in the header.
+    fn sfnt_size(data: &[u8]) -> usize {
+        Reader::new(&data[16..]).u32().unwrap() as usize
+    }
+
+    /// The tables of an OpenType font in the order of its table directory.
+    fn tables(font: &[u8]) -> Vec<(Tag, &[u8])> {
+        let mut r = Reader::new(font);
+        r.skip(4).unwrap();
+        let num_tables = r.u16().unwrap();
+        r.skip(6).unwrap();
+        (0..num_tables)
+            .map(|_| {
+                let tag = r.tag().unwrap();
+                r.skip(4).unwrap();
+                let offset = r.u32().unwrap() as usize;
+                let len = r.u32().unwrap() as usize;
+                (tag, &font[offset..offset + len])
+            })
+            .collect()
+    }
+
+    /// Computes the checksum of a table, which for the head table excludes
+    /// the checksum adjustment.
+    fn table_checksum(tag: Tag, data: &[u8]) -> u32 {
+        let mut data = data.to_vec();
+        if &tag == b"head" {
+            data[8..12].fill(0);
+        }
+        checksum(&data)
+    }
+}
@@ -2,6 +2,7 @@
 
 mod case;
 mod deco;
+mod dropcap;
 mod font;
 mod item;
 mod lang;
@@ -17,6 +18,7 @@ mod space;
 
 pub use self::case::*;
 pub use self::deco::*;
+pub use self::dropcap::*;
 pub use self::font::*;
 pub use self::item::*;
 pub use self::lang::*;
@@ -69,6 +71,7 @@ pub(super) fn define(global: &mut Scope) {
     global.define_elem::<StrikeElem>();
     global.define_elem::<HighlightElem>();
     global.define_elem::<SmallcapsElem>();
+    global.define_elem::<DropcapElem>();
     global.define_elem::<RawElem>();
     global.define_func::<lower>();
     global.define_func::<upper>();
@@ -190,6 +193,53 @@ pub struct TextElem {
     #[ghost]
     pub fallback: bool,
 
+    /// Font families to try for text in specific scripts or languages when
+    /// the primary font list contains no match.
+    ///
+    /// This is a dictionary that maps keys to font lists of the same form as
+    /// the [`font`]($text.font) parameter. A key can be:
+    ///
+    /// - A four letter [ISO 15924 script code](https://unicode.org/iso15924/),
+    ///   like `{"hani"}` for Han or `{"arab"}` for Arabic text.
+    /// - A [language code]($text.lang), like `{"ja"}`.
+    /// - A language code followed by a hyphen and a [region code]($text.region),
+    ///   like `{"zh-TW"}`.
+    ///
+    /// For a piece of text, Typst first tries the font list for the language
+    /// and region, then the one for just the language, and then the one for
+    /// the text's script. Only if none of them has the necessary glyphs, it
+    /// resorts to the automatic [fallback]($text.fallback). This makes the
+    /// choice of fonts for mixed-script documents predictable across machines.
+    ///
+    /// Settings for different keys accumulate across set rules, while a later
+    /// setting for the same key replaces the earlier one.
+    ///
+    /// ```example
+    /// #set text(
+    ///   font: "Libertinus Serif",
+    ///   fallback-fonts: (
+    ///     hani: "Noto Serif CJK SC",
+    ///     ja: "Noto Serif CJK JP",
+    ///   ),
+    /// )
+    ///
+    /// Chinese: 骨 \
+    /// #text(lang: "ja")[Japanese: 骨]
+    /// ```
+    #[fold]
+    #[ghost]
+    pub fallback_fonts: FallbackFonts,
+
+    /// Whether to warn about characters that are not covered by the primary
+    /// font list and are thus shaped with a fallback font.
+    ///
+    /// The warnings name the fallback font that was chosen for each piece of
+    /// text. This helps to find text for which a font should be configured
+    /// explicitly, e.g. through [`fallback-fonts`]($text.fallback-fonts).
+    #[default(false)]
+    #[ghost]
+    pub warn_fallback: bool,
+
     /// The desired font style.
     ///
     /// When an italic style is requested and only an oblique one is available,
@@ -974,8 +1024,129 @@ cast! {
     values: Array => Self::new(values.into_iter().map(|v| v.cast()).collect::<HintedStrResult<_>>()?)?,
 }
 
+/// Font families to try for text in specific scripts or languages.
+///
+/// Entries are kept in the order in which they were defined.
+#[derive(Debug, Default, Clone, PartialEq, Hash)]
+pub struct FallbackFonts(pub Vec<(FallbackTarget, FontList)>);
+
+impl FallbackFonts {
+    /// The font list configured for exactly this target, if any.
+    pub fn get(&self, target: FallbackTarget) -> Option<&FontList> {
+        self.0.iter().find(|(t, _)| *t == target).map(|(_, list)| list)
+    }
+
+    /// Resolve a prioritized iterator over the font families configured for
+    /// text in the given language, region, and script.
+    pub fn select(
+        &self,
+        lang: Lang,
+        region: Option<Region>,
+        script: Option<WritingScript>,
+    ) -> impl Iterator<Item = &FontFamily> + Clone {
+        let regional = region.map(|region| FallbackTarget::Lang(lang, Some(region)));
+        [
+            regional,
+            Some(FallbackTarget::Lang(lang, None)),
+            script.map(FallbackTarget::Script),
+        ]
+        .into_iter()
+        .flatten()
+        .filter_map(|target| self.get(target))
+        .flatten()
+    }
+}
+
+impl Fold for FallbackFonts {
+    fn fold(self, outer: Self) -> Self {
+        let mut entries = self.0;
+        for (target, list) in outer.0 {
+            if !entries.iter().any(|(t, _)| *t == target) {
+                entries.push((target, list));
+            }
+        }
+        Self(entries)
+    }
+}
+
+cast! {
+    FallbackFonts,
+    self => self.0
+        .into_iter()
+        .map(|(target, list)| (target.to_string().into(), list.into_value()))
+        .collect::<Dict>()
+        .into_value(),
+    values: Dict => Self(values
+        .into_iter()
+        .map(|(key, value)| Ok((
+            key.parse::<FallbackTarget>()?,
+            value.cast::<FontList>().hint(eco_format!("occurred in key `{key}`"))?,
+        )))
+        .collect::<HintedStrResult<_>>()?),
+}
+
+/// The text to which a list of [fallback fonts](FallbackFonts) applies.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum FallbackTarget {
+    /// Text in a writing script.
+    Script(WritingScript),
+    /// Text in a language, optionally restricted to a region.
+    Lang(Lang, Option<Region>),
+}
+
+impl FromStr for FallbackTarget {
+    type Err = EcoString;
+
+    /// Parses a four letter script code or a language code with an optional
+    /// region, separated by a hyphen.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.len() == 4 && s.chars().all(|c| c.is_ascii_alphabetic()) {
+            return Ok(Self::Script(s.parse()?));
+        }
+
+        let (lang, region) = match s.split_once('-') {
+            Some((lang, region)) => (lang, Some(region)),
+            None => (s, None),
+        };
+        let invalid = |_| {
+            eco_format!(
+                "invalid fallback font key `{s}` (expected a script code like \
+                 `hani` or a language code like `ja` or `zh-TW`)"
+            )
+        };
+        let lang = lang.parse().map_err(invalid)?;
+        let region = region.map(str::parse).transpose().map_err(invalid)?;
+        Ok(Self::Lang(lang, region))
+    }
+}
+
+impl fmt::Display for FallbackTarget {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Self::Script(script) => f.write_str(script.as_str()),
+            Self::Lang(lang, None) => f.write_str(lang.as_str()),
+            Self::Lang(lang, Some(region)) => {
+                write!(f, "{}-{}", lang.as_str(), region.as_str())
+            }
+        }
+    }
+}
+
 /// Resolve a prioritized iterator over the font families.
 pub fn families(styles: StyleChain<'_>) -> impl Iterator<Item = &'_ FontFamily> + Clone {
+    families_for(styles, None)
+}
+
+/// Resolve a prioritized iterator over the font families for text in the
+/// given script.
+///
+/// Between the primary font list and the default fallback families, this
+/// yields the configured [fallback fonts](TextElem::fallback_fonts) for the
+/// text's language, region, and script.
+pub fn families_for(
+    styles: StyleChain<'_>,
+    script: Option<WritingScript>,
+) -> impl Iterator<Item = &'_ FontFamily> + Clone {
     let fallbacks = singleton!(Vec<FontFamily>, {
         [
             "libertinus serif",
@@ -989,8 +1160,18 @@ pub fn families(styles: StyleChain<'_>) -> impl Iterator<Item = &'_ FontFamily>
         .collect()
     });
 
+    let configured = styles.get_ref(TextElem::fallback_fonts).select(
+        styles.get(TextElem::lang),
+        styles.get(TextElem::region),
+        script,
+    );
+
     let tail = if styles.get(TextElem::fallback) { fallbacks.as_slice() } else { &[] };
-    styles.get_ref(TextElem::font).into_iter().chain(tail.iter())
+    styles
+        .get_ref(TextElem::font)
+        .into_iter()
+        .chain(configured)
+        .chain(tail.iter())
 }
 
 /// Resolve the font variant.
@@ -13,7 +13,7 @@ use bumpalo::collections::{CollectIn, String as BumpString, Vec as BumpVec};
 use comemo::Track;
 use ecow::EcoString;
 use typst_library::diag::{At, SourceResult, bail, warning};
-use typst_library::engine::Engine;
+use typst_library::engine::{Budget, Engine};
 use typst_library::foundations::{
     Content, Context, ContextElem, Element, NativeElement, NativeShowRule, Packed,
     Recipe, RecipeIndex, Selector, SequenceElem, ShowSet, Style, StyleChain, StyledElem,
@@ -31,7 +31,9 @@ use typst_library::model::{
     ParElem, ParbreakElem, TermsElem,
 };
 use typst_library::routines::{Arenas, FragmentKind, Pair, RealizationKind};
-use typst_library::text::{LinebreakElem, SmartQuoteElem, SpaceElem, TextElem};
+use typst_library::text::{
+    DropcapElem, LinebreakElem, SmartQuoteElem, SpaceElem, TextElem,
+};
 use typst_syntax::Span;
 use typst_utils::{ListSet, SliceExt, SmallBitSet};
 
@@ -49,6 +51,7 @@ pub fn realize<'a>(
     styles: StyleChain<'a>,
 ) -> SourceResult<Vec<Pair<'a>>> {
     let mut s = State {
+        budget: engine.traced.budget(),
         engine,
         locator,
         arenas,
@@ -89,6 +92,9 @@ struct State<'a, 'x, 'y, 'z> {
     kind: RealizationKind<'x>,
     /// The engine.
     engine: &'x mut Engine<'y>,
+    /// The resource budget of the compilation, used to stop realization early
+    /// when the compilation is cancelled.
+    budget: Budget,
     /// Assigns unique locations to elements.
     locator: &'x mut SplitLocator<'z>,
     /// Temporary storage arenas for lifetime extension during realization.
@@ -231,6 +237,9 @@ fn visit<'a>(
         return Ok(());
     }
 
+    // Stop if the compilation was cancelled or ran out of time.
+    s.budget.poll(s.engine.traced).at(content.span())?;
+
     // Transformations for content based on the realization kind. Needs
     // to happen before show rules.
     if visit_kind_rules(s, content, styles)? {
@@ -358,6 +367,10 @@ fn visit_show_rules<'a>(
         let result = match step {
             // Apply a user-defined show rule.
             ShowStep::Recipe(recipe, guard) => {
+                let _scope = typst_timing::TimingScope::with_span(
+                    "show rule",
+                    Some(recipe.span().into_raw()),
+                );
                 let context = Context::new(output.location(), Some(chained));
                 recipe.apply(
                     s.engine,
@@ -368,7 +381,10 @@ fn visit_show_rules<'a>(
 
             // Apply a built-in show rule.
             ShowStep::Builtin(rule) => {
-                let _scope = typst_timing::TimingScope::new(output.elem().name());
+                let _scope = typst_timing::TimingScope::with_span(
+                    output.elem().name(),
+                    Some(output.span().into_raw()),
+                );
                 rule.apply(&output, s.engine, chained)
                     .map(|content| content.spanned(output.span()))
             }
@@ -960,6 +976,7 @@ static PAR: GroupingRule = GroupingRule {
             || elem == SmartQuoteElem::ELEM
             || elem == InlineElem::ELEM
             || elem == BoxElem::ELEM
+            || elem == DropcapElem::ELEM
             || match state.kind {
                 RealizationKind::HtmlDocument { is_phrasing, .. }
                 | RealizationKind::HtmlFragment { is_phrasing, .. } => {
@@ -1833,6 +1833,14 @@ impl<'a> Closure<'a> {
         self.0.cast_first()
     }
 
+    /// The annotated return type: `let f(x) as int = ..`.
+    pub fn returns(self) -> Option<Expr<'a>> {
+        self.0
+            .children()
+            .skip_while(|node| node.kind() != SyntaxKind::As)
+            .find_map(SyntaxNode::cast)
+    }
+
     /// The body of the closure.
     pub fn body(self) -> Expr<'a> {
         self.0.cast_last()
@@ -1849,6 +1857,33 @@ impl<'a> Params<'a> {
     pub fn children(self) -> impl DoubleEndedIterator<Item = Param<'a>> {
         self.0.children().filter_map(SyntaxNode::cast)
     }
+
+    /// The parameter bindings along with their type annotations.
+    pub fn annotated(
+        self,
+    ) -> impl DoubleEndedIterator<Item = (Param<'a>, Option<Expr<'a>>)> {
+        self.0.children().filter_map(|node| match node.cast::<TypedParam>() {
+            Some(typed) => Some((typed.param(), Some(typed.ty()))),
+            Option::None => Some((node.cast()?, Option::None)),
+        })
+    }
+}
+
+node! {
+    /// A closure parameter with a type annotation: `x as int`.
+    struct TypedParam
+}
+
+impl<'a> TypedParam<'a> {
+    /// The annotated parameter.
+    pub fn param(self) -> Param<'a> {
+        self.0.cast_first()
+    }
+
+    /// The annotated type.
+    pub fn ty(self) -> Expr<'a> {
+        self.0.cast_last()
+    }
 }
 
 /// A parameter to a closure.
@@ -1865,6 +1900,7 @@ pub enum Param<'a> {
 impl<'a> AstNode<'a> for Param<'a> {
     fn from_untyped(node: &'a SyntaxNode) -> Option<Self> {
         match node.kind() {
+            SyntaxKind::TypedParam => node.children().find_map(SyntaxNode::cast),
             SyntaxKind::Named => Some(Self::Named(Named(node))),
             SyntaxKind::Spread => Some(Self::Spread(Spread(node))),
             _ => node.cast().map(Self::Pos),
@@ -1925,6 +1961,12 @@ impl<'a> Pattern<'a> {
     }
 }
 
+impl Default for Param<'_> {
+    fn default() -> Self {
+        Self::Pos(Pattern::default())
+    }
+}
+
 impl Default for Pattern<'_> {
     fn default() -> Self {
         Self::Normal(Expr::default())
@@ -2403,4 +2445,28 @@ mod tests {
     fn test_expr_default() {
         assert!(Expr::default().to_untyped().cast::<Expr>().is_some());
     }
+
+    #[test]
+    fn test_typed_params() {
+        let root = crate::parse_code("let f(x as int, y: 1 as (float, none)) as str = x");
+        let Expr::LetBinding(binding) = root.cast_first::<Expr>() else {
+            panic!("expected let binding");
+        };
+        let LetBindingKind::Closure(_) = binding.kind() else {
+            panic!("expected closure");
+        };
+        let Some(Expr::Closure(closure)) = binding.init() else {
+            panic!("expected closure");
+        };
+
+        let params: Vec<_> = closure.params().annotated().collect();
+        assert_eq!(params.len(), 2);
+        assert!(matches!(params[0], (Param::Pos(_), Some(Expr::Ident(_)))));
+        assert!(matches!(params[1], (Param::Named(_), Some(Expr::Array(_)))));
+        assert_eq!(closure.params().children().count(), 2);
+        assert!(
+            matches!(closure.returns(), Some(Expr::Ident(ty)) if ty.as_str() == "str")
+        );
+        assert!(matches!(closure.body(), Expr::Ident(x) if x.as_str() == "x"));
+    }
 }
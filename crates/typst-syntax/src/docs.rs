@@ -0,0 +1,243 @@
+use ecow::EcoString;
+
+use crate::{LinkedNode, SyntaxKind, SyntaxNode, is_ident, is_newline};
+
+/// Documentation attached to a let binding through `///` comments.
+///
+/// The comments directly precede the binding. Lines of the form
+/// `- name (type, ..): description` document a parameter, with the types
+/// being optional, and a line of the form `-> type` documents the return
+/// type. All other lines make up the description.
+///
+/// ```typ
+/// /// Draws a framed box.
+/// ///
+/// /// - width (length): How wide the box is.
+/// /// - body (content): What to put into the box.
+/// /// -> content
+/// #let frame(width: 1cm, body) = box(width: width, stroke: 1pt, body)
+/// ```
+#[derive(Debug, Default, Clone, Eq, PartialEq, Hash)]
+pub struct DocComment {
+    /// The description of the binding.
+    pub body: EcoString,
+    /// The documented parameters, in order.
+    pub params: Vec<ParamDoc>,
+    /// The documented return type.
+    pub returns: Option<EcoString>,
+}
+
+/// Documentation for a parameter of a function.
+#[derive(Debug, Default, Clone, Eq, PartialEq, Hash)]
+pub struct ParamDoc {
+    /// The name of the parameter.
+    pub name: EcoString,
+    /// The documented types of the parameter.
+    pub types: Vec<EcoString>,
+    /// The description of the parameter.
+    pub docs: EcoString,
+}
+
+impl DocComment {
+    /// Parses documentation from the text of a doc comment with the leading
+    /// slashes removed.
+    pub fn parse(text: &str) -> Self {
+        let mut body = String::new();
+        let mut params: Vec<ParamDoc> = vec![];
+        let mut returns = None;
+
+        // Whether indented lines continue the last parameter's description.
+        let mut in_param = false;
+
+        for line in text.lines() {
+            if let Some(param) = parse_param(line) {
+                params.push(param);
+                in_param = true;
+            } else if let Some(ty) = line.strip_prefix("->") {
+                returns = Some(ty.trim().into());
+                in_param = false;
+            } else if in_param
+                && line.starts_with(char::is_whitespace)
+                && !line.trim().is_empty()
+                && let Some(param) = params.last_mut()
+            {
+                param.docs.push('\n');
+                param.docs.push_str(line.trim());
+            } else {
+                in_param = false;
+                body.push_str(line);
+                body.push('\n');
+            }
+        }
+
+        Self { body: body.trim().into(), params, returns }
+    }
+
+    /// The documentation of the parameter with the given name.
+    pub fn param(&self, name: &str) -> Option<&ParamDoc> {
+        self.params.iter().find(|param| param.name == name)
+    }
+}
+
+/// Parses a line of the form `- name (type, ..): description`.
+fn parse_param(line: &str) -> Option<ParamDoc> {
+    let (head, docs) = line.strip_prefix("- ")?.split_once(':')?;
+    let (name, types) = match head.split_once('(') {
+        Some((name, types)) => (
+            name.trim(),
+            types
+                .trim()
+                .strip_suffix(')')?
+                .split(',')
+                .map(str::trim)
+                .filter(|ty| !ty.is_empty())
+                .map(Into::into)
+                .collect(),
+        ),
+        None => (head.trim(), vec![]),
+    };
+
+    if !is_ident(name) {
+        return None;
+    }
+
+    Some(ParamDoc { name: name.into(), types, docs: docs.trim().into() })
+}
+
+impl LinkedNode<'_> {
+    /// The documentation comment of this node if it is a let binding.
+    ///
+    /// The comment consists of the `///` line comments that directly precede
+    /// the binding, with no empty lines in between.
+    pub fn doc_comment(&self) -> Option<DocComment> {
+        if self.kind() != SyntaxKind::LetBinding {
+            return None;
+        }
+
+        let parent = self.parent()?;
+        let siblings = parent.get().children().as_slice();
+        let mut before: Vec<&SyntaxNode> =
+            siblings[..self.index()].iter().rev().collect();
+
+        // In markup, the binding is preceded by a hash.
+        if before.first().is_some_and(|node| node.kind() == SyntaxKind::Hash) {
+            before.remove(0);
+        }
+
+        // Trivia before the first statement of a code block is not part of the
+        // code node.
+        if parent.kind() == SyntaxKind::Code
+            && before.iter().all(|node| node.kind().is_trivia())
+            && let Some(outer) = parent.parent()
+        {
+            let siblings = outer.get().children().as_slice();
+            before.extend(siblings[..parent.index()].iter().rev());
+        }
+
+        let mut lines = vec![];
+        let mut after_comment = false;
+        for node in before {
+            let newlines = node.text().chars().filter(|&c| is_newline(c)).count();
+            match node.kind() {
+                SyntaxKind::Space if newlines == 1 => {
+                    after_comment = false;
+                    continue;
+                }
+                SyntaxKind::LineComment => {
+                    if let Some(line) = node.text().strip_prefix("///")
+                        && !line.starts_with('/')
+                    {
+                        lines.push(line.strip_prefix(' ').unwrap_or(line));
+                        after_comment = true;
+                        continue;
+                    }
+                }
+                _ => {}
+            }
+
+            // A comment that follows other code on the same line belongs to
+            // that code.
+            if after_comment && newlines == 0 {
+                lines.pop();
+            }
+
+            break;
+        }
+
+        if lines.is_empty() {
+            return None;
+        }
+
+        lines.reverse();
+        Some(DocComment::parse(&lines.join("\n")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Side, parse};
+
+    #[track_caller]
+    fn test(text: &str, body: Option<&str>) {
+        let root = parse(text);
+        let leaf = LinkedNode::new(&root)
+            .leaf_at(text.rfind("let").unwrap() + 1, Side::After)
+            .unwrap();
+        let docs = leaf.parent().unwrap().doc_comment();
+        assert_eq!(docs.map(|docs| docs.body).as_deref(), body);
+    }
+
+    #[test]
+    fn test_doc_comment_markup() {
+        test("/// Hello\n/// world\n#let x = 1", Some("Hello\nworld"));
+        test("Text\n\n///Unspaced\n#let f() = 1", Some("Unspaced"));
+    }
+
+    #[test]
+    fn test_doc_comment_code() {
+        test("#{\n  /// Hi\n  let x = 1\n}", Some("Hi"));
+        test("#{ let y = 2 /// Trailing\n /// Own\n let x = 1 }", Some("Own"));
+    }
+
+    #[test]
+    fn test_doc_comment_missing() {
+        test("#let x = 1", None);
+        test("// Plain\n#let x = 1", None);
+        test("/// Separated\n\n#let x = 1", None);
+        test("#{ let y = 2 /// Trailing\n let x = 1 }", None);
+        test("//// Ruler\n#let x = 1", None);
+    }
+
+    #[test]
+    fn test_doc_comment_params() {
+        let docs = DocComment::parse(
+            "Draws a box.\n\n- width (length, auto): The width.\n  In points.\n\
+             - body: The body.\n-> content",
+        );
+        assert_eq!(docs.body, "Draws a box.");
+        assert_eq!(docs.returns.as_deref(), Some("content"));
+        assert_eq!(
+            docs.params,
+            [
+                ParamDoc {
+                    name: "width".into(),
+                    types: vec!["length".into(), "auto".into()],
+                    docs: "The width.\nIn points.".into(),
+                },
+                ParamDoc {
+                    name: "body".into(),
+                    types: vec![],
+                    docs: "The body.".into()
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_doc_comment_list_in_body() {
+        let docs = DocComment::parse("Options:\n- first\n- two words: second");
+        assert_eq!(docs.body, "Options:\n- first\n- two words: second");
+        assert!(docs.params.is_empty());
+    }
+}
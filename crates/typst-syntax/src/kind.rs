@@ -250,6 +250,8 @@ pub enum SyntaxKind {
     Closure,
     /// A closure's parameters: `(x, y)`.
     Params,
+    /// A closure parameter with a type annotation: `x as int`.
+    TypedParam,
     /// A let binding: `let x = 1`.
     LetBinding,
     /// A set rule: `set text(...)`.
@@ -494,6 +496,7 @@ impl SyntaxKind {
             Self::Spread => "spread",
             Self::Closure => "closure",
             Self::Params => "closure parameters",
+            Self::TypedParam => "typed parameter",
             Self::LetBinding => "`let` expression",
             Self::SetRule => "`set` expression",
             Self::ShowRule => "`show` expression",
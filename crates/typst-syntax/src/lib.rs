@@ -3,6 +3,7 @@
 pub mod ast;
 pub mod package;
 
+mod docs;
 mod highlight;
 mod kind;
 mod lexer;
@@ -15,6 +16,7 @@ mod set;
 mod source;
 mod span;
 
+pub use self::docs::{DocComment, ParamDoc};
 pub use self::highlight::{Tag, highlight, highlight_html};
 pub use self::kind::SyntaxKind;
 pub use self::lexer::{
@@ -142,6 +142,77 @@ pub struct PackageInfo {
     pub unknown_fields: UnknownFields,
 }
 
+/// A parsed project manifest.
+///
+/// A project manifest is a `typst.toml` file that declares how the documents
+/// in a project are compiled. Its sections may also be part of a package
+/// manifest, in which case the package sections end up in `unknown_fields`.
+///
+/// The `unknown_fields` contains fields which were found but not expected.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProjectManifest {
+    /// Settings shared by all targets of the project.
+    #[serde(default)]
+    pub project: ProjectInfo,
+    /// The documents to build, from the `[[target]]` array of tables.
+    #[serde(default, rename = "target", skip_serializing_if = "Vec::is_empty")]
+    pub targets: Vec<TargetInfo>,
+    /// The tools section for third-party configuration.
+    #[serde(default)]
+    pub tool: ToolInfo,
+    /// All parsed but unknown fields, this can be used for validation.
+    #[serde(flatten, skip_serializing)]
+    pub unknown_fields: UnknownFields,
+}
+
+/// The `[project]` key in the project manifest.
+///
+/// Paths are relative to the directory containing the manifest.
+///
+/// The `unknown_fields` contains fields which were found but not expected.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct ProjectInfo {
+    /// The project root (for absolute paths).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub root: Option<EcoString>,
+    /// Additional directories that are recursively searched for fonts.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub font_paths: Vec<EcoString>,
+    /// String key-value pairs visible through `sys.inputs`.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub inputs: BTreeMap<EcoString, EcoString>,
+    /// In-development features to enable.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub features: Vec<EcoString>,
+    /// All parsed but unknown fields, this can be used for validation.
+    #[serde(flatten, skip_serializing)]
+    pub unknown_fields: UnknownFields,
+}
+
+/// An entry of the `[[target]]` array in the project manifest.
+///
+/// The `unknown_fields` contains fields which were found but not expected.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct TargetInfo {
+    /// The path of the Typst file to compile.
+    pub input: EcoString,
+    /// The path of the output file. Defaults to the input path with the
+    /// format's extension.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output: Option<EcoString>,
+    /// The output format, inferred from the output path by default.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub format: Option<EcoString>,
+    /// The PDF standards to enforce conformance with.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub pdf_standards: Vec<EcoString>,
+    /// All parsed but unknown fields, this can be used for validation.
+    #[serde(flatten, skip_serializing)]
+    pub unknown_fields: UnknownFields,
+}
+
 impl PackageManifest {
     /// Create a new package manifest with the given package info.
     pub fn new(package: PackageInfo) -> Self {
@@ -639,6 +710,39 @@ mod tests {
         assert_eq!(my_tool, MyTool { key: "value".into() });
     }
 
+    #[test]
+    fn project_manifest() {
+        let manifest: ProjectManifest = toml::from_str(
+            r#"
+            [project]
+            root = ".."
+            font-paths = ["fonts"]
+            inputs = { lang = "de" }
+
+            [[target]]
+            input = "main.typ"
+
+            [[target]]
+            input = "main.typ"
+            output = "build/main.png"
+            pdf-standards = ["a-2b"]
+
+            [package]
+            name = "package"
+        "#,
+        )
+        .unwrap();
+
+        assert_eq!(manifest.project.root.as_deref(), Some(".."));
+        assert_eq!(manifest.project.font_paths, ["fonts"]);
+        assert_eq!(manifest.project.inputs["lang"], "de");
+        assert_eq!(manifest.targets.len(), 2);
+        assert_eq!(manifest.targets[0].output, None);
+        assert_eq!(manifest.targets[1].output.as_deref(), Some("build/main.png"));
+        assert_eq!(manifest.targets[1].pdf_standards, ["a-2b"]);
+        assert!(manifest.unknown_fields.contains_key("package"));
+    }
+
     #[test]
     fn unknown_keys() {
         let manifest: PackageManifest = toml::from_str(
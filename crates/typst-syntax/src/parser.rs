@@ -823,6 +823,11 @@ fn let_binding(p: &mut Parser) {
         if p.directly_at(SyntaxKind::LeftParen) {
             params(p);
             closure = true;
+
+            // Parses a return type annotation: `let f(x) as int = ..`.
+            if p.eat_if(SyntaxKind::As) {
+                code_expr_prec(p, true, 0);
+            }
         }
     } else {
         pattern(p, false, &mut FxHashSet::default(), None);
@@ -1325,9 +1330,15 @@ fn param<'s>(p: &mut Parser<'s>, seen: &mut FxHashSet<&'s str>, sink: &mut bool)
         if p.at_set(set::PATTERN_LEAF) {
             pattern_leaf(p, false, seen, Some("parameter"));
         }
+        let annotated = p.eat_if(SyntaxKind::As);
+        if annotated {
+            code_expr_prec(p, true, 0);
+        }
         p.wrap(m, SyntaxKind::Spread);
         if mem::replace(sink, true) {
             p[m].convert_to_error("only one argument sink is allowed");
+        } else if annotated {
+            p[m].convert_to_error("argument sinks cannot have a type annotation");
         }
         return;
     }
@@ -1346,6 +1357,12 @@ fn param<'s>(p: &mut Parser<'s>, seen: &mut FxHashSet<&'s str>, sink: &mut bool)
         code_expr(p);
         p.wrap(m, SyntaxKind::Named);
     }
+
+    // Parses a type annotation: `x as int`.
+    if p.eat_if(SyntaxKind::As) {
+        code_expr_prec(p, true, 0);
+        p.wrap(m, SyntaxKind::TypedParam);
+    }
 }
 
 /// Parses a binding or reassignment pattern.
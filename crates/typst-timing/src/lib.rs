@@ -1,5 +1,6 @@
 //! Performance timing for Typst.
 
+use std::collections::HashMap;
 use std::io::Write;
 use std::num::NonZeroU64;
 use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
@@ -146,6 +147,93 @@ pub fn export_json<W: Write>(
     Ok(())
 }
 
+/// Aggregated timings of all recorded scopes with the same name and span.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProfileEntry {
+    /// The name of the scopes.
+    pub name: &'static str,
+    /// The raw value of the span the scopes were recorded with.
+    pub span: Option<NonZeroU64>,
+    /// How often a scope was entered.
+    pub calls: u64,
+    /// The time spent in the scopes, including nested scopes, in
+    /// microseconds.
+    ///
+    /// Scopes nested in a scope with the same name and span (e.g. recursive
+    /// function calls) are not counted again.
+    pub inclusive: f64,
+    /// The time spent in the scopes, excluding nested scopes, in
+    /// microseconds.
+    pub exclusive: f64,
+}
+
+/// Aggregates the recorded events into a profile.
+///
+/// The entries are sorted by exclusive time, in descending order. Scopes
+/// on different threads are tracked separately, so work that is spawned onto
+/// another thread counts towards the exclusive time of the spawning scope.
+pub fn profile() -> Vec<ProfileEntry> {
+    aggregate(&EVENTS.lock())
+}
+
+/// Aggregates a list of events into a profile.
+fn aggregate(events: &[Event]) -> Vec<ProfileEntry> {
+    /// A scope that has been entered, but not yet exited.
+    struct Open {
+        name: &'static str,
+        span: Option<NonZeroU64>,
+        start: Timestamp,
+        nested: f64,
+    }
+
+    let mut stacks: HashMap<u64, Vec<Open>> = HashMap::new();
+    let mut indices = HashMap::new();
+    let mut entries: Vec<ProfileEntry> = vec![];
+
+    for event in events {
+        let stack = stacks.entry(event.thread_id).or_default();
+        if event.kind == EventKind::Start {
+            stack.push(Open {
+                name: event.name,
+                span: event.span,
+                start: event.timestamp,
+                nested: 0.0,
+            });
+            continue;
+        }
+
+        let Some(open) = stack.pop() else { continue };
+        let duration = event.timestamp.micros_since(open.start);
+        if let Some(parent) = stack.last_mut() {
+            parent.nested += duration;
+        }
+
+        let recursive = stack
+            .iter()
+            .any(|outer| outer.name == open.name && outer.span == open.span);
+        let index = *indices.entry((open.name, open.span)).or_insert_with(|| {
+            entries.push(ProfileEntry {
+                name: open.name,
+                span: open.span,
+                calls: 0,
+                inclusive: 0.0,
+                exclusive: 0.0,
+            });
+            entries.len() - 1
+        });
+
+        let entry = &mut entries[index];
+        entry.calls += 1;
+        entry.exclusive += (duration - open.nested).max(0.0);
+        if !recursive {
+            entry.inclusive += duration;
+        }
+    }
+
+    entries.sort_by(|a, b| b.exclusive.total_cmp(&a.exclusive));
+    entries
+}
+
 /// A scope that records an event when it is dropped.
 pub struct TimingScope {
     name: &'static str,
@@ -315,3 +403,121 @@ impl WasmTimer {
         self.time_origin + self.perf.now()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, UNIX_EPOCH};
+
+    use super::*;
+
+    /// Creates an event on the given thread at the given microsecond.
+    fn event(kind: EventKind, name: &'static str, thread_id: u64, micros: u64) -> Event {
+        Event {
+            kind,
+            timestamp: Timestamp { inner: UNIX_EPOCH + Duration::from_micros(micros) },
+            name,
+            span: NonZeroU64::new(1),
+            thread_id,
+        }
+    }
+
+    /// Creates the events of a scope from `start` to `end` with `nested` events
+    /// in between.
+    fn scope(
+        name: &'static str,
+        start: u64,
+        nested: impl IntoIterator<Item = Event>,
+        end: u64,
+    ) -> Vec<Event> {
+        let mut events = vec![event(EventKind::Start, name, 1, start)];
+        events.extend(nested);
+        events.push(event(EventKind::End, name, 1, end));
+        events
+    }
+
+    /// Aggregates the events and returns name, calls, inclusive, and exclusive
+    /// time of each entry.
+    fn test(events: Vec<Event>) -> Vec<(&'static str, u64, f64, f64)> {
+        aggregate(&events)
+            .into_iter()
+            .map(|entry| (entry.name, entry.calls, entry.inclusive, entry.exclusive))
+            .collect()
+    }
+
+    #[test]
+    fn test_profile_nested() {
+        let events = scope(
+            "a",
+            0,
+            [scope("b", 2, [], 5), scope("c", 6, scope("b", 7, [], 8), 9)]
+                .into_iter()
+                .flatten(),
+            12,
+        );
+        assert_eq!(
+            test(events),
+            [("a", 1, 12.0, 6.0), ("b", 2, 4.0, 4.0), ("c", 1, 3.0, 2.0)],
+        );
+    }
+
+    #[test]
+    fn test_profile_recursive() {
+        // Only the outermost scope counts towards the inclusive time, but all
+        // scopes count towards the exclusive time.
+        let inner = [scope("f", 2, [], 4), scope("g", 5, [], 6)];
+        let events = scope("f", 0, scope("f", 1, inner.into_iter().flatten(), 8), 10);
+        assert_eq!(test(events), [("f", 3, 10.0, 9.0), ("g", 1, 1.0, 1.0)]);
+    }
+
+    #[test]
+    fn test_profile_same_name_other_span() {
+        // Scopes with the same name but a different span are not recursive.
+        let mut events = scope("f", 0, scope("f", 1, [], 3), 4);
+        events[1].span = NonZeroU64::new(2);
+        events[2].span = NonZeroU64::new(2);
+        let profile = aggregate(&events);
+        assert_eq!(profile.len(), 2);
+        assert_eq!(profile[0].span, NonZeroU64::new(2));
+        assert_eq!((profile[0].inclusive, profile[0].exclusive), (2.0, 2.0));
+        assert_eq!((profile[1].inclusive, profile[1].exclusive), (4.0, 2.0));
+    }
+
+    #[test]
+    fn test_profile_threads() {
+        // Scopes on other threads do not nest in the scopes of this thread.
+        let events = vec![
+            event(EventKind::Start, "a", 1, 0),
+            event(EventKind::Start, "b", 2, 1),
+            event(EventKind::Start, "c", 1, 2),
+            event(EventKind::End, "b", 2, 5),
+            event(EventKind::End, "c", 1, 3),
+            event(EventKind::End, "a", 1, 10),
+        ];
+        assert_eq!(
+            test(events),
+            [("a", 1, 10.0, 9.0), ("b", 1, 4.0, 4.0), ("c", 1, 1.0, 1.0)],
+        );
+    }
+
+    #[test]
+    fn test_profile_scopes() {
+        enable();
+        clear();
+        {
+            let _outer = TimingScope::new("outer");
+            for _ in 0..3 {
+                let _inner = TimingScope::new("inner");
+            }
+        }
+        disable();
+
+        let mut profile = profile();
+        clear();
+        profile.sort_by_key(|entry| entry.name);
+        assert_eq!(profile.len(), 2);
+        assert_eq!((profile[0].name, profile[0].calls), ("inner", 3));
+        assert_eq!((profile[1].name, profile[1].calls), ("outer", 1));
+        assert!(profile[1].inclusive >= profile[0].inclusive);
+        assert!(profile.iter().all(|entry| entry.inclusive >= entry.exclusive));
+    }
+}
@@ -38,7 +38,7 @@ pub use typst_syntax as syntax;
 #[doc(inline)]
 pub use typst_utils as utils;
 
-use std::sync::LazyLock;
+use std::sync::{Arc, LazyLock};
 
 use arrayvec::ArrayVec;
 use comemo::{Track, Tracked};
@@ -48,7 +48,9 @@ use typst_html::HtmlDocument;
 use typst_library::diag::{
     FileError, SourceDiagnostic, SourceResult, Warned, bail, warning,
 };
-use typst_library::engine::{Engine, Route, Sink, Traced};
+use typst_library::engine::{
+    Budget, CancellationToken, Debugger, Engine, Limits, Route, Sink, Traced,
+};
 use typst_library::foundations::{NativeRuleMap, StyleChain, Styles, Value};
 use typst_library::introspection::{ITER_NAMES, Introspector, MAX_ITERS};
 use typst_library::layout::PagedDocument;
@@ -75,6 +77,49 @@ where
     Warned { output, warnings: sink.warnings() }
 }
 
+/// Compile sources into a fully layouted document with the given options.
+///
+/// If the compilation is cancelled, it fails with a single error whose message
+/// is [`CancellationToken::MESSAGE`].
+#[typst_macros::time]
+pub fn compile_with<D>(
+    world: &dyn World,
+    options: &CompileOptions,
+) -> Warned<SourceResult<D>>
+where
+    D: Document,
+{
+    let mut sink = Sink::new();
+    let budget = Budget::new(options.limits, options.cancellation.clone());
+    let traced = Traced::budgeted(budget);
+    let mut output =
+        compile_impl::<D>(world.track(), traced.track(), &mut sink).map_err(deduplicate);
+
+    // Report a cancellation as such instead of at the span where it was
+    // noticed.
+    let cancelled = options
+        .cancellation
+        .as_ref()
+        .is_some_and(CancellationToken::is_cancelled);
+    if cancelled && output.is_err() {
+        let error = SourceDiagnostic::error(Span::detached(), CancellationToken::MESSAGE);
+        output = Err(eco_vec![error]);
+    }
+
+    Warned { output, warnings: sink.warnings() }
+}
+
+/// Options for a compilation.
+#[derive(Debug, Default, Clone)]
+pub struct CompileOptions {
+    /// Limits for the resources the compilation may use. Exceeding a limit
+    /// results in an error pointing to the offending code.
+    pub limits: Limits,
+    /// A token through which the compilation can be cancelled from another
+    /// thread.
+    pub cancellation: Option<CancellationToken>,
+}
+
 /// Compiles sources and returns all values and styles observed at the given
 /// `span` during compilation.
 #[typst_macros::time]
@@ -88,8 +133,25 @@ where
     sink.values()
 }
 
+/// Compiles sources while notifying the given debugger about the evaluation of
+/// Typst code.
+///
+/// Evaluations that are already cached are not observed by the debugger, so
+/// this should typically be called with a fresh cache.
+#[typst_macros::time]
+pub fn debug<D>(world: &dyn World, debugger: Arc<dyn Debugger>) -> Warned<SourceResult<D>>
+where
+    D: Document,
+{
+    let mut sink = Sink::new();
+    let traced = Traced::debugged(debugger);
+    let output =
+        compile_impl::<D>(world.track(), traced.track(), &mut sink).map_err(deduplicate);
+    Warned { output, warnings: sink.warnings() }
+}
+
 /// The internal implementation of `compile` with a bit lower-level interface
-/// that is also used by `trace`.
+/// that is also used by `compile_with`, `trace`, and `debug`.
 fn compile_impl<D: Document>(
     world: Tracked<dyn World + '_>,
     traced: Tracked<Traced>,
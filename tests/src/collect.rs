@@ -4,10 +4,12 @@ use std::ops::Range;
 use std::path::{Path, PathBuf};
 use std::str::FromStr;
 use std::sync::LazyLock;
+use std::time::Duration;
 
 use bitflags::{Flags, bitflags};
 use ecow::{EcoString, eco_format};
 use rustc_hash::{FxHashMap, FxHashSet};
+use typst::engine::Limits;
 use typst::foundations::Bytes;
 use typst_kit::files::FileLoader;
 use typst_pdf::PdfStandard;
@@ -87,6 +89,8 @@ bitflags! {
 pub struct Attrs {
     pub large: bool,
     pub pdf_standard: Option<PdfStandard>,
+    /// The resource limits to compile the test with.
+    pub limits: Limits,
     /// The test stages that are either directly specified or are implied by a
     /// test attribute. If not specified otherwise by the `--stages` flag a
     /// reference output will be generated.
@@ -326,6 +330,7 @@ pub enum NoteKind {
     Error,
     Warning,
     Hint,
+    Trace,
 }
 
 impl FromStr for NoteKind {
@@ -336,6 +341,7 @@ impl FromStr for NoteKind {
             "Error" => Self::Error,
             "Warning" => Self::Warning,
             "Hint" => Self::Hint,
+            "Trace" => Self::Trace,
             _ => return Err(()),
         })
     }
@@ -347,6 +353,7 @@ impl Display for NoteKind {
             Self::Error => "Error",
             Self::Warning => "Warning",
             Self::Hint => "Hint",
+            Self::Trace => "Trace",
         })
     }
 }
@@ -617,6 +624,7 @@ impl<'a> Parser<'a> {
         let mut stages = TestStages::empty();
         let mut flags = AttrFlags::empty();
         let mut pdf_standard = None;
+        let mut limits = Limits::default();
         while !self.s.eat_if("---") {
             let attr_name = self.s.eat_while(is_id_continue);
             let mut attr_params = None;
@@ -644,6 +652,13 @@ impl<'a> Parser<'a> {
                         })
                         .ok();
                 }
+                "limits" => {
+                    let Some(param) = attr_params.take() else {
+                        self.error("expected parameter for `limits`");
+                        continue;
+                    };
+                    limits = self.parse_limits(param);
+                }
                 "html" => self.set_attr(attr_name, &mut stages, TestStages::HTML),
                 "large" => self.set_attr(attr_name, &mut flags, AttrFlags::LARGE),
 
@@ -669,10 +684,41 @@ impl<'a> Parser<'a> {
         Attrs {
             large: flags.contains(AttrFlags::LARGE),
             pdf_standard,
+            limits,
             stages: stages.with_implied(),
         }
     }
 
+    /// Parses the parameters of the `limits` attribute, e.g.
+    /// `limits(eval-steps: 100, pages: 1)`. The timeout is given in
+    /// milliseconds.
+    fn parse_limits(&mut self, param: &str) -> Limits {
+        let mut limits = Limits::default();
+        for pair in param.split(',').map(str::trim).filter(|pair| !pair.is_empty()) {
+            let Some((key, value)) = pair.split_once(':') else {
+                self.error(format!("expected `limit: value`, found `{pair}`"));
+                continue;
+            };
+
+            let key = key.trim();
+            let Ok(value) = value.trim().parse::<u64>() else {
+                self.error(format!("expected number for limit `{key}`"));
+                continue;
+            };
+
+            match key {
+                "eval-steps" => limits.eval_steps = Some(value),
+                "timeout" => limits.timeout = Some(Duration::from_millis(value)),
+                "pages" => limits.pages = Some(value as usize),
+                "str-len" => limits.str_len = Some(value as usize),
+                "array-len" => limits.array_len = Some(value as usize),
+                "plugin-fuel" => limits.plugin_fuel = Some(value),
+                _ => self.error(format!("unknown limit `{key}`")),
+            }
+        }
+        limits
+    }
+
     /// Set an attribute flag and check for duplicates.
     fn set_attr<F: Flags + Copy>(&mut self, attr: &str, flags: &mut F, flag: F) {
         if flags.contains(flag) {
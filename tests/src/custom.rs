@@ -1,10 +1,12 @@
 use std::fmt::Write;
+use std::time::Duration;
 
-use typst::World;
+use typst::engine::{CancellationToken, Limits};
 use typst::foundations::Smart;
 use typst::introspection::{Location, Tag};
 use typst::layout::{Frame, FrameItem, PagedDocument};
 use typst::model::DocumentInfo;
+use typst::{CompileOptions, World};
 
 use crate::collect::Test;
 use crate::world::TestWorld;
@@ -37,6 +39,35 @@ pub fn check(test: &Test, world: &TestWorld, doc: Option<&PagedDocument>) -> Str
             test_eq!(sink, info.author, ["Changed"]);
             test_eq!(sink, info.title.as_deref(), Some("Alternative"));
         }
+        "limits-interrupted-not-reused" => {
+            // The closure call is memoized. Its interrupted result must not be
+            // reused by a compilation without limits.
+            let output = typst::compile::<PagedDocument>(world).output;
+            test_eq!(sink, output.err(), None);
+        }
+        "cancel-from-other-thread" => {
+            let token = CancellationToken::new();
+            let canceller = {
+                let token = token.clone();
+                std::thread::spawn(move || {
+                    std::thread::sleep(Duration::from_millis(50));
+                    token.cancel();
+                })
+            };
+            test_eq!(sink, cancelled(world, token), [CancellationToken::MESSAGE]);
+            canceller.join().unwrap();
+        }
+        "cancel-not-reused" => {
+            // The closure call is memoized. Its cancelled result must neither
+            // be reused by the next compilation with a fresh token nor by one
+            // without a budget.
+            let token = CancellationToken::new();
+            token.cancel();
+            test_eq!(sink, cancelled(world, token), [CancellationToken::MESSAGE]);
+            test_eq!(sink, cancelled(world, CancellationToken::new()), [] as [&str; 0]);
+            let output = typst::compile::<PagedDocument>(world).output;
+            test_eq!(sink, output.err(), None);
+        }
         "tags-grouping" | "tags-textual" => {
             if let Some(doc) = doc {
                 if let Err(message) = check_balanced(doc) {
@@ -51,6 +82,18 @@ pub fn check(test: &Test, world: &TestWorld, doc: Option<&PagedDocument>) -> Str
     sink
 }
 
+/// Compiles the test with a cancellation token and returns the error messages.
+fn cancelled(world: &TestWorld, token: CancellationToken) -> Vec<String> {
+    let options = CompileOptions {
+        limits: Limits::default(),
+        cancellation: Some(token),
+    };
+    match typst::compile_with::<PagedDocument>(world, &options).output {
+        Ok(_) => vec![],
+        Err(errors) => errors.into_iter().map(|error| error.message.into()).collect(),
+    }
+}
+
 /// Extract the document information.
 fn info(doc: Option<&PagedDocument>) -> DocumentInfo {
     doc.map(|doc| doc.info.clone()).unwrap_or_default()
@@ -9,7 +9,7 @@ use regex::{Captures, Regex};
 use rustc_hash::FxHashMap;
 use typst::diag::{SourceDiagnostic, Warned};
 use typst::layout::PagedDocument;
-use typst::{Document, WorldExt};
+use typst::{CompileOptions, Document, WorldExt};
 use typst_html::HtmlDocument;
 use typst_syntax::{FileId, VirtualPath};
 
@@ -182,7 +182,9 @@ impl<'a> Runner<'a> {
 
     /// Compile a document with the specified target.
     fn compile<D: Document>(&mut self, target: TestTarget) -> Option<D> {
-        let Warned { output, warnings } = typst::compile::<D>(&self.world);
+        let options =
+            CompileOptions { limits: self.test.attrs.limits, cancellation: None };
+        let Warned { output, warnings } = typst::compile_with::<D>(&self.world, &options);
         for warning in &warnings {
             self.check_diagnostic(NoteKind::Warning, warning, target);
         }
@@ -457,6 +459,24 @@ impl<'a> Runner<'a> {
             let range = self.world.range(span);
             self.validate_note(NoteKind::Hint, span.id(), range, &hint.v, stage);
         }
+
+        // Check tracepoints, but only for tests that annotate them.
+        if self.test.notes.iter().any(|note| note.kind == NoteKind::Trace) {
+            for point in &diag.trace {
+                let message = match point.v.args() {
+                    Some(args) => format!("{} with arguments {args}", point.v),
+                    None => point.v.to_string(),
+                };
+                let range = self.world.range(point.span);
+                self.validate_note(
+                    NoteKind::Trace,
+                    point.span.id(),
+                    range,
+                    &message,
+                    stage,
+                );
+            }
+        }
     }
 
     /// Try to find a matching note for the given `kind`, `range`, and
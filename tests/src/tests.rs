@@ -37,6 +37,9 @@ const SYNTAX_PATH: &str = "tests/store/syntax";
 /// The directory where the reference output is stored.
 const REF_PATH: &str = "tests/ref";
 
+/// The directory where additional fonts for the tests are stored.
+const FONTS_PATH: &str = "tests/fonts";
+
 /// The file where the skipped tests are stored.
 const SKIP_PATH: &str = "tests/skip.txt";
 
@@ -19,6 +19,8 @@ use typst::{Feature, Library, LibraryExt, World};
 use typst_kit::files::{FileLoader, FileStore};
 use typst_syntax::{Lines, VirtualRoot};
 
+use crate::FONTS_PATH;
+
 /// A world that provides access to the tests environment.
 #[derive(Clone)]
 pub struct TestWorld {
@@ -100,7 +102,9 @@ impl Default for TestBase {
     fn default() -> Self {
         let fonts: Vec<_> = typst_assets::fonts()
             .chain(typst_dev_assets::fonts())
-            .flat_map(|data| Font::iter(Bytes::new(data)))
+            .map(Bytes::new)
+            .chain(test_fonts())
+            .flat_map(Font::iter)
             .collect();
 
         Self {
@@ -112,6 +116,20 @@ impl Default for TestBase {
     }
 }
 
+/// Loads the fonts from the [`FONTS_PATH`], sorted by file name.
+fn test_fonts() -> Vec<Bytes> {
+    let mut paths: Vec<_> = fs::read_dir(FONTS_PATH)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|ext| ext != "txt"))
+        .collect();
+    paths.sort();
+    paths
+        .into_iter()
+        .map(|path| Bytes::new(fs::read(path).unwrap()))
+        .collect()
+}
+
 /// Loads files from the test suite, Typst assets, and the test packages.
 /// Excludes the main source file, which is directly handled by the `World`.
 pub struct TestFiles;
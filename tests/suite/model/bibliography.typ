@@ -180,3 +180,44 @@ hi:
   title: none,
   full: true,
 )
+
+--- bibliography-multiple paged ---
+// Citations are numbered independently for each bibliography. A citation
+// belongs to the first bibliography that covers it and lists its work.
+#show bibliography: none
+#show text: it => { metadata(it.text); it }
+
+#place(hide[
+  @netwok
+
+  #box() <middle>
+
+  @arrgh
+
+  @netwok
+
+  @quark
+])
+
+#bibliography("/assets/bib/works.bib", key-prefix: "net")
+#bibliography("/assets/bib/works.bib", target: selector(cite).after(<middle>))
+
+#context test(query(metadata).map(m => m.value).join(), "[1][1][1][2]")
+
+--- bibliography-target-uncovered paged ---
+#show bibliography: none
+// Error: 1-7 citation is not covered by any bibliography
+// Hint: 1-7 the `target` of each bibliography excludes this citation
+@arrgh
+#box() <middle>
+#bibliography("/assets/bib/works.bib", target: selector(cite).after(<middle>))
+
+--- bibliography-key-prefix-filtered paged ---
+#show bibliography: none
+// Error: 1-7 key `arrgh` does not exist in the bibliography
+@arrgh
+#bibliography("/assets/bib/works.bib", key-prefix: "net")
+
+--- bibliography-types-unknown paged ---
+// Error: 47-54 unknown work type `novel`
+#bibliography("/assets/bib/works.bib", types: "novel")
@@ -0,0 +1,64 @@
+--- endnote-counter paged ---
+#show endnote: none
+#endnote[A]
+#endnote[B]
+#context test(counter(endnote).get(), (2,))
+
+--- endnote-ref-counter paged ---
+// References to other endnotes do not step the counter.
+#show endnote: none
+#endnote[A] <a>
+#endnote(<a>)
+#context test(counter(endnote).get(), (1,))
+
+--- endnotes-realize-lists paged ---
+// Each list shows the notes since the previous one. Numbering continues
+// across a list without reset and restarts after one with reset.
+#set endnote(numbering: n => metadata(n))
+#show endnote.entry: it => { metadata("entry"); it }
+
+#place(hide[
+  A#endnote[#metadata("a")]
+  B#endnote[#metadata("b")] <b>
+  C#endnote(<b>)
+  #endnotes(title: none, reset: false)
+  D#endnote[#metadata("d")]
+  #endnotes(title: none)
+  E#endnote[#metadata("e")]
+  #endnotes(title: none)
+])
+
+#context test(query(metadata).map(m => m.value), (
+  1, 2, 2,
+  "entry", 1, "a", "entry", 2, "b",
+  3,
+  "entry", 3, "d",
+  1,
+  "entry", 1, "e",
+))
+
+--- endnotes-realize-back-links paged ---
+// The numbering runs at the note that a number links to, so it can record
+// the location. The entries link back to their notes and references to a note
+// link to its declaration.
+#set endnote(numbering: n => metadata(here()))
+
+#place(hide[
+  A#endnote[A] <a>
+  B#endnote(<a>)
+  C#endnote[C]
+  #endnotes()
+])
+
+#context {
+  let (a, _, c) = query(endnote).map(note => note.location())
+  test(query(metadata).map(m => m.value), (a, a, c, a, c))
+}
+
+--- endnote-missing-body paged ---
+// Error: 2-11 missing argument: body
+#endnote()
+
+--- endnotes-reset-wrong-type paged ---
+// Error: 18-22 expected boolean, found string
+#endnotes(reset: "no")
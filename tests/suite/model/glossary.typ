@@ -0,0 +1,67 @@
+// The uses and the glossary are hidden in these tests, but their realized
+// output is still introspectable through the metadata left behind by the terms
+// and page numbers.
+
+--- glossary-realize-first-use paged ---
+#let t(s) = [#metadata(s)#s]
+#glossary.entry(t("PDF"), long: t("Portable Document Format")) <pdf>
+#glossary.entry(t("API")) <api>
+
+#place(hide[
+  @pdf @api @pdf @api
+  #counter(ref.where(target: <pdf>)).update(0)
+  @pdf @pdf[the format]
+])
+
+#context test(query(metadata).map(m => m.value), (
+  "Portable Document Format", "PDF",
+  "API", "PDF", "API",
+  "Portable Document Format", "PDF",
+))
+
+--- glossary-realize-items paged ---
+#let t(s) = [#metadata(s)#s]
+#set page(numbering: n => [#metadata(n)#n], footer: none)
+#glossary.entry(t("XML"), long: t("Extensible Markup Language")) <xml>
+#glossary.entry(t("CSS"), description: t("Style sheets.")) <css>
+#glossary.entry(t("API")) <api>
+
+#place(hide[@xml @css @css])
+#place(hide(glossary(title: none)))
+
+#context test(query(metadata).map(m => m.value), (
+  "Extensible Markup Language", "XML", "CSS", "CSS",
+  "CSS", "Style sheets.", 1,
+  "XML", "Extensible Markup Language", 1,
+))
+
+--- glossary-realize-back-links paged ---
+// Each page links back to the first use of the term on it. The numbering
+// function runs at the linked use, so it can record its location.
+#set page(numbering: n => metadata(here()), footer: none)
+#glossary.entry("PDF") <pdf>
+#glossary.entry("API") <api>
+
+#place(hide[@api @pdf @api @pdf])
+#place(hide(glossary()))
+#place(hide(glossary()))
+
+#context {
+  let uses(label) = query(ref.where(target: label)).map(r => r.location())
+  let first = (uses(<api>).first(), uses(<pdf>).first())
+  test(query(metadata).map(m => m.value), first + first)
+}
+
+--- glossary-entry-invisible paged ---
+// The entries produce no visible output.
+#glossary.entry("HTML", long: [HyperText Markup Language]) <html>
+#context test(measure[#glossary.entry[XML]].width, 0pt)
+
+--- glossary-unused paged ---
+// Unused entries are not listed, so the glossary is empty.
+#glossary.entry("CSS", long: [Cascading Style Sheets]) <css>
+#glossary(title: none)
+
+--- glossary-entry-missing-short paged ---
+// Error: 2-18 missing argument: short
+#glossary.entry()
@@ -0,0 +1,127 @@
+// The index is hidden in most of these tests, but its realized output is still
+// introspectable. Terms, page numbers and group letters leave metadata behind,
+// which the tests compare against.
+
+--- index-realize-grouped paged ---
+#let t(s) = [#metadata(s)#s]
+#set page(numbering: n => [#metadata(n)#n], footer: none)
+#show strong: it => {
+  metadata(if it.body.has("text") { "group " + it.body.text } else { "main" })
+  it
+}
+
+#index.entry(t("banana"))
+#index.entry(t("apple"), main: true)
+#index.entry(t("avocado"), sub: t("ripe"))
+#index.entry(t("apple"))
+#index.entry(t("cherry"), see: t("banana"))
+#index.entry(t("1984"))
+
+#place(hide(index(title: none)))
+#context test(query(metadata).map(m => m.value), (
+  "1984", 1,
+  "group A", "apple", "main", 1, "avocado", "ripe", 1,
+  "group B", "banana", 1,
+  "group C", "cherry", "banana",
+))
+
+--- index-realize-ungrouped paged ---
+#let t(s) = [#metadata(s)#s]
+#set page(numbering: n => [#metadata(n)#n], footer: none)
+#show strong: it => { metadata("strong"); it }
+
+#index.entry(t("cherry"))
+#index.entry(t("banana"), see-also: t("cherry"))
+#index.entry(t("banana"))
+#index.entry(t("apple"))
+
+#place(hide(index(title: none, grouped: false)))
+#context test(query(metadata).map(m => m.value), (
+  "apple", 1, "banana", 1, "cherry", "cherry", 1,
+))
+
+--- index-realize-sub-entries paged ---
+#let t(s) = [#metadata(s)#s]
+#set page(numbering: n => [#metadata(n)#n], footer: none)
+
+#index.entry(t("page"), sub: t("size"))
+#index.entry(t("page"), sub: t("margins"))
+#index.entry(t("page"), see-also: t("paper"))
+#index.entry(t("paper"), sub: t("size"))
+
+#place(hide(index(title: none, grouped: false)))
+#context test(query(metadata).map(m => m.value), (
+  "page", "paper", "margins", 1, "size", 1,
+  "paper", "size", 1,
+))
+
+--- index-realize-sort-lang paged ---
+#let t(s) = [#metadata(s)#s]
+#set page(numbering: n => [#metadata(n)#n], footer: none)
+#set text(lang: "sv")
+#show strong: it => { metadata(it.body.text); it }
+
+#index.entry(t("zon"))
+#index.entry(t("öl"))
+#index.entry(t("ost"))
+#index.entry(key: "Gogh", t("van Gogh"))
+
+#place(hide(index(title: none)))
+#context test(query(metadata).map(m => m.value), (
+  "G", "van Gogh", 1,
+  "O", "ost", 1,
+  "Z", "zon", 1,
+  "Ö", "öl", 1,
+))
+
+--- index-realize-page-ranges-main paged ---
+#let t(s) = [#metadata(s)#s]
+#set page(numbering: n => [#metadata(n)#n], footer: none)
+#show strong: it => { metadata("main"); it }
+
+#index.entry(t("fonts"))
+#pagebreak()
+#index.entry(t("fonts"))
+#pagebreak()
+#index.entry(t("fonts"), main: true)
+#index.entry(t("fonts"))
+#pagebreak()
+#index.entry(t("fonts"), main: true)
+#pagebreak()
+#index.entry(t("fonts"))
+
+#place(hide(index(title: none, grouped: false)))
+#context test(query(metadata).map(m => m.value), (
+  "fonts", 1, 2, "main", 3, 4, 5,
+))
+
+--- index-entry-invisible paged ---
+// The entries produce no visible output.
+#index.entry[A]
+#index.entry("B", sub: "C")
+#context test(measure[#index.entry[D]].width, 0pt)
+
+--- index-entry-missing-term paged ---
+// Error: 2-15 missing argument: term
+#index.entry()
+
+--- index-grouped-wrong-type paged ---
+// Error: 17-21 expected boolean, found string
+#index(grouped: "no")
+
+--- index-page-ranges-main paged ---
+// A change between main and other references starts a new page range.
+#set page(width: 120pt, height: auto, numbering: "1", footer: none)
+#index.entry[Kerning]
+#index.entry[Ligatures]
+#pagebreak()
+#index.entry[Kerning]
+#pagebreak()
+#index.entry(main: true)[Kerning]
+#index.entry(main: true)[Ligatures]
+#pagebreak()
+#index.entry(main: true)[Kerning]
+#index.entry[Ligatures]
+#pagebreak()
+#index.entry[Kerning]
+#index(title: none, grouped: false)
@@ -206,3 +206,22 @@
 // Error: 6-7 unclosed delimiter
 // Error: 1:7-2:1 unclosed string
 #func("]
+
+--- call-trace-nested paged ---
+// Error: 1:17-1:25 panicked with: 3
+// Trace: 2:18-2:30 error occurred in this call of function `inner` with arguments (x: 3)
+// Trace: 3:17-3:30 error occurred in this call of function `middle` with arguments (y: 2)
+// Trace: 4:2-4:10 error occurred in this call of function `outer` with arguments (z: 1)
+#let inner(x) = panic(x)
+#let middle(y) = inner(y + 1)
+#let outer(z) = middle(z * 2)
+#outer(1)
+
+--- call-trace-recursion paged ---
+// The recursive calls share a span and are only traced once, with the
+// arguments of the innermost call. Long arguments are cut off.
+// Error: 1:32-1:40 panicked with: "xxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxx"
+// Trace: 1:50-1:65 error occurred in this call of function `count` with arguments (n: 0, s: "xxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxx..)
+// Trace: 2:2-2:64 error occurred in this call of function `count` with arguments (n: 3, s: "xxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxx..)
+#let count(n, s) = if n == 0 { panic(s) } else { count(n - 1, s) }
+#count(3, "xxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxx")
@@ -0,0 +1,52 @@
+// Test resource limits and cancellation.
+
+--- limits-eval-steps paged limits(eval-steps: 100) ---
+// Error: 2-25 maximum number of evaluation steps exceeded
+// Hint: 2-25 the document performs too much computation
+#for i in range(1000) {}
+
+--- limits-timeout paged limits(timeout: 0) ---
+// Error: 2-27 maximum compilation time exceeded
+// Hint: 2-27 the document takes too long to compile
+#for i in range(100000) {}
+
+--- limits-pages paged limits(pages: 1) ---
+// Error: 23-31 maximum number of pages exceeded
+// Hint: 23-31 the document may not have more than 1 pages
+#block[A]#pagebreak()#block[B]
+
+--- limits-str-len paged limits(str-len: 10) ---
+// Error: 10-19 maximum string length exceeded
+#let s = "a" * 100
+
+--- limits-array-len paged limits(array-len: 10) ---
+// Error: 10-20 maximum array length exceeded
+#let a = range(100)
+
+--- limits-plugin-fuel paged limits(plugin-fuel: 1) ---
+#let p = plugin("/assets/plugins/hello.wasm")
+// Error: 10-19 plugin function `hello` ran out of fuel
+#let b = p.hello()
+
+--- limits-within paged limits(eval-steps: 10000, pages: 1, str-len: 1000, array-len: 100) ---
+#let a = range(100).map(str).join()
+#test(a.len(), 190)
+
+--- limits-interrupted-not-reused paged limits(eval-steps: 100) ---
+// The custom check compiles this test again without limits.
+// Error: 15-35 maximum number of evaluation steps exceeded
+// Hint: 15-35 the document performs too much computation
+#let f(n) = { for i in range(n) {}; n }
+#let x = f(1000)
+
+--- cancel-not-reused paged ---
+// The custom check compiles this test again with cancellation tokens.
+#let f(n) = { for i in range(n) {}; n }
+#let x = f(1000)
+
+--- cancel-from-other-thread paged limits(eval-steps: 10000) ---
+// The custom check compiles this test again without limits and cancels it
+// from another thread.
+// Error: 27-52 maximum number of evaluation steps exceeded
+// Hint: 27-52 the document performs too much computation
+#for i in range(100000) { for j in range(100000) {} }
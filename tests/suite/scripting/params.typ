@@ -67,3 +67,93 @@
 --- issue-1351-parameter-dictionary paged ---
 // Error: 17-22 expected pattern, found string
 #let foo((test: "bar")) = {}
+
+--- params-typed paged ---
+// Type annotations on parameters and return values.
+#let f(x as int, y: 2 as (int, float), ..rest) as int = x + int(y)
+#test(f(1), 3)
+#test(f(1, y: 2.5), 3)
+#test(f(1, 2, 3), 3)
+
+#let g(x as (str, none)) = x
+#test(g(none), none)
+#test(g("a"), "a")
+
+#let h((a, b) as array, c: auto as auto) = a + b
+#test(h((1, 2)), 3)
+#test(((x as content) => x)[A], [A])
+
+--- params-typed-coercion paged ---
+// Arguments are coerced like those of built-in functions.
+#let f(x as float) = x
+#test(f(1), 1.0)
+#test(type(f(1)), float)
+
+#let g(x as relative) = x
+#test(g(50%), 50% + 0pt)
+#test(type(g(50%)), relative)
+#test(g(1pt), 0% + 1pt)
+#test(type(g(1pt)), relative)
+
+#let h(x as content) = x
+#test(h("a"), [a])
+#test(type(h(sym.arrow)), content)
+
+--- params-typed-coercion-union paged ---
+// A value that matches one of the types exactly is kept as is.
+#let f(x as (int, float)) = x
+#test(type(f(1)), int)
+#test(type(f(1.5)), float)
+
+--- params-typed-coercion-default-and-return paged ---
+#let f(x: 1 as float) = x
+#test(type(f()), float)
+
+#let g() as content = "a"
+#test(g(), [a])
+
+--- params-typed-positional-mismatch paged ---
+// Error: 2:4-2:8 expected integer, found string
+#let f(x as int) = x
+#f("hi")
+
+--- params-typed-named-mismatch paged ---
+// Error: 2:10-2:13 expected integer or float, found string
+#let f(x, y: 1 as (int, float)) = x
+#f(1, y: "a")
+
+--- params-typed-default-mismatch paged ---
+// Error: 11-14 expected integer, found string
+// Hint: 11-14 the default value must match the parameter's type
+#let f(x: "a" as int) = x
+
+--- params-typed-return-mismatch paged ---
+// Error: 19-22 expected integer, found string
+#let f() as int = "a"
+#f()
+
+--- params-typed-explicit-return-mismatch paged ---
+// Error: 3:3-3:11 expected content, found integer
+#let f(x) as content = {
+  if x { return [A] }
+  return 1
+}
+#test(f(true), [A])
+#f(false)
+
+--- params-typed-invalid paged ---
+// Error: 13-14 expected type, none, auto, or array, found integer
+#let f(x as 1) = x
+
+--- params-typed-empty paged ---
+// Error: 13-15 type annotation must not be empty
+#let f(x as ()) = x
+
+--- params-typed-element paged ---
+// Error: 13-20 expected type, none, auto, or array, found element function
+// Hint: 13-20 use `content` to accept any content
+#let f(x as heading) = x
+
+--- params-typed-sink-invalid paged ---
+// Error: 8-21 argument sinks cannot have a type annotation
+#let f(..rest as int) = rest
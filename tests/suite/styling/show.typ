@@ -271,6 +271,14 @@ I am *strong*, I am _emphasized_, and I am #[special<special>].
 = Hello
 *strong*
 
+--- show-rule-trace paged ---
+// Error: 1:17-1:32 panicked with: "failed"
+// Trace: 2:2-2:20 error occurred in this show rule
+// Trace: 3:1-3:8 error occurred while applying show rule to this heading
+#let fail(it) = panic("failed")
+#show heading: fail
+= Hello
+
 --- issue-5690-oom-par-box paged ---
 // Error: 3:6-5:1 maximum grouping depth exceeded
 #show par: box
@@ -0,0 +1,60 @@
+--- dropcap-indent paged ---
+// The spanned lines are indented by the width of the enlarged letter.
+#context {
+  let plain = measure[Some text]
+  let capped = measure[#dropcap[S]ome text]
+  assert(capped.width > plain.width)
+}
+
+--- dropcap-short-height paged ---
+// A paragraph with fewer lines than the drop cap spans grows to contain it.
+#context {
+  let plain = measure[Some text]
+  let capped = measure[#dropcap[S]ome text]
+  assert(capped.height > plain.height)
+  assert(capped.height > 2 * plain.height)
+}
+
+--- dropcap-lines-two paged ---
+#set page(width: 120pt)
+#dropcap(lines: 2)[T]his paragraph starts with a drop cap that spans two lines.
+
+--- dropcap-lines-three paged ---
+#set page(width: 120pt)
+#dropcap(lines: 3)[T]his paragraph starts with a drop cap that spans three lines
+of text, which are all indented.
+
+--- dropcap-gap-custom paged ---
+#set page(width: 120pt)
+#dropcap(lines: 2, gap: 8pt)[W]ith a larger gap between the initial and the text.
+
+--- dropcap-short paged ---
+// The drop cap doesn't overlap the next paragraph.
+#set page(width: 120pt)
+#dropcap[S]hort.
+
+The next paragraph.
+
+--- dropcap-rtl paged ---
+#set page(width: 120pt)
+#set text(lang: "ar", font: ("Libertinus Serif", "Noto Sans Arabic"))
+#dropcap(lines: 2)[ل]وريم إيبسوم هو ببساطة نص شكلي يستخدم في صناعة الطباعة والتنضيد.
+
+--- dropcap-lines paged ---
+// A drop cap spanning more lines is larger.
+#context {
+  let two = measure[#dropcap(lines: 2)[A]b]
+  let three = measure[#dropcap(lines: 3)[A]b]
+  assert(two.width < three.width)
+}
+
+--- dropcap-gap paged ---
+#context {
+  let narrow = measure[#dropcap(gap: 0pt)[A]b]
+  let wide = measure[#dropcap(gap: 10pt)[A]b]
+  assert(wide.width > narrow.width)
+}
+
+--- dropcap-lines-zero paged ---
+// Error: 17-18 number must be positive
+#dropcap(lines: 0)[A]
@@ -77,6 +77,28 @@ I
 #let var = text(font: ("list-of", "nonexistent-fonts"))[don't]
 #var
 
+--- text-fallback-fonts-reflection paged ---
+#set text(fallback-fonts: (hani: "Noto Serif CJK SC", "zh-TW": ("A", "B")))
+#set text(fallback-fonts: (ja: "Noto Serif CJK JP", hani: "Noto Sans CJK SC"))
+#context test(text.fallback-fonts, (
+  ja: "noto serif cjk jp",
+  hani: "noto sans cjk sc",
+  "zh-TW": ("a", "b"),
+))
+
+--- text-fallback-fonts-bad-key paged ---
+// Error: 27-43 invalid fallback font key `latin-x` (expected a script code like `hani` or a language code like `ja` or `zh-TW`)
+#set text(fallback-fonts: ("latin-x": "A"))
+
+--- text-fallback-fonts-warning paged ---
+#set text(
+  font: "Libertinus Serif",
+  fallback-fonts: (hani: "Noto Serif CJK SC"),
+  warn-fallback: true,
+)
+// Warning: 34-36 used fallback font "Noto Serif CJK SC" for "你好"
+#context { let _ = measure[Hello 你好] }
+
 --- issue-5499-text-fill-in-clip-block paged ---
 
 #let t = tiling(
@@ -208,3 +230,20 @@ Hello
 --- empty-text-font-array paged ---
 // Error: 17-19 font fallback list must not be empty
 #set text(font: ())
+
+--- text-font-woff paged ---
+// Test fonts loaded from a WOFF file (regular) and a WOFF2 file (italic).
+// Their glyphs are decoded from the compressed and transformed outlines.
+#let depth(style) = {
+  let body = text(
+    font: "Source Code Pro",
+    style: style,
+    fallback: false,
+    top-edge: "baseline",
+    bottom-edge: "bounds",
+  )[g]
+  calc.round(measure(body).height.pt(), digits: 2)
+}
+
+#context test(depth("normal"), 2.24)
+#context test(depth("italic"), 2.17)